@@ -1,6 +1,6 @@
 use alpaca_api_client::{
-    market_data::stocks::{HistoricalBarsQuery, LatestBarsQuery},
     TimeFrame,
+    market_data::stocks::{Adjustment, HistoricalBarsQuery, LatestBarsQuery, StockFeed},
 };
 
 fn main() {
@@ -12,14 +12,15 @@ fn historical_bar_query() {
     HistoricalBarsQuery::new(vec!["AAPL"], TimeFrame::OneDay)
         .start("2022-02-01")
         .end("2022-02-10")
-        .feed("iex")
+        .feed(StockFeed::Iex)
+        .adjustment(Adjustment::All)
         .send()
         .unwrap();
 }
 
 fn latest_bar_query() {
     LatestBarsQuery::new(vec!["AAPL", "TSLA"])
-        .feed("iex")
+        .feed(StockFeed::Iex)
         .send()
         .unwrap();
 }
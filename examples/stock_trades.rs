@@ -1,4 +1,4 @@
-use alpaca_api_client::market_data::stocks::{HistoricalTradesQuery, LatestTradesQuery};
+use alpaca_api_client::market_data::stocks::{HistoricalTradesQuery, LatestTradesQuery, StockFeed};
 
 fn main() {
     historical_trade_query();
@@ -7,7 +7,7 @@ fn main() {
 
 fn historical_trade_query() {
     HistoricalTradesQuery::new(vec!["AAPL", "TSLA", "PLTR"])
-        .feed("sip")
+        .feed(StockFeed::Sip)
         .sort_desc()
         .currency("USD")
         .limit(10)
@@ -17,7 +17,7 @@ fn historical_trade_query() {
 
 fn latest_trade_query() {
     LatestTradesQuery::new(vec!["AAPL", "TSLA"])
-        .feed("iex")
+        .feed(StockFeed::Iex)
         .send()
         .unwrap();
 }
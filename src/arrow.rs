@@ -0,0 +1,408 @@
+use std::sync::Arc;
+
+use arrow_array::{
+    ArrayRef, Float64Array, RecordBatch, TimestampSecondArray,
+    builder::{
+        Float32Builder, Float64Builder, Int32Builder, ListBuilder, StringBuilder,
+        TimestampNanosecondBuilder,
+    },
+};
+use arrow_schema::{ArrowError, DataType, Field, Schema, TimeUnit};
+
+use crate::{
+    market_data::{
+        options::OptionSnapshots,
+        stocks::{HistoricalBars, HistoricalQuotes, HistoricalTrades},
+    },
+    time::parse_rfc3339_nanos,
+    trading::portfolio::PortfolioHistory,
+};
+
+/// Conversion of a market data response into a columnar Arrow `RecordBatch`.
+///
+/// Multi-symbol responses are flattened into long format with a leading `symbol` column,
+/// ordered by symbol and then by the order the API returned the rows in.
+/// Timestamps become `Timestamp(Nanosecond, "UTC")` columns.
+///
+/// # Example
+/// ```no_run
+/// use alpaca_api_client::{arrow::ToRecordBatch, market_data::stocks::HistoricalBarsQuery, TimeFrame};
+///
+/// let bars = HistoricalBarsQuery::new(vec!["AAPL", "TSLA"], TimeFrame::OneDay)
+///     .start("2024-01-01")
+///     .send()
+///     .unwrap();
+/// let batch = bars.to_record_batch().unwrap();
+/// println!("{} rows", batch.num_rows());
+/// ```
+pub trait ToRecordBatch {
+    fn to_record_batch(&self) -> Result<RecordBatch, ArrowError>;
+}
+
+impl ToRecordBatch for HistoricalBars {
+    fn to_record_batch(&self) -> Result<RecordBatch, ArrowError> {
+        let mut symbol = StringBuilder::new();
+        let mut timestamp = TimestampNanosecondBuilder::new().with_timezone("UTC");
+        let mut open = Float32Builder::new();
+        let mut high = Float32Builder::new();
+        let mut low = Float32Builder::new();
+        let mut close = Float32Builder::new();
+        let mut volume = Float32Builder::new();
+        let mut trade_count = Int32Builder::new();
+        let mut vwap = Float32Builder::new();
+
+        for (sym, bars) in sorted_by_symbol(self) {
+            for bar in bars {
+                symbol.append_value(sym);
+                timestamp.append_value(parse_timestamp(&bar.t)?);
+                open.append_value(bar.o);
+                high.append_value(bar.h);
+                low.append_value(bar.l);
+                close.append_value(bar.c);
+                volume.append_value(bar.v);
+                trade_count.append_value(bar.n);
+                vwap.append_value(bar.vw);
+            }
+        }
+
+        let schema = Schema::new(vec![
+            Field::new("symbol", DataType::Utf8, false),
+            timestamp_field("timestamp", false),
+            Field::new("open", DataType::Float32, false),
+            Field::new("high", DataType::Float32, false),
+            Field::new("low", DataType::Float32, false),
+            Field::new("close", DataType::Float32, false),
+            Field::new("volume", DataType::Float32, false),
+            Field::new("trade_count", DataType::Int32, false),
+            Field::new("vwap", DataType::Float32, false),
+        ]);
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(symbol.finish()),
+            Arc::new(timestamp.finish()),
+            Arc::new(open.finish()),
+            Arc::new(high.finish()),
+            Arc::new(low.finish()),
+            Arc::new(close.finish()),
+            Arc::new(volume.finish()),
+            Arc::new(trade_count.finish()),
+            Arc::new(vwap.finish()),
+        ];
+        RecordBatch::try_new(Arc::new(schema), columns)
+    }
+}
+
+impl ToRecordBatch for HistoricalTrades {
+    fn to_record_batch(&self) -> Result<RecordBatch, ArrowError> {
+        let mut symbol = StringBuilder::new();
+        let mut timestamp = TimestampNanosecondBuilder::new().with_timezone("UTC");
+        let mut exchange = StringBuilder::new();
+        let mut price = Float64Builder::new();
+        let mut size = Int32Builder::new();
+        let mut conditions = ListBuilder::new(StringBuilder::new());
+        let mut id = Int32Builder::new();
+        let mut tape = StringBuilder::new();
+
+        for (sym, trades) in sorted_by_symbol(self) {
+            for trade in trades {
+                symbol.append_value(sym);
+                timestamp.append_value(parse_timestamp(&trade.t)?);
+                exchange.append_value(&trade.x);
+                price.append_value(trade.p);
+                size.append_value(trade.s);
+                conditions.append_value(trade.c.iter().map(Some));
+                id.append_value(trade.i);
+                tape.append_value(&trade.z);
+            }
+        }
+
+        let schema = Schema::new(vec![
+            Field::new("symbol", DataType::Utf8, false),
+            timestamp_field("timestamp", false),
+            Field::new("exchange", DataType::Utf8, false),
+            Field::new("price", DataType::Float64, false),
+            Field::new("size", DataType::Int32, false),
+            conditions_field(),
+            Field::new("id", DataType::Int32, false),
+            Field::new("tape", DataType::Utf8, false),
+        ]);
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(symbol.finish()),
+            Arc::new(timestamp.finish()),
+            Arc::new(exchange.finish()),
+            Arc::new(price.finish()),
+            Arc::new(size.finish()),
+            Arc::new(conditions.finish()),
+            Arc::new(id.finish()),
+            Arc::new(tape.finish()),
+        ];
+        RecordBatch::try_new(Arc::new(schema), columns)
+    }
+}
+
+impl ToRecordBatch for HistoricalQuotes {
+    fn to_record_batch(&self) -> Result<RecordBatch, ArrowError> {
+        let mut symbol = StringBuilder::new();
+        let mut timestamp = TimestampNanosecondBuilder::new().with_timezone("UTC");
+        let mut ask_exchange = StringBuilder::new();
+        let mut ask_price = Float32Builder::new();
+        let mut ask_size = Int32Builder::new();
+        let mut bid_exchange = StringBuilder::new();
+        let mut bid_price = Float32Builder::new();
+        let mut bid_size = Int32Builder::new();
+        let mut conditions = ListBuilder::new(StringBuilder::new());
+        let mut tape = StringBuilder::new();
+
+        for (sym, quotes) in sorted_by_symbol(self) {
+            for quote in quotes {
+                symbol.append_value(sym);
+                timestamp.append_value(parse_timestamp(&quote.t)?);
+                ask_exchange.append_value(&quote.ax);
+                ask_price.append_value(quote.ap);
+                ask_size.append_value(quote.r#as);
+                bid_exchange.append_value(&quote.bx);
+                bid_price.append_value(quote.bp);
+                bid_size.append_value(quote.bs);
+                conditions.append_value(quote.c.iter().map(Some));
+                tape.append_value(&quote.z);
+            }
+        }
+
+        let schema = Schema::new(vec![
+            Field::new("symbol", DataType::Utf8, false),
+            timestamp_field("timestamp", false),
+            Field::new("ask_exchange", DataType::Utf8, false),
+            Field::new("ask_price", DataType::Float32, false),
+            Field::new("ask_size", DataType::Int32, false),
+            Field::new("bid_exchange", DataType::Utf8, false),
+            Field::new("bid_price", DataType::Float32, false),
+            Field::new("bid_size", DataType::Int32, false),
+            conditions_field(),
+            Field::new("tape", DataType::Utf8, false),
+        ]);
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(symbol.finish()),
+            Arc::new(timestamp.finish()),
+            Arc::new(ask_exchange.finish()),
+            Arc::new(ask_price.finish()),
+            Arc::new(ask_size.finish()),
+            Arc::new(bid_exchange.finish()),
+            Arc::new(bid_price.finish()),
+            Arc::new(bid_size.finish()),
+            Arc::new(conditions.finish()),
+            Arc::new(tape.finish()),
+        ];
+        RecordBatch::try_new(Arc::new(schema), columns)
+    }
+}
+
+impl ToRecordBatch for OptionSnapshots {
+    fn to_record_batch(&self) -> Result<RecordBatch, ArrowError> {
+        let mut symbol = StringBuilder::new();
+        let mut delta = Float64Builder::new();
+        let mut gamma = Float64Builder::new();
+        let mut rho = Float64Builder::new();
+        let mut theta = Float64Builder::new();
+        let mut vega = Float64Builder::new();
+        let mut implied_volatility = Float64Builder::new();
+        let mut quote_timestamp = TimestampNanosecondBuilder::new().with_timezone("UTC");
+        let mut bid_price = Float32Builder::new();
+        let mut bid_size = Int32Builder::new();
+        let mut ask_price = Float32Builder::new();
+        let mut ask_size = Int32Builder::new();
+        let mut trade_timestamp = TimestampNanosecondBuilder::new().with_timezone("UTC");
+        let mut trade_price = Float64Builder::new();
+        let mut trade_size = Int32Builder::new();
+
+        for (sym, snapshot) in sorted_by_symbol(self) {
+            symbol.append_value(sym);
+
+            let greeks = snapshot.greeks.as_ref();
+            delta.append_option(greeks.map(|g| g.delta));
+            gamma.append_option(greeks.map(|g| g.gamma));
+            rho.append_option(greeks.map(|g| g.rho));
+            theta.append_option(greeks.map(|g| g.theta));
+            vega.append_option(greeks.map(|g| g.vega));
+            implied_volatility.append_option(snapshot.implied_volatility);
+
+            match &snapshot.latest_quote {
+                Some(quote) => {
+                    quote_timestamp.append_value(parse_timestamp(&quote.t)?);
+                    bid_price.append_value(quote.bp);
+                    bid_size.append_value(quote.bs);
+                    ask_price.append_value(quote.ap);
+                    ask_size.append_value(quote.r#as);
+                }
+                None => {
+                    quote_timestamp.append_null();
+                    bid_price.append_null();
+                    bid_size.append_null();
+                    ask_price.append_null();
+                    ask_size.append_null();
+                }
+            }
+
+            match &snapshot.latest_trade {
+                Some(trade) => {
+                    trade_timestamp.append_value(parse_timestamp(&trade.t)?);
+                    trade_price.append_value(trade.p);
+                    trade_size.append_value(trade.s);
+                }
+                None => {
+                    trade_timestamp.append_null();
+                    trade_price.append_null();
+                    trade_size.append_null();
+                }
+            }
+        }
+
+        let schema = Schema::new(vec![
+            Field::new("symbol", DataType::Utf8, false),
+            Field::new("delta", DataType::Float64, true),
+            Field::new("gamma", DataType::Float64, true),
+            Field::new("rho", DataType::Float64, true),
+            Field::new("theta", DataType::Float64, true),
+            Field::new("vega", DataType::Float64, true),
+            Field::new("implied_volatility", DataType::Float64, true),
+            timestamp_field("quote_timestamp", true),
+            Field::new("bid_price", DataType::Float32, true),
+            Field::new("bid_size", DataType::Int32, true),
+            Field::new("ask_price", DataType::Float32, true),
+            Field::new("ask_size", DataType::Int32, true),
+            timestamp_field("trade_timestamp", true),
+            Field::new("trade_price", DataType::Float64, true),
+            Field::new("trade_size", DataType::Int32, true),
+        ]);
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(symbol.finish()),
+            Arc::new(delta.finish()),
+            Arc::new(gamma.finish()),
+            Arc::new(rho.finish()),
+            Arc::new(theta.finish()),
+            Arc::new(vega.finish()),
+            Arc::new(implied_volatility.finish()),
+            Arc::new(quote_timestamp.finish()),
+            Arc::new(bid_price.finish()),
+            Arc::new(bid_size.finish()),
+            Arc::new(ask_price.finish()),
+            Arc::new(ask_size.finish()),
+            Arc::new(trade_timestamp.finish()),
+            Arc::new(trade_price.finish()),
+            Arc::new(trade_size.finish()),
+        ];
+        RecordBatch::try_new(Arc::new(schema), columns)
+    }
+}
+
+impl ToRecordBatch for PortfolioHistory {
+    fn to_record_batch(&self) -> Result<RecordBatch, ArrowError> {
+        let schema = Schema::new(vec![
+            Field::new(
+                "timestamp",
+                DataType::Timestamp(TimeUnit::Second, Some("UTC".into())),
+                false,
+            ),
+            Field::new("equity", DataType::Float64, false),
+            Field::new("profit_loss", DataType::Float64, false),
+            Field::new("profit_loss_pct", DataType::Float64, false),
+        ]);
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(TimestampSecondArray::from(self.timestamp.clone()).with_timezone("UTC")),
+            Arc::new(Float64Array::from(self.equity.clone())),
+            Arc::new(Float64Array::from(self.profit_loss.clone())),
+            Arc::new(Float64Array::from(self.profit_loss_pct.clone())),
+        ];
+        RecordBatch::try_new(Arc::new(schema), columns)
+    }
+}
+
+fn sorted_by_symbol<V>(map: &std::collections::HashMap<String, V>) -> Vec<(&str, &V)> {
+    let mut entries: Vec<(&str, &V)> = map.iter().map(|(k, v)| (k.as_str(), v)).collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries
+}
+
+fn timestamp_field(name: &str, nullable: bool) -> Field {
+    Field::new(
+        name,
+        DataType::Timestamp(TimeUnit::Nanosecond, Some("UTC".into())),
+        nullable,
+    )
+}
+
+fn conditions_field() -> Field {
+    Field::new(
+        "conditions",
+        DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+        false,
+    )
+}
+
+fn parse_timestamp(timestamp: &str) -> Result<i64, ArrowError> {
+    parse_rfc3339_nanos(timestamp)
+        .ok_or_else(|| ArrowError::ParseError(format!("Invalid timestamp: {}", timestamp)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{Array, Float32Array, StringArray, TimestampNanosecondArray};
+
+    #[test]
+    fn test_historical_bars_to_record_batch() {
+        let json = r#"{
+            "TSLA": [{"t":"2024-01-03T05:00:00Z","o":2.0,"h":3.0,"l":1.0,"c":2.5,"v":10.0,"n":3,"vw":2.2}],
+            "AAPL": [
+                {"t":"2024-01-02T05:00:00Z","o":1.0,"h":2.0,"l":0.5,"c":1.5,"v":100.0,"n":7,"vw":1.2},
+                {"t":"2024-01-03T05:00:00Z","o":1.5,"h":2.5,"l":1.0,"c":2.0,"v":200.0,"n":9,"vw":1.8}
+            ]
+        }"#;
+        let bars: HistoricalBars = serde_json::from_str(json).unwrap();
+        let batch = bars.to_record_batch().unwrap();
+
+        assert_eq!(batch.num_rows(), 3);
+        let symbols = batch
+            .column_by_name("symbol")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(symbols.value(0), "AAPL");
+        assert_eq!(symbols.value(2), "TSLA");
+
+        let timestamps = batch
+            .column_by_name("timestamp")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<TimestampNanosecondArray>()
+            .unwrap();
+        assert_eq!(timestamps.value(0), 1_704_171_600_000_000_000);
+
+        let close = batch
+            .column_by_name("close")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Float32Array>()
+            .unwrap();
+        assert_eq!(close.value(1), 2.0);
+    }
+
+    #[test]
+    fn test_option_snapshots_to_record_batch() {
+        let json = r#"{
+            "AAPL261218C00200000": {
+                "greeks": {"delta":0.5,"gamma":0.01,"rho":0.2,"theta":-0.03,"vega":0.4},
+                "impliedVolatility": 0.25,
+                "latestQuote": {"t":"2024-01-02T15:00:00Z","ax":"C","ap":5.1,"as":10,"bx":"C","bp":4.9,"bs":12,"c":"A"}
+            },
+            "AAPL261218P00200000": {}
+        }"#;
+        let snapshots: OptionSnapshots = serde_json::from_str(json).unwrap();
+        let batch = snapshots.to_record_batch().unwrap();
+
+        assert_eq!(batch.num_rows(), 2);
+        let delta = batch.column_by_name("delta").unwrap();
+        assert!(delta.is_valid(0));
+        assert!(delta.is_null(1));
+        assert_eq!(batch.column_by_name("trade_price").unwrap().null_count(), 2);
+    }
+}
@@ -0,0 +1,80 @@
+//! Client-wide dry-run switch for the mutating trading calls.
+//!
+//! While enabled, order submission, replacement and cancellation, position closes, option exercise
+//! instructions and account configuration changes log the request they would have made and return a
+//! synthesized result instead of calling the API. Market data and other reads are unaffected.
+//!
+//! Skipped requests go to the logger set with `set_dry_run_logger`, or to `tracing` at info level when
+//! the `tracing` feature is on. Otherwise they are not logged.
+
+use serde::Serialize;
+use std::{
+    fmt,
+    sync::{
+        RwLock,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+static LOGGER: RwLock<Option<fn(&DryRunRequest)>> = RwLock::new(None);
+
+/// A request that was not sent because dry-run mode is on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DryRunRequest {
+    pub method: &'static str,
+    pub url: String,
+    /// The JSON body, for POST, PATCH and PUT requests.
+    pub body: Option<serde_json::Value>,
+}
+
+impl fmt::Display for DryRunRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.method, self.url)?;
+        if let Some(body) = &self.body {
+            write!(f, " {}", body)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn set_dry_run(enabled: bool) {
+    DRY_RUN.store(enabled, Ordering::SeqCst);
+}
+
+pub fn is_dry_run() -> bool {
+    DRY_RUN.load(Ordering::SeqCst)
+}
+
+/// Where skipped requests are logged, instead of `tracing` or nowhere.
+pub fn set_dry_run_logger(logger: fn(&DryRunRequest)) {
+    *LOGGER.write().unwrap_or_else(|e| e.into_inner()) = Some(logger);
+}
+
+/// Log the request and return `true` when dry-run mode is on, in which case the caller must not send it.
+pub(crate) fn intercept(method: &'static str, url: &str) -> bool {
+    intercept_request(method, url, None)
+}
+
+/// Like `intercept`, for requests with a JSON body.
+pub(crate) fn intercept_json<T: Serialize>(method: &'static str, url: &str, body: &T) -> bool {
+    intercept_request(method, url, serde_json::to_value(body).ok())
+}
+
+fn intercept_request(method: &'static str, url: &str, body: Option<serde_json::Value>) -> bool {
+    if !is_dry_run() {
+        return false;
+    }
+    let request = DryRunRequest {
+        method,
+        url: url.to_string(),
+        body,
+    };
+    if let Some(logger) = *LOGGER.read().unwrap_or_else(|e| e.into_inner()) {
+        logger(&request);
+    } else {
+        #[cfg(feature = "tracing")]
+        tracing::info!(method = request.method, url = %request.url, body = ?request.body, "dry run");
+    }
+    true
+}
@@ -0,0 +1,142 @@
+//! Technical indicators computed over bar series.
+//!
+//! Every indicator is a small state machine that is fed one bar at a time with
+//! [`Indicator::next_bar`], so the same instance works on a historical `Vec<StockBar>`
+//! (via [`Indicator::batch`]) and on live `StreamBar` updates.
+//!
+//! # Example
+//! ```no_run
+//! use alpaca_api_client::indicators::{Indicator, Rsi, Sma};
+//! use alpaca_api_client::market_data::stocks::HistoricalBarsQuery;
+//! use alpaca_api_client::stream::{Feed, MarketDataMessage, StockStream};
+//! use alpaca_api_client::TimeFrame;
+//!
+//! let bars = HistoricalBarsQuery::new(vec!["AAPL"], TimeFrame::OneDay)
+//!     .start("2024-01-01")
+//!     .send()
+//!     .unwrap();
+//! let sma_20 = Sma::new(20).batch(&bars["AAPL"]);
+//! println!("{:?}", sma_20.last());
+//!
+//! let mut rsi = Rsi::new(14);
+//! StockStream::new(Feed::Iex)
+//!     .subscribe_bars(vec!["AAPL"])
+//!     .start(|msg| {
+//!         if let MarketDataMessage::Bar(bar) = msg {
+//!             if let Some(value) = rsi.next_bar(&bar) {
+//!                 println!("RSI: {:.2}", value);
+//!             }
+//!         }
+//!     })
+//!     .unwrap();
+//! ```
+
+use crate::{market_data::stocks::StockBar, stream::StreamBar};
+
+mod moving_average;
+pub use moving_average::*;
+
+mod oscillators;
+pub use oscillators::*;
+
+mod trend;
+pub use trend::*;
+
+mod volatility;
+pub use volatility::*;
+
+mod volume;
+pub use volume::*;
+
+/// Read access to the fields of a price bar.
+pub trait Ohlcv {
+    fn timestamp(&self) -> &str;
+    fn open(&self) -> f64;
+    fn high(&self) -> f64;
+    fn low(&self) -> f64;
+    fn close(&self) -> f64;
+    fn volume(&self) -> f64;
+}
+
+impl Ohlcv for StockBar {
+    fn timestamp(&self) -> &str {
+        &self.t
+    }
+    fn open(&self) -> f64 {
+        self.o as f64
+    }
+    fn high(&self) -> f64 {
+        self.h as f64
+    }
+    fn low(&self) -> f64 {
+        self.l as f64
+    }
+    fn close(&self) -> f64 {
+        self.c as f64
+    }
+    fn volume(&self) -> f64 {
+        self.v as f64
+    }
+}
+
+impl Ohlcv for StreamBar {
+    fn timestamp(&self) -> &str {
+        &self.t
+    }
+    fn open(&self) -> f64 {
+        self.o as f64
+    }
+    fn high(&self) -> f64 {
+        self.h as f64
+    }
+    fn low(&self) -> f64 {
+        self.l as f64
+    }
+    fn close(&self) -> f64 {
+        self.c as f64
+    }
+    fn volume(&self) -> f64 {
+        self.v as f64
+    }
+}
+
+/// An incrementally updated indicator.
+pub trait Indicator {
+    type Output;
+
+    /// Feed the next bar. Returns `None` until enough bars have been seen.
+    fn next_bar<B: Ohlcv>(&mut self, bar: &B) -> Option<Self::Output>;
+
+    /// Run the indicator over a whole series, returning one value per bar.
+    fn batch<B: Ohlcv>(mut self, bars: &[B]) -> Vec<Option<Self::Output>>
+    where
+        Self: Sized,
+    {
+        bars.iter().map(|bar| self.next_bar(bar)).collect()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_bars {
+    use crate::market_data::stocks::StockBar;
+
+    pub fn bar(t: &str, o: f32, h: f32, l: f32, c: f32, v: f32) -> StockBar {
+        StockBar {
+            t: t.to_string(),
+            o,
+            h,
+            l,
+            c,
+            v,
+            n: 1,
+            vw: c,
+        }
+    }
+
+    pub fn closes(values: &[f32]) -> Vec<StockBar> {
+        values
+            .iter()
+            .map(|c| bar("2024-01-02T14:30:00Z", *c, *c, *c, *c, 100.0))
+            .collect()
+    }
+}
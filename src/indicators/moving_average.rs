@@ -0,0 +1,114 @@
+use std::collections::VecDeque;
+
+use super::{Indicator, Ohlcv};
+
+/// Simple moving average of the close.
+#[derive(Debug, Clone)]
+pub struct Sma {
+    period: usize,
+    window: VecDeque<f64>,
+    sum: f64,
+}
+
+impl Sma {
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "Sma period must be greater than zero");
+        Self {
+            period,
+            window: VecDeque::with_capacity(period + 1),
+            sum: 0.0,
+        }
+    }
+
+    /// Feed a raw value instead of a bar.
+    pub fn update(&mut self, value: f64) -> Option<f64> {
+        self.window.push_back(value);
+        self.sum += value;
+        if self.window.len() > self.period {
+            self.sum -= self.window.pop_front().unwrap_or_default();
+        }
+        self.value()
+    }
+
+    pub fn value(&self) -> Option<f64> {
+        if self.window.len() == self.period {
+            Some(self.sum / self.period as f64)
+        } else {
+            None
+        }
+    }
+}
+
+impl Indicator for Sma {
+    type Output = f64;
+
+    fn next_bar<B: Ohlcv>(&mut self, bar: &B) -> Option<f64> {
+        self.update(bar.close())
+    }
+}
+
+/// Exponential moving average of the close, seeded with the SMA of the first `period` values.
+#[derive(Debug, Clone)]
+pub struct Ema {
+    period: usize,
+    alpha: f64,
+    seed: Sma,
+    current: Option<f64>,
+}
+
+impl Ema {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period,
+            alpha: 2.0 / (period as f64 + 1.0),
+            seed: Sma::new(period),
+            current: None,
+        }
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Feed a raw value instead of a bar.
+    pub fn update(&mut self, value: f64) -> Option<f64> {
+        self.current = match self.current {
+            Some(prev) => Some(prev + self.alpha * (value - prev)),
+            None => self.seed.update(value),
+        };
+        self.current
+    }
+
+    pub fn value(&self) -> Option<f64> {
+        self.current
+    }
+}
+
+impl Indicator for Ema {
+    type Output = f64;
+
+    fn next_bar<B: Ohlcv>(&mut self, bar: &B) -> Option<f64> {
+        self.update(bar.close())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::test_bars::closes;
+
+    #[test]
+    fn test_sma_batch() {
+        let bars = closes(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+        let values = Sma::new(3).batch(&bars);
+        assert_eq!(values, vec![None, None, Some(2.0), Some(3.0), Some(4.0)]);
+    }
+
+    #[test]
+    fn test_ema_seeded_with_sma() {
+        let bars = closes(&[1.0, 2.0, 3.0, 4.0]);
+        let values = Ema::new(3).batch(&bars);
+        // seed = 2.0, alpha = 0.5 -> 2.0 + 0.5 * (4.0 - 2.0) = 3.0
+        assert_eq!(values, vec![None, None, Some(2.0), Some(3.0)]);
+    }
+}
@@ -0,0 +1,144 @@
+use super::{Ema, Indicator, Ohlcv};
+
+/// Relative strength index using Wilder's smoothing.
+#[derive(Debug, Clone)]
+pub struct Rsi {
+    period: usize,
+    prev_close: Option<f64>,
+    seen: usize,
+    avg_gain: f64,
+    avg_loss: f64,
+}
+
+impl Rsi {
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "Rsi period must be greater than zero");
+        Self {
+            period,
+            prev_close: None,
+            seen: 0,
+            avg_gain: 0.0,
+            avg_loss: 0.0,
+        }
+    }
+
+    /// Feed a raw value instead of a bar.
+    pub fn update(&mut self, value: f64) -> Option<f64> {
+        let prev = self.prev_close.replace(value)?;
+        let change = value - prev;
+        let (gain, loss) = (change.max(0.0), (-change).max(0.0));
+        let period = self.period as f64;
+
+        self.seen += 1;
+        if self.seen <= self.period {
+            // Accumulate a simple average for the first period
+            self.avg_gain += gain / period;
+            self.avg_loss += loss / period;
+            if self.seen < self.period {
+                return None;
+            }
+        } else {
+            self.avg_gain = (self.avg_gain * (period - 1.0) + gain) / period;
+            self.avg_loss = (self.avg_loss * (period - 1.0) + loss) / period;
+        }
+
+        if self.avg_loss == 0.0 {
+            return Some(if self.avg_gain == 0.0 { 50.0 } else { 100.0 });
+        }
+        let rs = self.avg_gain / self.avg_loss;
+        Some(100.0 - 100.0 / (1.0 + rs))
+    }
+}
+
+impl Indicator for Rsi {
+    type Output = f64;
+
+    fn next_bar<B: Ohlcv>(&mut self, bar: &B) -> Option<f64> {
+        self.update(bar.close())
+    }
+}
+
+/// Output of [`Macd`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MacdValue {
+    pub macd: f64,
+    pub signal: f64,
+    pub histogram: f64,
+}
+
+/// Moving average convergence divergence.
+#[derive(Debug, Clone)]
+pub struct Macd {
+    fast: Ema,
+    slow: Ema,
+    signal: Ema,
+}
+
+impl Macd {
+    pub fn new(fast: usize, slow: usize, signal: usize) -> Self {
+        assert!(
+            fast < slow,
+            "Macd fast period must be shorter than slow period"
+        );
+        Self {
+            fast: Ema::new(fast),
+            slow: Ema::new(slow),
+            signal: Ema::new(signal),
+        }
+    }
+
+    /// Feed a raw value instead of a bar.
+    pub fn update(&mut self, value: f64) -> Option<MacdValue> {
+        let fast = self.fast.update(value);
+        let slow = self.slow.update(value);
+        let macd = fast? - slow?;
+        let signal = self.signal.update(macd)?;
+        Some(MacdValue {
+            macd,
+            signal,
+            histogram: macd - signal,
+        })
+    }
+}
+
+impl Default for Macd {
+    /// The conventional 12/26/9 configuration.
+    fn default() -> Self {
+        Self::new(12, 26, 9)
+    }
+}
+
+impl Indicator for Macd {
+    type Output = MacdValue;
+
+    fn next_bar<B: Ohlcv>(&mut self, bar: &B) -> Option<MacdValue> {
+        self.update(bar.close())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::test_bars::closes;
+
+    #[test]
+    fn test_rsi_bounds() {
+        let rising = closes(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+        let values = Rsi::new(3).batch(&rising);
+        assert_eq!(values[..3], [None, None, None]);
+        assert_eq!(values[3], Some(100.0));
+
+        let mixed = closes(&[10.0, 11.0, 10.0, 11.0, 10.0]);
+        let last = Rsi::new(2).batch(&mixed).pop().flatten().unwrap();
+        assert!(last > 0.0 && last < 100.0);
+    }
+
+    #[test]
+    fn test_macd_waits_for_signal() {
+        let bars = closes(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        let values = Macd::new(2, 3, 2).batch(&bars);
+        assert!(values[..3].iter().all(Option::is_none));
+        let value = values[3].unwrap();
+        assert!((value.histogram - (value.macd - value.signal)).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,66 @@
+use super::{Ema, Indicator, Ohlcv};
+use crate::Trend;
+
+/// Classifies the trend by comparing a fast and a slow EMA of the close.
+///
+/// The trend is [`Trend::Bullish`] while the fast EMA is above the slow EMA and
+/// [`Trend::Bearish`] otherwise.
+#[derive(Debug, Clone)]
+pub struct TrendClassifier {
+    fast: Ema,
+    slow: Ema,
+}
+
+impl TrendClassifier {
+    pub fn new(fast: usize, slow: usize) -> Self {
+        assert!(
+            fast < slow,
+            "TrendClassifier fast period must be shorter than slow period"
+        );
+        Self {
+            fast: Ema::new(fast),
+            slow: Ema::new(slow),
+        }
+    }
+
+    /// Feed a raw value instead of a bar.
+    pub fn update(&mut self, value: f64) -> Option<Trend> {
+        let fast = self.fast.update(value);
+        let slow = self.slow.update(value);
+        if fast? > slow? {
+            Some(Trend::Bullish)
+        } else {
+            Some(Trend::Bearish)
+        }
+    }
+}
+
+impl Indicator for TrendClassifier {
+    type Output = Trend;
+
+    fn next_bar<B: Ohlcv>(&mut self, bar: &B) -> Option<Trend> {
+        self.update(bar.close())
+    }
+}
+
+/// Classify the trend at the end of a bar series. Returns `None` if there are fewer than `slow` bars.
+pub fn classify_trend<B: Ohlcv>(bars: &[B], fast: usize, slow: usize) -> Option<Trend> {
+    TrendClassifier::new(fast, slow).batch(bars).pop().flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::test_bars::closes;
+
+    #[test]
+    fn test_classify_trend() {
+        let rising = closes(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        assert_eq!(classify_trend(&rising, 2, 4), Some(Trend::Bullish));
+
+        let falling = closes(&[6.0, 5.0, 4.0, 3.0, 2.0, 1.0]);
+        assert_eq!(classify_trend(&falling, 2, 4), Some(Trend::Bearish));
+
+        assert_eq!(classify_trend(&falling[..3], 2, 4), None);
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::VecDeque;
+
+use super::{Indicator, Ohlcv};
+
+/// Output of [`BollingerBands`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BollingerValue {
+    pub upper: f64,
+    pub middle: f64,
+    pub lower: f64,
+}
+
+/// Bollinger bands: an SMA of the close plus/minus `k` population standard deviations.
+#[derive(Debug, Clone)]
+pub struct BollingerBands {
+    period: usize,
+    k: f64,
+    window: VecDeque<f64>,
+}
+
+impl BollingerBands {
+    pub fn new(period: usize, k: f64) -> Self {
+        assert!(
+            period > 0,
+            "BollingerBands period must be greater than zero"
+        );
+        Self {
+            period,
+            k,
+            window: VecDeque::with_capacity(period + 1),
+        }
+    }
+
+    /// Feed a raw value instead of a bar.
+    pub fn update(&mut self, value: f64) -> Option<BollingerValue> {
+        self.window.push_back(value);
+        if self.window.len() > self.period {
+            self.window.pop_front();
+        }
+        if self.window.len() < self.period {
+            return None;
+        }
+
+        let n = self.period as f64;
+        let middle = self.window.iter().sum::<f64>() / n;
+        let variance = self
+            .window
+            .iter()
+            .map(|v| (v - middle).powi(2))
+            .sum::<f64>()
+            / n;
+        let width = self.k * variance.sqrt();
+        Some(BollingerValue {
+            upper: middle + width,
+            middle,
+            lower: middle - width,
+        })
+    }
+}
+
+impl Default for BollingerBands {
+    /// The conventional 20 period, 2 standard deviation configuration.
+    fn default() -> Self {
+        Self::new(20, 2.0)
+    }
+}
+
+impl Indicator for BollingerBands {
+    type Output = BollingerValue;
+
+    fn next_bar<B: Ohlcv>(&mut self, bar: &B) -> Option<BollingerValue> {
+        self.update(bar.close())
+    }
+}
+
+/// Average true range using Wilder's smoothing.
+#[derive(Debug, Clone)]
+pub struct Atr {
+    period: usize,
+    prev_close: Option<f64>,
+    seen: usize,
+    current: f64,
+}
+
+impl Atr {
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "Atr period must be greater than zero");
+        Self {
+            period,
+            prev_close: None,
+            seen: 0,
+            current: 0.0,
+        }
+    }
+}
+
+impl Indicator for Atr {
+    type Output = f64;
+
+    fn next_bar<B: Ohlcv>(&mut self, bar: &B) -> Option<f64> {
+        let range = bar.high() - bar.low();
+        let true_range = match self.prev_close {
+            Some(prev) => range
+                .max((bar.high() - prev).abs())
+                .max((bar.low() - prev).abs()),
+            None => range,
+        };
+        self.prev_close = Some(bar.close());
+
+        let period = self.period as f64;
+        self.seen += 1;
+        if self.seen <= self.period {
+            self.current += true_range / period;
+            if self.seen < self.period {
+                return None;
+            }
+        } else {
+            self.current = (self.current * (period - 1.0) + true_range) / period;
+        }
+        Some(self.current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::test_bars::{bar, closes};
+
+    #[test]
+    fn test_bollinger_bands() {
+        let bars = closes(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]);
+        let value = BollingerBands::new(8, 2.0)
+            .batch(&bars)
+            .pop()
+            .flatten()
+            .unwrap();
+        assert_eq!(value.middle, 5.0);
+        assert_eq!(value.upper, 9.0);
+        assert_eq!(value.lower, 1.0);
+    }
+
+    #[test]
+    fn test_atr_uses_previous_close() {
+        let bars = vec![
+            bar("2024-01-02T14:30:00Z", 10.0, 11.0, 9.0, 10.0, 100.0),
+            // Gap up: true range is high - prev close = 4.0
+            bar("2024-01-03T14:30:00Z", 13.0, 14.0, 12.0, 13.0, 100.0),
+            bar("2024-01-04T14:30:00Z", 13.0, 14.0, 13.0, 13.5, 100.0),
+        ];
+        let values = Atr::new(2).batch(&bars);
+        assert_eq!(values[0], None);
+        assert_eq!(values[1], Some(3.0));
+        assert_eq!(values[2], Some(2.0));
+    }
+}
@@ -0,0 +1,57 @@
+use super::{Indicator, Ohlcv};
+
+/// Session volume weighted average price of the typical price `(h + l + c) / 3`.
+///
+/// The running totals reset whenever the calendar date of the bar timestamp changes.
+#[derive(Debug, Clone, Default)]
+pub struct Vwap {
+    session: Option<String>,
+    price_volume: f64,
+    volume: f64,
+}
+
+impl Vwap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Indicator for Vwap {
+    type Output = f64;
+
+    fn next_bar<B: Ohlcv>(&mut self, bar: &B) -> Option<f64> {
+        let date = bar.timestamp().get(..10).unwrap_or_default();
+        if self.session.as_deref() != Some(date) {
+            self.session = Some(date.to_string());
+            self.price_volume = 0.0;
+            self.volume = 0.0;
+        }
+
+        let typical = (bar.high() + bar.low() + bar.close()) / 3.0;
+        self.price_volume += typical * bar.volume();
+        self.volume += bar.volume();
+
+        if self.volume == 0.0 {
+            None
+        } else {
+            Some(self.price_volume / self.volume)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::test_bars::bar;
+
+    #[test]
+    fn test_vwap_resets_each_session() {
+        let bars = vec![
+            bar("2024-01-02T14:30:00Z", 10.0, 10.0, 10.0, 10.0, 100.0),
+            bar("2024-01-02T14:31:00Z", 13.0, 13.0, 13.0, 13.0, 200.0),
+            bar("2024-01-03T14:30:00Z", 20.0, 20.0, 20.0, 20.0, 50.0),
+        ];
+        let values = Vwap::new().batch(&bars);
+        assert_eq!(values, vec![Some(10.0), Some(12.0), Some(20.0)]);
+    }
+}
@@ -0,0 +1,378 @@
+//! Instrumentation hooks for HTTP requests and streams. Spans and events are emitted with `tracing` and
+//! counters and histograms with `metrics` when those features are on, and everything compiles to nothing
+//! otherwise. Header values are never recorded, so API keys stay out of logs and metrics.
+
+use crate::stream::{MarketDataMessage, StreamError};
+
+#[cfg(feature = "tracing")]
+use std::collections::BTreeMap;
+#[cfg(any(feature = "tracing", feature = "metrics"))]
+use std::time::{Duration, Instant};
+#[cfg(any(feature = "tracing", feature = "metrics"))]
+use ureq::{
+    Body, SendBody,
+    http::{Request, Response, Uri},
+    middleware::MiddlewareNext,
+};
+
+/// How often a stream logs its message counts at debug level.
+#[cfg(feature = "tracing")]
+const REPORT_INTERVAL: Duration = Duration::from_secs(60);
+
+#[cfg(feature = "tracing")]
+const RATE_LIMIT_HEADERS: [(&str, &str); 3] = [
+    ("x-ratelimit-limit", "ratelimit_limit"),
+    ("x-ratelimit-remaining", "ratelimit_remaining"),
+    ("x-ratelimit-reset", "ratelimit_reset"),
+];
+
+/// Host and path, without the query string.
+#[cfg(feature = "tracing")]
+fn endpoint(uri: &Uri) -> String {
+    format!("{}{}", uri.host().unwrap_or_default(), uri.path())
+}
+
+/// Host and path with symbols and ids replaced by `{}`, to keep the number of label values bounded.
+#[cfg(feature = "metrics")]
+fn endpoint_label(uri: &Uri) -> String {
+    let is_version = |segment: &str| {
+        segment.strip_prefix('v').is_some_and(|rest| {
+            rest.starts_with(|c: char| c.is_ascii_digit())
+                && rest
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        })
+    };
+    let is_word = |segment: &str| {
+        !segment.is_empty()
+            && segment
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || matches!(c, '_' | '-' | ':'))
+    };
+    let path: Vec<&str> = uri
+        .path()
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| match is_word(segment) || is_version(segment) {
+            true => segment,
+            false => "{}",
+        })
+        .collect();
+    format!("{}/{}", uri.host().unwrap_or_default(), path.join("/"))
+}
+
+/// Traces and measures every request. Installed on the agent used by every request.
+#[cfg(any(feature = "tracing", feature = "metrics"))]
+pub(crate) fn middleware(
+    request: Request<SendBody>,
+    next: MiddlewareNext,
+) -> Result<Response<Body>, ureq::Error> {
+    #[cfg(feature = "metrics")]
+    let labels = (request.method().to_string(), endpoint_label(request.uri()));
+    #[cfg(feature = "tracing")]
+    let span = tracing::debug_span!(
+        "http_request",
+        method = %request.method(),
+        endpoint = %endpoint(request.uri()),
+        status = tracing::field::Empty,
+        latency_ms = tracing::field::Empty,
+        ratelimit_limit = tracing::field::Empty,
+        ratelimit_remaining = tracing::field::Empty,
+        ratelimit_reset = tracing::field::Empty,
+    );
+    #[cfg(feature = "tracing")]
+    let _entered = span.enter();
+
+    let started = Instant::now();
+    let result = next.handle(request);
+    let elapsed = started.elapsed();
+
+    #[cfg(feature = "tracing")]
+    trace_response(&span, &result, elapsed);
+    #[cfg(feature = "metrics")]
+    measure_response(labels, &result, elapsed);
+    result
+}
+
+#[cfg(any(feature = "tracing", feature = "metrics"))]
+fn status(result: &Result<Response<Body>, ureq::Error>) -> Option<u16> {
+    match result {
+        Ok(response) => Some(response.status().as_u16()),
+        Err(ureq::Error::StatusCode(status)) => Some(*status),
+        Err(_) => None,
+    }
+}
+
+#[cfg(feature = "tracing")]
+fn trace_response(
+    span: &tracing::Span,
+    result: &Result<Response<Body>, ureq::Error>,
+    elapsed: Duration,
+) {
+    span.record("latency_ms", elapsed.as_secs_f64() * 1000.0);
+    if let Ok(response) = result {
+        for (header, field) in RATE_LIMIT_HEADERS {
+            if let Some(value) = response.headers().get(header)
+                && let Ok(value) = value.to_str()
+            {
+                span.record(field, value);
+            }
+        }
+    }
+    match (status(result), result) {
+        (Some(status), _) if status >= 400 => {
+            span.record("status", status);
+            tracing::warn!(status, "request returned an error status");
+        }
+        (Some(status), _) => {
+            span.record("status", status);
+            tracing::debug!(status, "request completed");
+        }
+        (None, Err(e)) => tracing::warn!(error = %e, "request failed"),
+        (None, Ok(_)) => {}
+    }
+}
+
+#[cfg(feature = "metrics")]
+fn measure_response(
+    (method, endpoint): (String, String),
+    result: &Result<Response<Body>, ureq::Error>,
+    elapsed: Duration,
+) {
+    let status = match status(result) {
+        Some(status) => status.to_string(),
+        None => "error".to_string(),
+    };
+    if status == "429" {
+        metrics::counter!("alpaca_http_rate_limited_total", "endpoint" => endpoint.clone())
+            .increment(1);
+    }
+    if let Ok(response) = result
+        && let Some(remaining) = response
+            .headers()
+            .get("x-ratelimit-remaining")
+            .and_then(|value| value.to_str().ok()?.parse::<f64>().ok())
+    {
+        metrics::gauge!("alpaca_http_rate_limit_remaining").set(remaining);
+    }
+    metrics::histogram!(
+        "alpaca_http_request_duration_seconds",
+        "method" => method.clone(),
+        "endpoint" => endpoint.clone()
+    )
+    .record(elapsed.as_secs_f64());
+    metrics::counter!(
+        "alpaca_http_requests_total",
+        "method" => method,
+        "endpoint" => endpoint,
+        "status" => status
+    )
+    .increment(1);
+}
+
+/// A resubmitted order that the API rejected as a duplicate, resolved by `send_idempotent`.
+pub(crate) fn idempotent_retry() {
+    #[cfg(feature = "metrics")]
+    metrics::counter!("alpaca_order_idempotent_retries_total").increment(1);
+}
+
+#[cfg(feature = "tracing")]
+pub(crate) type PageSpan = tracing::span::EnteredSpan;
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct PageSpan;
+
+/// Entered around each page of a paginated query, so its requests carry the page number.
+pub(crate) fn page_span(page: u32) -> PageSpan {
+    #[cfg(feature = "tracing")]
+    return tracing::debug_span!("page", page).entered();
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = page;
+        PageSpan
+    }
+}
+
+/// Records the time spent in the stream handler when dropped.
+pub(crate) struct HandlerTimer {
+    #[cfg(feature = "metrics")]
+    stream: &'static str,
+    #[cfg(feature = "metrics")]
+    started: Instant,
+}
+
+#[cfg(feature = "metrics")]
+impl Drop for HandlerTimer {
+    fn drop(&mut self) {
+        metrics::histogram!("alpaca_stream_handler_duration_seconds", "stream" => self.stream)
+            .record(self.started.elapsed().as_secs_f64());
+    }
+}
+
+/// Lifecycle events, message counts and latencies for one stream connection.
+pub(crate) struct StreamMonitor {
+    #[cfg(feature = "metrics")]
+    stream: &'static str,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+    #[cfg(feature = "tracing")]
+    since_report: BTreeMap<&'static str, u64>,
+    #[cfg(feature = "tracing")]
+    total: u64,
+    #[cfg(feature = "tracing")]
+    last_report: Instant,
+}
+
+// Each feature reads only some of the arguments.
+#[cfg_attr(
+    not(all(feature = "tracing", feature = "metrics")),
+    allow(unused_variables)
+)]
+impl StreamMonitor {
+    pub(crate) fn new(stream: &'static str, url: &str) -> Self {
+        Self {
+            #[cfg(feature = "metrics")]
+            stream,
+            #[cfg(feature = "tracing")]
+            span: tracing::info_span!("stream", stream, url),
+            #[cfg(feature = "tracing")]
+            since_report: BTreeMap::new(),
+            #[cfg(feature = "tracing")]
+            total: 0,
+            #[cfg(feature = "tracing")]
+            last_report: Instant::now(),
+        }
+    }
+
+    /// Run `f` inside the stream's span, recording the error it fails with.
+    pub(crate) fn watch<T, F>(&mut self, f: F) -> Result<T, StreamError>
+    where
+        F: FnOnce(&mut Self) -> Result<T, StreamError>,
+    {
+        #[cfg(feature = "tracing")]
+        let span = self.span.clone();
+        #[cfg(feature = "tracing")]
+        let _entered = span.enter();
+        let result = f(self);
+        if let Err(e) = &result {
+            self.error(e);
+        }
+        result
+    }
+
+    pub(crate) fn connected(&self) {
+        #[cfg(feature = "tracing")]
+        tracing::info!(parent: &self.span, "connected");
+        #[cfg(feature = "metrics")]
+        metrics::counter!("alpaca_stream_connections_total", "stream" => self.stream).increment(1);
+    }
+
+    pub(crate) fn authenticated(&self) {
+        #[cfg(feature = "tracing")]
+        tracing::info!(parent: &self.span, "authenticated");
+    }
+
+    pub(crate) fn subscribed(&self, request: &serde_json::Value) {
+        #[cfg(feature = "tracing")]
+        tracing::info!(parent: &self.span, subscription = %request, "subscribed");
+    }
+
+    /// Count a market data message, measuring exchange-to-receive latency for trades and quotes.
+    pub(crate) fn market_data(&mut self, message: &MarketDataMessage) {
+        let timestamp = match message {
+            MarketDataMessage::Trade(trade) => Some(trade.t.as_str()),
+            MarketDataMessage::Quote(quote) => Some(quote.t.as_str()),
+            _ => None,
+        };
+        self.message(message.kind(), timestamp);
+    }
+
+    /// Count a message of `kind`, measuring latency from `timestamp` when given.
+    pub(crate) fn message(&mut self, kind: &'static str, timestamp: Option<&str>) {
+        #[cfg(feature = "metrics")]
+        {
+            metrics::counter!("alpaca_stream_messages_total", "stream" => self.stream, "type" => kind)
+                .increment(1);
+            if let Some(sent) = timestamp.and_then(crate::time::parse_rfc3339_nanos) {
+                let now = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_nanos() as i64)
+                    .unwrap_or_default();
+                metrics::histogram!(
+                    "alpaca_stream_receive_latency_seconds",
+                    "stream" => self.stream,
+                    "type" => kind
+                )
+                .record((now - sent) as f64 / 1e9);
+            }
+        }
+        #[cfg(feature = "tracing")]
+        {
+            *self.since_report.entry(kind).or_default() += 1;
+            self.total += 1;
+            if self.last_report.elapsed() >= REPORT_INTERVAL {
+                tracing::debug!(
+                    parent: &self.span,
+                    total = self.total,
+                    counts = ?self.since_report,
+                    seconds = self.last_report.elapsed().as_secs(),
+                    "message counts"
+                );
+                self.since_report.clear();
+                self.last_report = Instant::now();
+            }
+        }
+    }
+
+    /// Hold while the handler runs.
+    pub(crate) fn time_handler(&self) -> HandlerTimer {
+        HandlerTimer {
+            #[cfg(feature = "metrics")]
+            stream: self.stream,
+            #[cfg(feature = "metrics")]
+            started: Instant::now(),
+        }
+    }
+
+    pub(crate) fn error(&self, error: &StreamError) {
+        #[cfg(feature = "tracing")]
+        tracing::warn!(parent: &self.span, error = %error, "stream error");
+        #[cfg(feature = "metrics")]
+        metrics::counter!("alpaca_stream_errors_total", "stream" => self.stream).increment(1);
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl Drop for StreamMonitor {
+    fn drop(&mut self) {
+        tracing::info!(parent: &self.span, total = self.total, "closed");
+    }
+}
+
+#[cfg(all(test, feature = "metrics"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_label() {
+        let label = |url: &str| endpoint_label(&url.parse().unwrap());
+        assert_eq!(
+            label("https://data.alpaca.markets/v2/stocks/AAPL/bars?timeframe=1Day"),
+            "data.alpaca.markets/v2/stocks/{}/bars"
+        );
+        assert_eq!(
+            label(
+                "https://paper-api.alpaca.markets/v2/orders/61e69015-8549-4bfd-b9c3-01e75843f47d"
+            ),
+            "paper-api.alpaca.markets/v2/orders/{}"
+        );
+        assert_eq!(
+            label("https://paper-api.alpaca.markets/v2/orders:by_client_order_id"),
+            "paper-api.alpaca.markets/v2/orders:by_client_order_id"
+        );
+        assert_eq!(
+            label("https://data.alpaca.markets/v1beta3/crypto/us/latest/quotes"),
+            "data.alpaca.markets/v1beta3/crypto/us/latest/quotes"
+        );
+    }
+}
@@ -0,0 +1,686 @@
+//! Audit journal of trading API requests.
+//!
+//! Once enabled, every order submission, replacement and cancellation, position close and other
+//! mutating call to the trading API is appended to a JSON Lines file with its request body, response,
+//! HTTP status, timestamps and latency. API keys are redacted. Market data requests are never recorded.
+//!
+//! ```no_run
+//! use alpaca_api_client::journal::{Journal, order_timelines, read_journal};
+//!
+//! Journal::new("audit/trading.jsonl")
+//!     .max_file_bytes(16 * 1024 * 1024)
+//!     .max_files(30)
+//!     .enable()
+//!     .unwrap();
+//!
+//! // ... trade ...
+//!
+//! let records = read_journal("audit/trading.jsonl").unwrap();
+//! for timeline in order_timelines(&records) {
+//!     println!("{} {:?}", timeline.symbol, timeline.order_ids);
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ffi::OsString,
+    fmt,
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, Read, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::Instant,
+};
+use ureq::{
+    AsSendBody, Body, SendBody,
+    http::{Request, Response},
+    middleware::MiddlewareNext,
+};
+
+use crate::time::now_rfc3339;
+
+const TRADING_HOSTS: [&str; 2] = ["api.alpaca.markets", "paper-api.alpaca.markets"];
+const REDACTED_HEADERS: [&str; 2] = ["apca-api-key-id", "apca-api-secret-key"];
+const REDACTED: &str = "[REDACTED]";
+
+static JOURNAL: Mutex<Option<JournalWriter>> = Mutex::new(None);
+
+/// Settings for the audit journal. Nothing is recorded until `enable` is called.
+#[derive(Debug, Clone)]
+pub struct Journal {
+    path: PathBuf,
+    max_file_bytes: u64,
+    max_files: usize,
+    include_reads: bool,
+}
+
+impl Journal {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            max_file_bytes: 64 * 1024 * 1024,
+            max_files: 10,
+            include_reads: false,
+        }
+    }
+
+    /// Rotate once the file reaches this size. Defaults to 64 MiB.
+    pub fn max_file_bytes(mut self, max_file_bytes: u64) -> Self {
+        self.max_file_bytes = max_file_bytes;
+        self
+    }
+
+    /// Rotated files to keep, as `path.1` (newest) to `path.N`. Defaults to 10.
+    pub fn max_files(mut self, max_files: usize) -> Self {
+        self.max_files = max_files;
+        self
+    }
+
+    /// Also record GET requests to the trading API, such as order and position lookups. Defaults to `false`.
+    pub fn include_reads(mut self, include_reads: bool) -> Self {
+        self.include_reads = include_reads;
+        self
+    }
+
+    /// Open the file for appending and start recording, replacing any journal already enabled.
+    pub fn enable(self) -> io::Result<()> {
+        if let Some(dir) = self.path.parent()
+            && !dir.as_os_str().is_empty()
+        {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let size = file.metadata()?.len();
+        *journal() = Some(JournalWriter {
+            settings: self,
+            file,
+            size,
+            error: None,
+        });
+        Ok(())
+    }
+}
+
+/// Stop recording and close the file.
+pub fn disable_journal() {
+    *journal() = None;
+}
+
+pub fn is_journal_enabled() -> bool {
+    journal().is_some()
+}
+
+/// The first error writing the journal since it was enabled or this was last called. Records that
+/// failed to write are lost, so check this where a gap in the journal matters.
+pub fn take_journal_error() -> Option<io::Error> {
+    journal().as_mut().and_then(|writer| writer.error.take())
+}
+
+fn journal() -> std::sync::MutexGuard<'static, Option<JournalWriter>> {
+    JOURNAL.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// One request and its outcome, a line in the journal.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct JournalRecord {
+    pub sent_at: String,
+    pub received_at: String,
+    pub latency_ms: f64,
+    pub method: String,
+    pub url: String,
+    /// API key headers are replaced with `[REDACTED]`.
+    pub request_headers: BTreeMap<String, String>,
+    pub request_body: Option<serde_json::Value>,
+    /// `None` when no response was received.
+    pub status: Option<u16>,
+    pub response_body: Option<serde_json::Value>,
+    /// Transport or decoding error, when the request failed without a response.
+    pub error: Option<String>,
+}
+
+impl JournalRecord {
+    /// Path segments after `/v2/`, e.g. `["orders", "{id}"]`.
+    fn route(&self) -> Vec<&str> {
+        let path = self.url.split('?').next().unwrap_or_default();
+        match path.split_once("/v2/") {
+            Some((_, route)) => route.split('/').filter(|s| !s.is_empty()).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn query_param(&self, key: &str) -> Option<&str> {
+        let (_, query) = self.url.split_once('?')?;
+        query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix(key)?.strip_prefix('='))
+            .filter(|value| !value.is_empty())
+    }
+}
+
+struct JournalWriter {
+    settings: Journal,
+    file: File,
+    size: u64,
+    error: Option<io::Error>,
+}
+
+impl JournalWriter {
+    fn append(&mut self, record: &JournalRecord) -> io::Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+        if self.size > 0 && self.size + line.len() as u64 > self.settings.max_file_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(&line)?;
+        self.file.flush()?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        let path = &self.settings.path;
+        let max_files = self.settings.max_files;
+        if max_files == 0 {
+            self.file.set_len(0)?;
+            self.size = 0;
+            return Ok(());
+        }
+        let oldest = rotated_path(path, max_files);
+        if oldest.exists() {
+            fs::remove_file(&oldest)?;
+        }
+        for index in (1..max_files).rev() {
+            let from = rotated_path(path, index);
+            if from.exists() {
+                fs::rename(&from, rotated_path(path, index + 1))?;
+            }
+        }
+        fs::rename(path, rotated_path(path, 1))?;
+        self.file = OpenOptions::new().create(true).append(true).open(path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(format!(".{}", index));
+    PathBuf::from(name)
+}
+
+fn parse_body(bytes: &[u8]) -> Option<serde_json::Value> {
+    if bytes.is_empty() {
+        return None;
+    }
+    serde_json::from_slice(bytes)
+        .ok()
+        .or_else(|| Some(String::from_utf8_lossy(bytes).into_owned().into()))
+}
+
+fn should_record<B>(request: &Request<B>) -> bool {
+    let is_trading = request
+        .uri()
+        .host()
+        .is_some_and(|host| TRADING_HOSTS.contains(&host));
+    if !is_trading {
+        return false;
+    }
+    match journal().as_ref() {
+        Some(writer) => writer.settings.include_reads || request.method() != "GET",
+        None => false,
+    }
+}
+
+/// Records trading requests while the journal is enabled. Installed on the agent used by every request.
+pub(crate) fn middleware(
+    request: Request<SendBody>,
+    next: MiddlewareNext,
+) -> Result<Response<Body>, ureq::Error> {
+    if !should_record(&request) {
+        return next.handle(request);
+    }
+
+    let sent_at = now_rfc3339();
+    let method = request.method().to_string();
+    let url = request.uri().to_string();
+    let request_headers = request
+        .headers()
+        .iter()
+        .map(|(name, value)| {
+            let value = match REDACTED_HEADERS.contains(&name.as_str()) {
+                true => REDACTED.to_string(),
+                false => value.to_str().unwrap_or_default().to_string(),
+            };
+            (name.to_string(), value)
+        })
+        .collect();
+
+    // The body is a one-shot reader, so read it for the record and send the copy
+    let (mut parts, body) = request.into_parts();
+    let mut bytes = Vec::new();
+    body.into_reader().read_to_end(&mut bytes)?;
+    let request_body = parse_body(&bytes);
+    if !bytes.is_empty() && !parts.headers.contains_key("content-type") {
+        parts
+            .headers
+            .insert("content-type", "application/json".parse().unwrap());
+    }
+
+    let started = Instant::now();
+    let response = match bytes.is_empty() {
+        true => next.handle(Request::from_parts(parts, SendBody::none())),
+        false => next.handle(Request::from_parts(parts, bytes.as_body())),
+    };
+    let result = response.and_then(|response| {
+        let (parts, mut body) = response.into_parts();
+        let mime_type = body.mime_type().map(str::to_string);
+        let charset = body.charset().map(str::to_string);
+        let data = body.read_to_vec()?;
+
+        let mut builder = Body::builder();
+        if let Some(mime_type) = mime_type {
+            builder = builder.mime_type(mime_type);
+        }
+        if let Some(charset) = charset {
+            builder = builder.charset(charset);
+        }
+        let response_body = parse_body(&data);
+        Ok((
+            Response::from_parts(parts, builder.data(data)),
+            response_body,
+        ))
+    });
+
+    let mut record = JournalRecord {
+        sent_at,
+        received_at: now_rfc3339(),
+        latency_ms: started.elapsed().as_secs_f64() * 1000.0,
+        method,
+        url,
+        request_headers,
+        request_body,
+        status: None,
+        response_body: None,
+        error: None,
+    };
+    // Error statuses arrive as responses only for callers that read them, otherwise without a body
+    match &result {
+        Ok((response, body)) => {
+            record.status = Some(response.status().as_u16());
+            record.response_body = body.clone();
+        }
+        Err(ureq::Error::StatusCode(status)) => record.status = Some(*status),
+        Err(e) => record.error = Some(e.to_string()),
+    }
+    if let Some(writer) = journal().as_mut()
+        && let Err(e) = writer.append(&record)
+    {
+        writer.error.get_or_insert(e);
+    }
+
+    result.map(|(response, _)| response)
+}
+
+/// Read a journal and its rotated files, oldest record first.
+pub fn read_journal(path: impl AsRef<Path>) -> io::Result<Vec<JournalRecord>> {
+    let path = path.as_ref();
+    let mut files = Vec::new();
+    let mut index = 1;
+    while rotated_path(path, index).exists() {
+        files.push(rotated_path(path, index));
+        index += 1;
+    }
+    files.reverse();
+    if path.exists() {
+        files.push(path.to_path_buf());
+    }
+
+    let mut records = Vec::new();
+    for file in files {
+        for line in BufReader::new(File::open(file)?).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            records.push(
+                serde_json::from_str(&line)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+            );
+        }
+    }
+    Ok(records)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimelineAction {
+    Submit,
+    Replace,
+    Cancel,
+    /// An order to close a position, from `close_all_positions` or `close_position_by_id_or_symbol`.
+    Close,
+    /// An order lookup, recorded with `include_reads`.
+    Fetch,
+}
+
+impl fmt::Display for TimelineAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = match self {
+            TimelineAction::Submit => "submit",
+            TimelineAction::Replace => "replace",
+            TimelineAction::Cancel => "cancel",
+            TimelineAction::Close => "close",
+            TimelineAction::Fetch => "fetch",
+        };
+        write!(f, "{}", action)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimelineEvent {
+    pub sent_at: String,
+    pub action: TimelineAction,
+    /// The order acted on, or created by a submit or replace. `None` for a rejected submit.
+    pub order_id: Option<String>,
+    pub http_status: Option<u16>,
+    /// The order status in the response, when the response is an order.
+    pub order_status: Option<String>,
+    pub latency_ms: f64,
+    pub error: Option<String>,
+}
+
+/// Everything recorded for one order, following replacements.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderTimeline {
+    /// The original order first, then each replacement.
+    pub order_ids: Vec<String>,
+    pub client_order_id: Option<String>,
+    pub symbol: String,
+    pub events: Vec<TimelineEvent>,
+}
+
+#[derive(Default)]
+struct TimelineBuilder {
+    timelines: Vec<OrderTimeline>,
+    by_order_id: HashMap<String, usize>,
+    by_client_order_id: HashMap<String, usize>,
+}
+
+impl TimelineBuilder {
+    /// The timeline for an order, matched by id, then client order id, created if neither is known.
+    fn timeline(&mut self, order_id: Option<&str>, client_order_id: Option<&str>) -> usize {
+        let found = order_id
+            .and_then(|id| self.by_order_id.get(id))
+            .or_else(|| client_order_id.and_then(|id| self.by_client_order_id.get(id)))
+            .copied();
+        let index = found.unwrap_or_else(|| {
+            self.timelines.push(OrderTimeline {
+                order_ids: Vec::new(),
+                client_order_id: None,
+                symbol: String::new(),
+                events: Vec::new(),
+            });
+            self.timelines.len() - 1
+        });
+        if let Some(id) = order_id {
+            self.link(index, id);
+        }
+        if let Some(client_order_id) = client_order_id {
+            self.by_client_order_id
+                .insert(client_order_id.to_string(), index);
+            let timeline = &mut self.timelines[index];
+            if timeline.client_order_id.is_none() {
+                timeline.client_order_id = Some(client_order_id.to_string());
+            }
+        }
+        index
+    }
+
+    fn link(&mut self, index: usize, order_id: &str) {
+        if self
+            .by_order_id
+            .insert(order_id.to_string(), index)
+            .is_none()
+        {
+            self.timelines[index].order_ids.push(order_id.to_string());
+        }
+    }
+
+    fn push(
+        &mut self,
+        index: usize,
+        record: &JournalRecord,
+        action: TimelineAction,
+        order_id: Option<&str>,
+        order: Option<&serde_json::Value>,
+    ) {
+        let timeline = &mut self.timelines[index];
+        if timeline.symbol.is_empty()
+            && let Some(symbol) = order.and_then(|o| text(o, "symbol"))
+        {
+            timeline.symbol = symbol.to_string();
+        }
+        timeline.events.push(TimelineEvent {
+            sent_at: record.sent_at.clone(),
+            action,
+            order_id: order_id.map(str::to_string),
+            http_status: record.status,
+            order_status: order.and_then(|o| text(o, "status")).map(str::to_string),
+            latency_ms: record.latency_ms,
+            error: record.error.clone(),
+        });
+    }
+
+    /// An event whose response, when successful, is the order.
+    fn order_event(
+        &mut self,
+        record: &JournalRecord,
+        action: TimelineAction,
+        order: Option<&serde_json::Value>,
+        fallback_id: Option<&str>,
+    ) {
+        let order = order.filter(|o| text(o, "id").is_some());
+        let order_id = order.and_then(|o| text(o, "id")).or(fallback_id);
+        let client_order_id = order
+            .or(record.request_body.as_ref())
+            .and_then(|o| text(o, "client_order_id"))
+            .or_else(|| record.query_param("client_order_id"));
+        let index = self.timeline(order_id, client_order_id);
+        let symbol_source = order.or(record.request_body.as_ref());
+        self.push(index, record, action, order_id, symbol_source);
+    }
+}
+
+fn text<'a>(value: &'a serde_json::Value, key: &str) -> Option<&'a str> {
+    value.get(key)?.as_str().filter(|s| !s.is_empty())
+}
+
+/// Group journal records by order, following replacements from the old order id to the new one.
+/// Records that are not about orders, such as account configuration changes, are left out.
+pub fn order_timelines(records: &[JournalRecord]) -> Vec<OrderTimeline> {
+    let mut builder = TimelineBuilder::default();
+    for record in records {
+        let route = record.route();
+        let response = record.response_body.as_ref();
+        match (record.method.as_str(), route.as_slice()) {
+            ("POST", ["orders"]) => {
+                builder.order_event(record, TimelineAction::Submit, response, None)
+            }
+            ("PATCH", ["orders", old_id]) => {
+                let old = builder.timeline(Some(old_id), None);
+                match response.and_then(|o| text(o, "id")) {
+                    Some(new_id) => {
+                        builder.link(old, new_id);
+                        builder.push(old, record, TimelineAction::Replace, Some(new_id), response);
+                    }
+                    None => builder.push(old, record, TimelineAction::Replace, Some(old_id), None),
+                }
+            }
+            ("DELETE", ["orders", id]) => {
+                let index = builder.timeline(Some(id), None);
+                builder.push(index, record, TimelineAction::Cancel, Some(id), None);
+            }
+            ("DELETE", ["orders"]) => {
+                for canceled in response.and_then(|r| r.as_array()).into_iter().flatten() {
+                    if let Some(id) = text(canceled, "id") {
+                        let index = builder.timeline(Some(id), None);
+                        builder.push(index, record, TimelineAction::Cancel, Some(id), None);
+                    }
+                }
+            }
+            ("DELETE", ["positions"]) => {
+                for closed in response.and_then(|r| r.as_array()).into_iter().flatten() {
+                    builder.order_event(record, TimelineAction::Close, closed.get("body"), None);
+                }
+            }
+            ("DELETE", ["positions", _]) => {
+                builder.order_event(record, TimelineAction::Close, response, None)
+            }
+            ("GET", ["orders", id]) => {
+                builder.order_event(record, TimelineAction::Fetch, response, Some(id))
+            }
+            ("GET", ["orders:by_client_order_id"]) => {
+                builder.order_event(record, TimelineAction::Fetch, response, None)
+            }
+            _ => {}
+        }
+    }
+    builder.timelines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(
+        method: &str,
+        path: &str,
+        request_body: Option<serde_json::Value>,
+        status: u16,
+        response_body: serde_json::Value,
+    ) -> JournalRecord {
+        JournalRecord {
+            sent_at: "2025-03-20T14:00:00.000000Z".to_string(),
+            received_at: "2025-03-20T14:00:00.120000Z".to_string(),
+            latency_ms: 120.0,
+            method: method.to_string(),
+            url: format!("https://paper-api.alpaca.markets/v2/{}", path),
+            request_headers: BTreeMap::new(),
+            request_body,
+            status: Some(status),
+            response_body: Some(response_body),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_order_timelines() {
+        let order = |id: &str, status: &str| serde_json::json!({"id": id, "client_order_id": "c1", "symbol": "AAPL", "status": status});
+        let records = vec![
+            record(
+                "POST",
+                "orders",
+                Some(serde_json::json!({"symbol": "AAPL", "client_order_id": "c1"})),
+                200,
+                order("o1", "accepted"),
+            ),
+            record("PATCH", "orders/o1", None, 200, order("o2", "accepted")),
+            record(
+                "GET",
+                "orders:by_client_order_id?client_order_id=c1",
+                None,
+                200,
+                order("o2", "new"),
+            ),
+            record("DELETE", "orders/o2", None, 204, serde_json::Value::Null),
+            record(
+                "POST",
+                "orders",
+                Some(serde_json::json!({"symbol": "MSFT", "client_order_id": "c2"})),
+                403,
+                serde_json::json!({"code": 40310000, "message": "insufficient buying power"}),
+            ),
+            record(
+                "PATCH",
+                "account/configurations",
+                None,
+                200,
+                serde_json::json!({}),
+            ),
+        ];
+
+        let timelines = order_timelines(&records);
+        assert_eq!(timelines.len(), 2);
+
+        let aapl = &timelines[0];
+        assert_eq!(aapl.order_ids, vec!["o1", "o2"]);
+        assert_eq!(aapl.client_order_id.as_deref(), Some("c1"));
+        assert_eq!(aapl.symbol, "AAPL");
+        let actions: Vec<_> = aapl.events.iter().map(|e| e.action).collect();
+        assert_eq!(
+            actions,
+            vec![
+                TimelineAction::Submit,
+                TimelineAction::Replace,
+                TimelineAction::Fetch,
+                TimelineAction::Cancel
+            ]
+        );
+        assert_eq!(aapl.events[2].order_status.as_deref(), Some("new"));
+        assert_eq!(aapl.events[3].order_id.as_deref(), Some("o2"));
+
+        let rejected = &timelines[1];
+        assert!(rejected.order_ids.is_empty());
+        assert_eq!(
+            (rejected.symbol.as_str(), rejected.events[0].http_status),
+            ("MSFT", Some(403))
+        );
+    }
+
+    #[test]
+    fn test_journal_rotation_and_read() {
+        let dir = std::env::temp_dir().join(format!("apca-journal-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("audit.jsonl");
+
+        let mut writer = JournalWriter {
+            settings: Journal::new(&path).max_file_bytes(600).max_files(2),
+            file: {
+                fs::create_dir_all(&dir).unwrap();
+                File::create(&path).unwrap()
+            },
+            size: 0,
+            error: None,
+        };
+        for i in 0..8 {
+            let id = format!("o{}", i);
+            let line = record(
+                "DELETE",
+                &format!("orders/{}", id),
+                None,
+                204,
+                serde_json::Value::Null,
+            );
+            writer.append(&line).unwrap();
+        }
+        assert!(rotated_path(&path, 2).exists());
+        assert!(!rotated_path(&path, 3).exists());
+
+        // The oldest records were rotated out, the rest read back in order
+        let records = read_journal(&path).unwrap();
+        assert!(records.len() < 8);
+        assert!(records.last().unwrap().url.ends_with("orders/o7"));
+        let ids: Vec<_> = records
+            .iter()
+            .map(|r| r.url.rsplit('/').next().unwrap())
+            .collect();
+        let mut sorted = ids.clone();
+        sorted.sort();
+        assert_eq!(ids, sorted);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
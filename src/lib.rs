@@ -1,5 +1,7 @@
 use dotenvy::dotenv;
+use std::sync::OnceLock;
 
+pub mod indicators;
 pub mod market_data;
 pub mod trading;
 
@@ -14,6 +16,18 @@ pub use stream::*;
 
 mod serde;
 
+pub mod journal;
+
+mod dry_run;
+pub use dry_run::{DryRunRequest, is_dry_run, set_dry_run, set_dry_run_logger};
+
+mod time;
+
+mod instrument;
+
+#[cfg(feature = "arrow")]
+pub mod arrow;
+
 pub(crate) fn get_auth() -> (String, String) {
     dotenv().ok();
     let id_key = std::env::var("APCA_API_KEY_ID").expect("API Id Key Not Found");
@@ -21,13 +35,24 @@ pub(crate) fn get_auth() -> (String, String) {
     (id_key, secret_key)
 }
 
+/// Shared by every request, so connections are reused and the audit journal sees trading calls.
+pub(crate) fn agent() -> &'static ureq::Agent {
+    static AGENT: OnceLock<ureq::Agent> = OnceLock::new();
+    AGENT.get_or_init(|| {
+        let config = ureq::Agent::config_builder().middleware(journal::middleware);
+        #[cfg(any(feature = "tracing", feature = "metrics"))]
+        let config = config.middleware(instrument::middleware);
+        config.build().new_agent()
+    })
+}
+
 /// For requests without a body (GET, DELETE)
 fn request(method: &str, address: &str) -> ureq::RequestBuilder<ureq::typestate::WithoutBody> {
     let (id_key, secret_key) = get_auth();
 
     match method {
-        "GET" => ureq::get(address),
-        "DELETE" => ureq::delete(address),
+        "GET" => agent().get(address),
+        "DELETE" => agent().delete(address),
         _ => panic!("Use json_request for methods with body: {}", method),
     }
     .header("APCA-API-KEY-ID", &id_key)
@@ -39,11 +64,16 @@ fn json_request(method: &str, address: &str) -> ureq::RequestBuilder<ureq::types
     let (id_key, secret_key) = get_auth();
 
     match method {
-        "POST" => ureq::post(address),
-        "PUT" => ureq::put(address),
-        "PATCH" => ureq::patch(address),
+        "POST" => agent().post(address),
+        "PUT" => agent().put(address),
+        "PATCH" => agent().patch(address),
         _ => panic!("Use request for methods without body: {}", method),
     }
     .header("APCA-API-KEY-ID", &id_key)
     .header("APCA-API-SECRET-KEY", &secret_key)
 }
+
+/// For callers that read error responses themselves: error statuses are returned as responses, not errors.
+fn raw_status<B>(request: ureq::RequestBuilder<B>) -> ureq::RequestBuilder<B> {
+    request.config().http_status_as_error(false).build()
+}
@@ -0,0 +1,651 @@
+use crate::request;
+use serde::Deserialize;
+use std::fmt;
+
+/// Corporate action types accepted by the `types` filter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CorporateActionType {
+    ForwardSplit,
+    ReverseSplit,
+    UnitSplit,
+    StockDividend,
+    CashDividend,
+    SpinOff,
+    CashMerger,
+    StockMerger,
+    StockAndCashMerger,
+    Redemption,
+    NameChange,
+    WorthlessRemoval,
+    RightsDistribution,
+}
+
+impl fmt::Display for CorporateActionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action_type = match self {
+            CorporateActionType::ForwardSplit => "forward_split",
+            CorporateActionType::ReverseSplit => "reverse_split",
+            CorporateActionType::UnitSplit => "unit_split",
+            CorporateActionType::StockDividend => "stock_dividend",
+            CorporateActionType::CashDividend => "cash_dividend",
+            CorporateActionType::SpinOff => "spin_off",
+            CorporateActionType::CashMerger => "cash_merger",
+            CorporateActionType::StockMerger => "stock_merger",
+            CorporateActionType::StockAndCashMerger => "stock_and_cash_merger",
+            CorporateActionType::Redemption => "redemption",
+            CorporateActionType::NameChange => "name_change",
+            CorporateActionType::WorthlessRemoval => "worthless_removal",
+            CorporateActionType::RightsDistribution => "rights_distribution",
+        };
+        write!(f, "{}", action_type)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ForwardSplit {
+    pub id: String,
+    pub symbol: String,
+    pub cusip: Option<String>,
+    pub new_rate: f64,
+    pub old_rate: f64,
+    pub process_date: String,
+    pub ex_date: Option<String>,
+    pub record_date: Option<String>,
+    pub payable_date: Option<String>,
+    pub due_bill_redemption_date: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ReverseSplit {
+    pub id: String,
+    pub symbol: String,
+    pub old_cusip: Option<String>,
+    pub new_cusip: Option<String>,
+    pub new_rate: f64,
+    pub old_rate: f64,
+    pub process_date: String,
+    pub ex_date: Option<String>,
+    pub record_date: Option<String>,
+    pub payable_date: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct UnitSplit {
+    pub id: String,
+    pub old_symbol: String,
+    pub old_cusip: Option<String>,
+    pub old_rate: f64,
+    pub new_symbol: String,
+    pub new_cusip: Option<String>,
+    pub new_rate: f64,
+    pub alternate_symbol: Option<String>,
+    pub alternate_cusip: Option<String>,
+    pub alternate_rate: Option<f64>,
+    pub process_date: String,
+    pub effective_date: Option<String>,
+    pub payable_date: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct StockDividend {
+    pub id: String,
+    pub symbol: String,
+    pub cusip: Option<String>,
+    pub rate: f64,
+    pub process_date: String,
+    pub ex_date: Option<String>,
+    pub record_date: Option<String>,
+    pub payable_date: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CashDividend {
+    pub id: String,
+    pub symbol: String,
+    pub cusip: Option<String>,
+    pub rate: f64,
+    pub special: Option<bool>,
+    pub foreign: Option<bool>,
+    pub process_date: String,
+    pub ex_date: Option<String>,
+    pub record_date: Option<String>,
+    pub payable_date: Option<String>,
+    pub due_bill_on_date: Option<String>,
+    pub due_bill_off_date: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SpinOff {
+    pub id: String,
+    pub source_symbol: String,
+    pub source_cusip: Option<String>,
+    pub source_rate: f64,
+    pub new_symbol: String,
+    pub new_cusip: Option<String>,
+    pub new_rate: f64,
+    pub process_date: String,
+    pub ex_date: Option<String>,
+    pub record_date: Option<String>,
+    pub payable_date: Option<String>,
+    pub due_bill_redemption_date: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CashMerger {
+    pub id: String,
+    pub acquirer_symbol: Option<String>,
+    pub acquirer_cusip: Option<String>,
+    pub acquiree_symbol: String,
+    pub acquiree_cusip: Option<String>,
+    pub rate: f64,
+    pub process_date: String,
+    pub effective_date: Option<String>,
+    pub payable_date: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct StockMerger {
+    pub id: String,
+    pub acquirer_symbol: String,
+    pub acquirer_cusip: Option<String>,
+    pub acquirer_rate: f64,
+    pub acquiree_symbol: String,
+    pub acquiree_cusip: Option<String>,
+    pub acquiree_rate: f64,
+    pub process_date: String,
+    pub effective_date: Option<String>,
+    pub payable_date: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct StockAndCashMerger {
+    pub id: String,
+    pub acquirer_symbol: String,
+    pub acquirer_cusip: Option<String>,
+    pub acquirer_rate: f64,
+    pub acquiree_symbol: String,
+    pub acquiree_cusip: Option<String>,
+    pub acquiree_rate: f64,
+    pub cash_rate: f64,
+    pub process_date: String,
+    pub effective_date: Option<String>,
+    pub payable_date: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Redemption {
+    pub id: String,
+    pub symbol: String,
+    pub cusip: Option<String>,
+    pub rate: f64,
+    pub process_date: String,
+    pub payable_date: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct NameChange {
+    pub id: String,
+    pub old_symbol: String,
+    pub old_cusip: Option<String>,
+    pub new_symbol: String,
+    pub new_cusip: Option<String>,
+    pub process_date: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct WorthlessRemoval {
+    pub id: String,
+    pub symbol: String,
+    pub cusip: Option<String>,
+    pub process_date: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct RightsDistribution {
+    pub id: String,
+    pub source_symbol: String,
+    pub source_cusip: Option<String>,
+    pub new_symbol: String,
+    pub new_cusip: Option<String>,
+    pub rate: f64,
+    pub process_date: String,
+    pub ex_date: Option<String>,
+    pub record_date: Option<String>,
+    pub payable_date: Option<String>,
+    pub expiration_date: Option<String>,
+}
+
+/// Corporate actions grouped by type, as returned by the API.
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct CorporateActions {
+    pub forward_splits: Vec<ForwardSplit>,
+    pub reverse_splits: Vec<ReverseSplit>,
+    pub unit_splits: Vec<UnitSplit>,
+    pub stock_dividends: Vec<StockDividend>,
+    pub cash_dividends: Vec<CashDividend>,
+    pub spin_offs: Vec<SpinOff>,
+    pub cash_mergers: Vec<CashMerger>,
+    pub stock_mergers: Vec<StockMerger>,
+    pub stock_and_cash_mergers: Vec<StockAndCashMerger>,
+    pub redemptions: Vec<Redemption>,
+    pub name_changes: Vec<NameChange>,
+    pub worthless_removals: Vec<WorthlessRemoval>,
+    pub rights_distributions: Vec<RightsDistribution>,
+}
+
+/// A single corporate action of any type.
+#[derive(Debug, Clone)]
+pub enum CorporateAction {
+    ForwardSplit(ForwardSplit),
+    ReverseSplit(ReverseSplit),
+    UnitSplit(UnitSplit),
+    StockDividend(StockDividend),
+    CashDividend(CashDividend),
+    SpinOff(SpinOff),
+    CashMerger(CashMerger),
+    StockMerger(StockMerger),
+    StockAndCashMerger(StockAndCashMerger),
+    Redemption(Redemption),
+    NameChange(NameChange),
+    WorthlessRemoval(WorthlessRemoval),
+    RightsDistribution(RightsDistribution),
+}
+
+impl CorporateAction {
+    pub fn id(&self) -> &str {
+        match self {
+            CorporateAction::ForwardSplit(a) => &a.id,
+            CorporateAction::ReverseSplit(a) => &a.id,
+            CorporateAction::UnitSplit(a) => &a.id,
+            CorporateAction::StockDividend(a) => &a.id,
+            CorporateAction::CashDividend(a) => &a.id,
+            CorporateAction::SpinOff(a) => &a.id,
+            CorporateAction::CashMerger(a) => &a.id,
+            CorporateAction::StockMerger(a) => &a.id,
+            CorporateAction::StockAndCashMerger(a) => &a.id,
+            CorporateAction::Redemption(a) => &a.id,
+            CorporateAction::NameChange(a) => &a.id,
+            CorporateAction::WorthlessRemoval(a) => &a.id,
+            CorporateAction::RightsDistribution(a) => &a.id,
+        }
+    }
+
+    pub fn action_type(&self) -> CorporateActionType {
+        match self {
+            CorporateAction::ForwardSplit(_) => CorporateActionType::ForwardSplit,
+            CorporateAction::ReverseSplit(_) => CorporateActionType::ReverseSplit,
+            CorporateAction::UnitSplit(_) => CorporateActionType::UnitSplit,
+            CorporateAction::StockDividend(_) => CorporateActionType::StockDividend,
+            CorporateAction::CashDividend(_) => CorporateActionType::CashDividend,
+            CorporateAction::SpinOff(_) => CorporateActionType::SpinOff,
+            CorporateAction::CashMerger(_) => CorporateActionType::CashMerger,
+            CorporateAction::StockMerger(_) => CorporateActionType::StockMerger,
+            CorporateAction::StockAndCashMerger(_) => CorporateActionType::StockAndCashMerger,
+            CorporateAction::Redemption(_) => CorporateActionType::Redemption,
+            CorporateAction::NameChange(_) => CorporateActionType::NameChange,
+            CorporateAction::WorthlessRemoval(_) => CorporateActionType::WorthlessRemoval,
+            CorporateAction::RightsDistribution(_) => CorporateActionType::RightsDistribution,
+        }
+    }
+
+    /// Date the action was processed, which is when holdings are adjusted.
+    pub fn process_date(&self) -> &str {
+        match self {
+            CorporateAction::ForwardSplit(a) => &a.process_date,
+            CorporateAction::ReverseSplit(a) => &a.process_date,
+            CorporateAction::UnitSplit(a) => &a.process_date,
+            CorporateAction::StockDividend(a) => &a.process_date,
+            CorporateAction::CashDividend(a) => &a.process_date,
+            CorporateAction::SpinOff(a) => &a.process_date,
+            CorporateAction::CashMerger(a) => &a.process_date,
+            CorporateAction::StockMerger(a) => &a.process_date,
+            CorporateAction::StockAndCashMerger(a) => &a.process_date,
+            CorporateAction::Redemption(a) => &a.process_date,
+            CorporateAction::NameChange(a) => &a.process_date,
+            CorporateAction::WorthlessRemoval(a) => &a.process_date,
+            CorporateAction::RightsDistribution(a) => &a.process_date,
+        }
+    }
+
+    /// Every symbol involved in the action, e.g. both sides of a merger.
+    pub fn symbols(&self) -> Vec<&str> {
+        match self {
+            CorporateAction::ForwardSplit(a) => vec![&a.symbol],
+            CorporateAction::ReverseSplit(a) => vec![&a.symbol],
+            CorporateAction::UnitSplit(a) => {
+                let mut symbols = vec![a.old_symbol.as_str(), a.new_symbol.as_str()];
+                symbols.extend(a.alternate_symbol.as_deref());
+                symbols
+            }
+            CorporateAction::StockDividend(a) => vec![&a.symbol],
+            CorporateAction::CashDividend(a) => vec![&a.symbol],
+            CorporateAction::SpinOff(a) => vec![&a.source_symbol, &a.new_symbol],
+            CorporateAction::CashMerger(a) => {
+                let mut symbols = vec![a.acquiree_symbol.as_str()];
+                symbols.extend(a.acquirer_symbol.as_deref());
+                symbols
+            }
+            CorporateAction::StockMerger(a) => vec![&a.acquirer_symbol, &a.acquiree_symbol],
+            CorporateAction::StockAndCashMerger(a) => {
+                vec![&a.acquirer_symbol, &a.acquiree_symbol]
+            }
+            CorporateAction::Redemption(a) => vec![&a.symbol],
+            CorporateAction::NameChange(a) => vec![&a.old_symbol, &a.new_symbol],
+            CorporateAction::WorthlessRemoval(a) => vec![&a.symbol],
+            CorporateAction::RightsDistribution(a) => vec![&a.source_symbol, &a.new_symbol],
+        }
+    }
+}
+
+impl CorporateActions {
+    pub fn len(&self) -> usize {
+        self.forward_splits.len()
+            + self.reverse_splits.len()
+            + self.unit_splits.len()
+            + self.stock_dividends.len()
+            + self.cash_dividends.len()
+            + self.spin_offs.len()
+            + self.cash_mergers.len()
+            + self.stock_mergers.len()
+            + self.stock_and_cash_mergers.len()
+            + self.redemptions.len()
+            + self.name_changes.len()
+            + self.worthless_removals.len()
+            + self.rights_distributions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Append the actions of another page.
+    pub fn extend(&mut self, other: CorporateActions) {
+        self.forward_splits.extend(other.forward_splits);
+        self.reverse_splits.extend(other.reverse_splits);
+        self.unit_splits.extend(other.unit_splits);
+        self.stock_dividends.extend(other.stock_dividends);
+        self.cash_dividends.extend(other.cash_dividends);
+        self.spin_offs.extend(other.spin_offs);
+        self.cash_mergers.extend(other.cash_mergers);
+        self.stock_mergers.extend(other.stock_mergers);
+        self.stock_and_cash_mergers
+            .extend(other.stock_and_cash_mergers);
+        self.redemptions.extend(other.redemptions);
+        self.name_changes.extend(other.name_changes);
+        self.worthless_removals.extend(other.worthless_removals);
+        self.rights_distributions.extend(other.rights_distributions);
+    }
+
+    /// Flatten into a single list ordered by process date.
+    pub fn into_actions(self) -> Vec<CorporateAction> {
+        let mut actions: Vec<CorporateAction> = Vec::with_capacity(self.len());
+        actions.extend(
+            self.forward_splits
+                .into_iter()
+                .map(CorporateAction::ForwardSplit),
+        );
+        actions.extend(
+            self.reverse_splits
+                .into_iter()
+                .map(CorporateAction::ReverseSplit),
+        );
+        actions.extend(self.unit_splits.into_iter().map(CorporateAction::UnitSplit));
+        actions.extend(
+            self.stock_dividends
+                .into_iter()
+                .map(CorporateAction::StockDividend),
+        );
+        actions.extend(
+            self.cash_dividends
+                .into_iter()
+                .map(CorporateAction::CashDividend),
+        );
+        actions.extend(self.spin_offs.into_iter().map(CorporateAction::SpinOff));
+        actions.extend(
+            self.cash_mergers
+                .into_iter()
+                .map(CorporateAction::CashMerger),
+        );
+        actions.extend(
+            self.stock_mergers
+                .into_iter()
+                .map(CorporateAction::StockMerger),
+        );
+        actions.extend(
+            self.stock_and_cash_mergers
+                .into_iter()
+                .map(CorporateAction::StockAndCashMerger),
+        );
+        actions.extend(
+            self.redemptions
+                .into_iter()
+                .map(CorporateAction::Redemption),
+        );
+        actions.extend(
+            self.name_changes
+                .into_iter()
+                .map(CorporateAction::NameChange),
+        );
+        actions.extend(
+            self.worthless_removals
+                .into_iter()
+                .map(CorporateAction::WorthlessRemoval),
+        );
+        actions.extend(
+            self.rights_distributions
+                .into_iter()
+                .map(CorporateAction::RightsDistribution),
+        );
+        actions.sort_by(|a, b| a.process_date().cmp(b.process_date()));
+        actions
+    }
+
+    /// All actions that involve the given symbol, ordered by process date.
+    pub fn for_symbol(&self, symbol: &str) -> Vec<CorporateAction> {
+        self.clone()
+            .into_actions()
+            .into_iter()
+            .filter(|action| action.symbols().contains(&symbol))
+            .collect()
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CorporateActionsResponse {
+    pub corporate_actions: CorporateActions,
+    pub next_page_token: Option<String>,
+}
+
+pub struct CorporateActionsQuery<'a> {
+    url: &'a str,
+    symbols: Vec<&'a str>,
+    cusips: Option<Vec<&'a str>>,
+    types: Option<Vec<CorporateActionType>>,
+    ids: Option<Vec<&'a str>>,
+    start: Option<&'a str>,
+    end: Option<&'a str>,
+    limit: Option<usize>,
+    page_token: Option<&'a str>,
+    sort_asc: bool,
+    sort_desc: bool,
+}
+
+impl<'a> CorporateActionsQuery<'a> {
+    pub fn new(symbols: Vec<&'a str>) -> Self {
+        Self {
+            url: "https://data.alpaca.markets/v1/corporate-actions",
+            symbols,
+            cusips: None,
+            types: None,
+            ids: None,
+            start: None,
+            end: None,
+            limit: None,
+            page_token: None,
+            sort_asc: false,
+            sort_desc: false,
+        }
+    }
+
+    pub fn cusips(mut self, cusips: Vec<&'a str>) -> Self {
+        self.cusips = Some(cusips);
+        self
+    }
+
+    pub fn types(mut self, types: Vec<CorporateActionType>) -> Self {
+        self.types = Some(types);
+        self
+    }
+
+    /// Look up specific actions by id. Cannot be combined with the other filters.
+    pub fn ids(mut self, ids: Vec<&'a str>) -> Self {
+        self.ids = Some(ids);
+        self
+    }
+
+    /// Inclusive start of the process date range (YYYY-MM-DD).
+    pub fn start(mut self, start: &'a str) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// Inclusive end of the process date range (YYYY-MM-DD).
+    pub fn end(mut self, end: &'a str) -> Self {
+        self.end = Some(end);
+        self
+    }
+
+    /// Maximum number of actions returned in total, across all pages.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Resume from a `next_page_token` returned by an earlier request.
+    pub fn page_token(mut self, page_token: &'a str) -> Self {
+        self.page_token = Some(page_token);
+        self
+    }
+
+    pub fn sort_asc(mut self) -> Self {
+        self.sort_asc = true;
+        self.sort_desc = false;
+        self
+    }
+
+    pub fn sort_desc(mut self) -> Self {
+        self.sort_desc = true;
+        self.sort_asc = false;
+        self
+    }
+
+    fn build(&self) -> String {
+        let mut query = String::new();
+        if !self.symbols.is_empty() {
+            query.push_str(&format!("&symbols={}", self.symbols.join(",")));
+        }
+        if let Some(cusips) = &self.cusips {
+            query.push_str(&format!("&cusips={}", cusips.join(",")));
+        }
+        if let Some(types) = &self.types {
+            let types: Vec<String> = types.iter().map(|t| t.to_string()).collect();
+            query.push_str(&format!("&types={}", types.join(",")));
+        }
+        if let Some(ids) = &self.ids {
+            query.push_str(&format!("&ids={}", ids.join(",")));
+        }
+        if let Some(start) = self.start {
+            query.push_str(&format!("&start={}", start));
+        }
+        if let Some(end) = self.end {
+            query.push_str(&format!("&end={}", end));
+        }
+        if self.sort_asc {
+            query.push_str("&sort=asc");
+        } else if self.sort_desc {
+            query.push_str("&sort=desc");
+        }
+
+        format!("{}?{}", self.url, query)
+    }
+
+    pub fn send(&self) -> Result<CorporateActions, ureq::Error> {
+        let route = self.build();
+        let mut corporate_actions = CorporateActions::default();
+        let mut page_token = self.page_token.map(String::from);
+        let mut page = 0;
+
+        loop {
+            let mut temp_address = route.clone();
+            // Only ask for what is left of the limit, so the total never exceeds it
+            if let Some(limit) = self.limit {
+                let remaining = limit - corporate_actions.len();
+                temp_address.push_str(&format!("&limit={}", remaining.min(1000)));
+            }
+            // If a token exists, append to address
+            if let Some(token) = &page_token {
+                temp_address.push_str(&format!("&page_token={}", token));
+            }
+            page += 1;
+            let _page = crate::instrument::page_span(page);
+            let response = request("GET", &temp_address).call()?;
+            let response: CorporateActionsResponse = response.into_body().read_json()?;
+
+            corporate_actions.extend(response.corporate_actions);
+
+            if self
+                .limit
+                .is_some_and(|limit| corporate_actions.len() >= limit)
+            {
+                break;
+            }
+
+            // If a token is in response, assign to page_token for next loop
+            match response.next_page_token {
+                Some(next_page_token) => page_token = Some(next_page_token),
+                _ => break,
+            }
+        }
+
+        Ok(corporate_actions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_corporate_actions() {
+        let json = r#"{
+            "corporate_actions": {
+                "forward_splits": [{"id":"a1","symbol":"NVDA","cusip":"67066G104","new_rate":10,"old_rate":1,"process_date":"2024-06-10","ex_date":"2024-06-10","record_date":"2024-06-06","payable_date":"2024-06-07"}],
+                "cash_dividends": [{"id":"a2","symbol":"AAPL","cusip":"037833100","rate":0.25,"special":false,"foreign":false,"process_date":"2024-05-16","ex_date":"2024-05-10","record_date":"2024-05-13","payable_date":"2024-05-16"}],
+                "name_changes": [{"id":"a3","old_symbol":"FB","old_cusip":"30303M102","new_symbol":"META","new_cusip":"30303M102","process_date":"2022-06-09"}]
+            },
+            "next_page_token": null
+        }"#;
+        let response: CorporateActionsResponse = serde_json::from_str(json).unwrap();
+        let actions = response.corporate_actions;
+        assert_eq!(actions.len(), 3);
+        assert_eq!(actions.for_symbol("META").len(), 1);
+
+        let flat = actions.into_actions();
+        assert_eq!(flat[0].action_type(), CorporateActionType::NameChange);
+        assert_eq!(flat[2].id(), "a1");
+    }
+
+    #[test]
+    fn test_corporate_actions_query() {
+        let res = CorporateActionsQuery::new(vec!["AAPL"])
+            .types(vec![CorporateActionType::CashDividend])
+            .start("2024-01-01")
+            .end("2024-12-31")
+            .send()
+            .unwrap();
+
+        dbg!(&res);
+        assert!(!res.cash_dividends.is_empty());
+    }
+}
@@ -93,6 +93,7 @@ impl<'a> HistoricalCryptoBarsQuery<'a> {
         let route = self.build();
         let mut multi_bars: HistoricalBars = HashMap::new();
         let mut page_token = None;
+        let mut page = 0;
 
         loop {
             // If a token exists, append to address
@@ -100,6 +101,8 @@ impl<'a> HistoricalCryptoBarsQuery<'a> {
                 Some(token) => format!("{}&page_token={}", &route, &token),
                 _ => route.clone(),
             };
+            page += 1;
+            let _page = crate::instrument::page_span(page);
             let response = request("GET", &temp_address).call()?;
             let response: HistoricalBarsResponse = response.into_body().read_json()?;
 
@@ -115,6 +115,7 @@ impl<'a> HistoricalCryptoQuotesQuery<'a> {
             1000
         };
 
+        let mut page = 0;
         loop {
             if i >= data_limit {
                 break;
@@ -124,6 +125,8 @@ impl<'a> HistoricalCryptoQuotesQuery<'a> {
                 Some(token) => format!("{}&page_token={}", &route, &token),
                 _ => route.clone(),
             };
+            page += 1;
+            let _page = crate::instrument::page_span(page);
             let response = request("GET", &temp_address).call()?;
             let response: HistoricalCryptoQuoteResponse = response.into_body().read_json()?;
 
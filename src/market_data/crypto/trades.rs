@@ -115,6 +115,7 @@ impl<'a> HistoricalCryptoTradesQuery<'a> {
         } else {
             1000
         };
+        let mut page = 0;
         loop {
             if i >= data_limit {
                 break;
@@ -125,6 +126,8 @@ impl<'a> HistoricalCryptoTradesQuery<'a> {
                 Some(token) => format!("{}&page_token={}", &route, &token),
                 _ => route.clone(),
             };
+            page += 1;
+            let _page = crate::instrument::page_span(page);
             let response = request("GET", &temp_address).call()?;
             let response: HistoricalCryptoTradesResponse = response.into_body().read_json()?;
 
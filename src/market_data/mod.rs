@@ -1,3 +1,4 @@
+pub mod corporate_actions;
 pub mod crypto;
 pub mod news;
 pub mod options;
@@ -131,6 +131,7 @@ impl<'a> NewsQuery<'a> {
         } else {
             50
         };
+        let mut page = 0;
         loop {
             if i >= data_limit {
                 break;
@@ -141,6 +142,8 @@ impl<'a> NewsQuery<'a> {
                 Some(token) => format!("{}&page_token={}", &route, &token),
                 _ => route.clone(),
             };
+            page += 1;
+            let _page = crate::instrument::page_span(page);
             let response = request("GET", &temp_address).call()?;
             let response: NewsResponse = response.into_body().read_json()?;
             i += response.news.len() as i32;
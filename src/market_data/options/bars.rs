@@ -6,7 +6,7 @@ use std::collections::HashMap;
 
 pub struct HistoricalOptionBarsQuery<'a> {
     url: &'a str,
-    symbols: Vec<&'a str>,
+    symbols: Vec<String>,
     timeframe: TimeFrame,
     start: Option<&'a str>,
     end: Option<&'a str>,
@@ -16,10 +16,11 @@ pub struct HistoricalOptionBarsQuery<'a> {
 }
 
 impl<'a> HistoricalOptionBarsQuery<'a> {
-    pub fn new(symbols: Vec<&'a str>, timeframe: TimeFrame) -> Self {
+    /// `symbols` can be OCC strings or [`OptionSymbol`](super::OptionSymbol)s.
+    pub fn new<S: ToString>(symbols: Vec<S>, timeframe: TimeFrame) -> Self {
         Self {
             url: "https://data.alpaca.markets/v1beta1/options/bars",
-            symbols,
+            symbols: symbols.iter().map(|s| s.to_string()).collect(),
             timeframe,
             start: None,
             end: None,
@@ -88,6 +89,7 @@ impl<'a> HistoricalOptionBarsQuery<'a> {
         let route = self.build();
         let mut multi_bars: HistoricalBars = HashMap::new();
         let mut page_token = None;
+        let mut page = 0;
 
         loop {
             // If a token exists, append to address
@@ -95,6 +97,8 @@ impl<'a> HistoricalOptionBarsQuery<'a> {
                 Some(token) => format!("{}&page_token={}", &route, &token),
                 _ => route.clone(),
             };
+            page += 1;
+            let _page = crate::instrument::page_span(page);
             let response = request("GET", &temp_address).call()?;
             let response: HistoricalBarsResponse = response.into_body().read_json()?;
 
@@ -2,11 +2,16 @@ use super::snapshot::{OptionSnapshotResponse, OptionSnapshots};
 use crate::request;
 use std::collections::HashMap;
 
+/// Most snapshots the API returns per request.
+const MAX_PAGE_SIZE: i32 = 1000;
+
 pub struct OptionChainQuery<'a> {
     url: &'a str,
     underlying_symbol: &'a str,
     feed: Option<&'a str>,
     limit: Option<i32>,
+    page_size: Option<i32>,
+    all_pages: bool,
     updated_since: Option<&'a str>,
     r#type: Option<&'a str>,
     strike_price_gte: Option<f64>,
@@ -26,6 +31,8 @@ impl<'a> OptionChainQuery<'a> {
             underlying_symbol,
             feed: None,
             limit: None,
+            page_size: None,
+            all_pages: false,
             updated_since: None,
             r#type: None,
             strike_price_gte: None,
@@ -44,8 +51,23 @@ impl<'a> OptionChainQuery<'a> {
         self
     }
 
+    /// Maximum number of snapshots returned in total, across all pages. Defaults to 100.
     pub fn limit(mut self, limit: i32) -> Self {
         self.limit = Some(limit);
+        self.all_pages = false;
+        self
+    }
+
+    /// Number of snapshots requested per page, up to 1,000.
+    pub fn page_size(mut self, page_size: i32) -> Self {
+        self.page_size = Some(page_size);
+        self
+    }
+
+    /// Fetch every page of the chain, with no total limit.
+    pub fn all_pages(mut self) -> Self {
+        self.all_pages = true;
+        self.limit = None;
         self
     }
 
@@ -108,10 +130,6 @@ impl<'a> OptionChainQuery<'a> {
             query.push_str(&format!("&feed={}", feed));
         }
 
-        if let Some(limit) = self.limit {
-            query.push_str(&format!("&limit={}", limit));
-        }
-
         if let Some(updated_since) = self.updated_since {
             query.push_str(&format!("&updated_since={}", updated_since));
         }
@@ -157,34 +175,37 @@ impl<'a> OptionChainQuery<'a> {
         let route = self.build();
         let mut snapshots: OptionSnapshots = HashMap::new();
         let mut page_token = None;
-
-        let mut i = 0;
-        let data_limit = if let Some(limit) = self.limit {
-            limit
-        } else {
-            100
+        let total = match self.all_pages {
+            true => None,
+            false => Some(self.limit.unwrap_or(100)),
         };
+        let page_size = self.page_size.unwrap_or(MAX_PAGE_SIZE);
+        let mut received = 0;
+        let mut page = 0;
+
         loop {
-            if i >= data_limit {
-                break;
-            }
+            let remaining = total.map_or(page_size, |total| total - received);
+            let mut temp_address = format!("{}&limit={}", route, page_size.min(remaining));
             // If a token exists, append to address
-            let temp_address = match page_token {
-                Some(token) => format!("{}&page_token={}", &route, &token),
-                _ => route.clone(),
-            };
+            if let Some(token) = &page_token {
+                temp_address.push_str(&format!("&page_token={}", token));
+            }
+            page += 1;
+            let _page = crate::instrument::page_span(page);
             let response = request("GET", &temp_address).call()?;
             let response: OptionSnapshotResponse = response.into_body().read_json()?;
 
             // Add snapshots to collection
-            for (symbol, snapshot) in response.snapshots {
-                i += 1;
-                snapshots.insert(symbol, snapshot);
+            received += response.snapshots.len() as i32;
+            snapshots.extend(response.snapshots);
+
+            if total.is_some_and(|total| received >= total) {
+                break;
             }
 
             // If a token is in response, assign to page_token for next loop
             match response.next_page_token {
-                Some(next_page_token) => page_token = Some(next_page_token.clone()),
+                Some(next_page_token) => page_token = Some(next_page_token),
                 _ => break,
             }
         }
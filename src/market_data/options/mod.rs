@@ -4,6 +4,9 @@ pub use bars::*;
 mod chain;
 pub use chain::*;
 
+mod pricing;
+pub use pricing::*;
+
 mod quotes;
 pub use quotes::*;
 
@@ -12,3 +15,12 @@ pub use snapshot::*;
 
 mod trades;
 pub use trades::*;
+
+mod strategy;
+pub use strategy::*;
+
+mod symbol;
+pub use symbol::*;
+
+mod surface;
+pub use surface::*;
@@ -0,0 +1,504 @@
+use super::{
+    quotes::OptionQuote,
+    snapshot::{Greeks, OptionSnapshot},
+    symbol::{OptionSymbol, OptionType},
+};
+use crate::{
+    time::{new_york_nanos, parse_rfc3339_nanos},
+    trading::assets::OptionContract,
+};
+use std::{f64::consts::PI, str::FromStr};
+
+/// Steps in the binomial tree used for American options.
+const BINOMIAL_STEPS: usize = 200;
+
+/// Bounds of the implied volatility search.
+const MIN_VOLATILITY: f64 = 1e-4;
+const MAX_VOLATILITY: f64 = 5.0;
+
+/// Exercise style, from `OptionContract.style`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExerciseStyle {
+    /// Exercise at expiration only. Priced with Black-Scholes-Merton.
+    European,
+    /// Exercise at any time. Priced with a Cox-Ross-Rubinstein binomial tree.
+    American,
+}
+
+impl FromStr for ExerciseStyle {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "european" | "European" => Ok(ExerciseStyle::European),
+            "american" | "American" => Ok(ExerciseStyle::American),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Inputs to the pricing model. Rates, yields and volatility are annualized decimals, e.g. `0.05` for 5%.
+///
+/// Greeks follow the convention of the snapshot endpoint: theta per calendar day,
+/// vega and rho per one percentage point change in volatility and rate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PricingInputs {
+    pub option_type: OptionType,
+    pub style: ExerciseStyle,
+    pub spot: f64,
+    pub strike: f64,
+    /// Years until expiration.
+    pub time_to_expiry: f64,
+    pub rate: f64,
+    pub dividend_yield: f64,
+    pub volatility: f64,
+}
+
+impl PricingInputs {
+    /// A European option with no interest rate or dividend yield.
+    pub fn new(
+        option_type: OptionType,
+        spot: f64,
+        strike: f64,
+        time_to_expiry: f64,
+        volatility: f64,
+    ) -> Self {
+        Self {
+            option_type,
+            style: ExerciseStyle::European,
+            spot,
+            strike,
+            time_to_expiry,
+            rate: 0.0,
+            dividend_yield: 0.0,
+            volatility,
+        }
+    }
+
+    /// Inputs for a contract symbol as of `valuation_date`, see [`time_to_expiry`].
+    pub fn from_symbol(
+        symbol: &OptionSymbol,
+        spot: f64,
+        volatility: f64,
+        valuation_date: &str,
+    ) -> Option<Self> {
+        let time_to_expiry = time_to_expiry(valuation_date, &symbol.expiration())?;
+        Some(Self::new(
+            symbol.option_type(),
+            spot,
+            symbol.strike(),
+            time_to_expiry,
+            volatility,
+        ))
+    }
+
+    /// Inputs for a contract as of `valuation_date`, using its exercise style.
+    pub fn from_contract(
+        contract: &OptionContract,
+        spot: f64,
+        volatility: f64,
+        valuation_date: &str,
+    ) -> Option<Self> {
+        let option_type = match contract.r#type.as_str() {
+            "call" => OptionType::Call,
+            "put" => OptionType::Put,
+            _ => return None,
+        };
+        Some(Self {
+            style: contract.style.parse().ok()?,
+            ..Self::new(
+                option_type,
+                spot,
+                contract.strike_price.parse().ok()?,
+                time_to_expiry(valuation_date, &contract.expiration_date)?,
+                volatility,
+            )
+        })
+    }
+
+    pub fn style(mut self, style: ExerciseStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn rate(mut self, rate: f64) -> Self {
+        self.rate = rate;
+        self
+    }
+
+    pub fn dividend_yield(mut self, dividend_yield: f64) -> Self {
+        self.dividend_yield = dividend_yield;
+        self
+    }
+
+    pub fn spot(mut self, spot: f64) -> Self {
+        self.spot = spot;
+        self
+    }
+
+    pub fn volatility(mut self, volatility: f64) -> Self {
+        self.volatility = volatility;
+        self
+    }
+
+    pub fn time_to_expiry(mut self, time_to_expiry: f64) -> Self {
+        self.time_to_expiry = time_to_expiry;
+        self
+    }
+
+    /// Theoretical price per share.
+    pub fn price(&self) -> f64 {
+        if self.is_expired() {
+            return self.intrinsic();
+        }
+        match self.style {
+            ExerciseStyle::European => self.black_scholes().price,
+            ExerciseStyle::American => self.binomial().price,
+        }
+    }
+
+    pub fn greeks(&self) -> Greeks {
+        if self.is_expired() {
+            let itm = self.intrinsic() > 0.0;
+            let delta = match (self.option_type, itm) {
+                (OptionType::Call, true) => 1.0,
+                (OptionType::Put, true) => -1.0,
+                _ => 0.0,
+            };
+            return Greeks {
+                delta,
+                gamma: 0.0,
+                rho: 0.0,
+                theta: 0.0,
+                vega: 0.0,
+            };
+        }
+
+        match self.style {
+            ExerciseStyle::European => self.black_scholes().greeks,
+            ExerciseStyle::American => {
+                // Delta, gamma and theta from the first tree nodes, vega and rho by central differences
+                let tree = self.binomial();
+                let price = |inputs: Self| inputs.binomial().price;
+                let (v, r) = (self.volatility, self.rate);
+                Greeks {
+                    delta: tree.delta,
+                    gamma: tree.gamma,
+                    theta: tree.theta / 365.0,
+                    vega: (price(self.volatility(v + 0.01))
+                        - price(self.volatility((v - 0.01).max(MIN_VOLATILITY))))
+                        / (v + 0.01 - (v - 0.01).max(MIN_VOLATILITY))
+                        / 100.0,
+                    rho: (price(self.rate(r + 0.01)) - price(self.rate(r - 0.01))) / 2.0,
+                }
+            }
+        }
+    }
+
+    /// The volatility that reproduces `market_price`, or `None` if no volatility between 0.01% and 500% does.
+    pub fn implied_volatility(&self, market_price: f64) -> Option<f64> {
+        if !market_price.is_finite() || self.time_to_expiry <= 0.0 {
+            return None;
+        }
+        let price_at = |volatility: f64| self.volatility(volatility).price();
+
+        let (mut low, mut high) = (MIN_VOLATILITY, MAX_VOLATILITY);
+        if market_price < price_at(low) || market_price > price_at(high) {
+            return None;
+        }
+        for _ in 0..100 {
+            let mid = (low + high) / 2.0;
+            let diff = price_at(mid) - market_price;
+            if diff.abs() < 1e-8 || high - low < 1e-10 {
+                return Some(mid);
+            }
+            if diff > 0.0 {
+                high = mid;
+            } else {
+                low = mid;
+            }
+        }
+        Some((low + high) / 2.0)
+    }
+
+    /// Implied volatility from the quote midpoint.
+    pub fn implied_volatility_from_quote(&self, quote: &OptionQuote) -> Option<f64> {
+        self.implied_volatility(quote.mid()?)
+    }
+
+    /// Server side Greeks when the snapshot has them, otherwise Greeks computed from the snapshot's
+    /// implied volatility, or from the volatility implied by its latest quote.
+    pub fn resolve_greeks(&self, snapshot: &OptionSnapshot) -> Option<Greeks> {
+        if let Some(greeks) = snapshot.greeks {
+            return Some(greeks);
+        }
+        let volatility = match snapshot.implied_volatility {
+            Some(volatility) if volatility > 0.0 => volatility,
+            _ => self.implied_volatility_from_quote(snapshot.latest_quote.as_ref()?)?,
+        };
+        Some(self.volatility(volatility).greeks())
+    }
+
+    fn is_expired(&self) -> bool {
+        self.time_to_expiry <= 0.0 || self.volatility <= 0.0
+    }
+
+    fn intrinsic(&self) -> f64 {
+        payoff(self.option_type, self.spot, self.strike)
+    }
+
+    fn black_scholes(&self) -> Valuation {
+        let (s, k, t, r, q, v) = (
+            self.spot,
+            self.strike,
+            self.time_to_expiry,
+            self.rate,
+            self.dividend_yield,
+            self.volatility,
+        );
+        let sqrt_t = t.sqrt();
+        let d1 = ((s / k).ln() + (r - q + v * v / 2.0) * t) / (v * sqrt_t);
+        let d2 = d1 - v * sqrt_t;
+        let discount = (-r * t).exp();
+        let carry = (-q * t).exp();
+
+        let gamma = carry * norm_pdf(d1) / (s * v * sqrt_t);
+        let vega = s * carry * norm_pdf(d1) * sqrt_t / 100.0;
+        let decay = -s * carry * norm_pdf(d1) * v / (2.0 * sqrt_t);
+
+        let (price, delta, theta, rho) = match self.option_type {
+            OptionType::Call => (
+                s * carry * norm_cdf(d1) - k * discount * norm_cdf(d2),
+                carry * norm_cdf(d1),
+                decay - r * k * discount * norm_cdf(d2) + q * s * carry * norm_cdf(d1),
+                k * t * discount * norm_cdf(d2),
+            ),
+            OptionType::Put => (
+                k * discount * norm_cdf(-d2) - s * carry * norm_cdf(-d1),
+                carry * (norm_cdf(d1) - 1.0),
+                decay + r * k * discount * norm_cdf(-d2) - q * s * carry * norm_cdf(-d1),
+                -k * t * discount * norm_cdf(-d2),
+            ),
+        };
+
+        Valuation {
+            price,
+            greeks: Greeks {
+                delta,
+                gamma,
+                rho: rho / 100.0,
+                theta: theta / 365.0,
+                vega,
+            },
+        }
+    }
+
+    fn binomial(&self) -> Tree {
+        let dt = self.time_to_expiry / BINOMIAL_STEPS as f64;
+        let u = (self.volatility * dt.sqrt()).exp();
+        let d = 1.0 / u;
+        let growth = ((self.rate - self.dividend_yield) * dt).exp();
+        let p = ((growth - d) / (u - d)).clamp(0.0, 1.0);
+        let discount = (-self.rate * dt).exp();
+        let node_spot =
+            |step: usize, ups: usize| self.spot * u.powi(ups as i32) * d.powi((step - ups) as i32);
+
+        let mut values: Vec<f64> = (0..=BINOMIAL_STEPS)
+            .map(|ups| {
+                payoff(
+                    self.option_type,
+                    node_spot(BINOMIAL_STEPS, ups),
+                    self.strike,
+                )
+            })
+            .collect();
+        let mut step_two = [0.0; 3];
+        let mut step_one = [0.0; 2];
+
+        for step in (0..BINOMIAL_STEPS).rev() {
+            for ups in 0..=step {
+                let continuation = discount * (p * values[ups + 1] + (1.0 - p) * values[ups]);
+                let exercise = payoff(self.option_type, node_spot(step, ups), self.strike);
+                values[ups] = continuation.max(exercise);
+            }
+            match step {
+                2 => step_two.copy_from_slice(&values[..3]),
+                1 => step_one.copy_from_slice(&values[..2]),
+                _ => {}
+            }
+        }
+
+        let (su, sd) = (self.spot * u, self.spot * d);
+        let (suu, sdd) = (self.spot * u * u, self.spot * d * d);
+        let delta = (step_one[1] - step_one[0]) / (su - sd);
+        let gamma = ((step_two[2] - step_two[1]) / (suu - self.spot)
+            - (step_two[1] - step_two[0]) / (self.spot - sdd))
+            / ((suu - sdd) / 2.0);
+        let theta = (step_two[1] - values[0]) / (2.0 * dt);
+
+        Tree {
+            price: values[0],
+            delta,
+            gamma,
+            theta,
+        }
+    }
+}
+
+struct Valuation {
+    price: f64,
+    greeks: Greeks,
+}
+
+struct Tree {
+    price: f64,
+    delta: f64,
+    gamma: f64,
+    /// Per year
+    theta: f64,
+}
+
+/// Years from `valuation_date` to the 16:00 New York close on `expiration` (YYYY-MM-DD), on a 365 day
+/// basis. `valuation_date` is an RFC 3339 timestamp, or a YYYY-MM-DD date valued at the 09:30 open.
+pub fn time_to_expiry(valuation_date: &str, expiration: &str) -> Option<f64> {
+    let valued_at = match parse_rfc3339_nanos(valuation_date) {
+        Some(nanos) => nanos,
+        None if valuation_date.len() == 10 => new_york_nanos(valuation_date, 9, 30)?,
+        None => return None,
+    };
+    let expires_at = new_york_nanos(expiration.get(..10)?, 16, 0)?;
+    Some((expires_at - valued_at).max(0) as f64 / 1e9 / (365.0 * 86_400.0))
+}
+
+/// Standard normal cumulative distribution function.
+pub fn norm_cdf(x: f64) -> f64 {
+    0.5 * erfc(-x / std::f64::consts::SQRT_2)
+}
+
+/// Standard normal probability density function.
+pub fn norm_pdf(x: f64) -> f64 {
+    (-x * x / 2.0).exp() / (2.0 * PI).sqrt()
+}
+
+fn payoff(option_type: OptionType, spot: f64, strike: f64) -> f64 {
+    match option_type {
+        OptionType::Call => (spot - strike).max(0.0),
+        OptionType::Put => (strike - spot).max(0.0),
+    }
+}
+
+/// Complementary error function, Chebyshev approximation with relative error below 1.2e-7.
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let r = t
+        * (-z * z - 1.265_512_23
+            + t * (1.000_023_68
+                + t * (0.374_091_96
+                    + t * (0.096_784_18
+                        + t * (-0.186_288_06
+                            + t * (0.278_868_07
+                                + t * (-1.135_203_98
+                                    + t * (1.488_515_87
+                                        + t * (-0.822_152_23 + t * 0.170_872_77)))))))))
+            .exp();
+    if x >= 0.0 { r } else { 2.0 - r }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64, tolerance: f64) -> bool {
+        (a - b).abs() < tolerance
+    }
+
+    #[test]
+    fn test_norm_cdf() {
+        assert!(close(norm_cdf(0.0), 0.5, 1e-7));
+        assert!(close(norm_cdf(1.96), 0.975_002, 1e-6));
+        assert!(close(norm_cdf(-1.0), 0.158_655, 1e-6));
+    }
+
+    #[test]
+    fn test_black_scholes() {
+        let call = PricingInputs::new(OptionType::Call, 100.0, 100.0, 1.0, 0.2).rate(0.05);
+        let put = PricingInputs {
+            option_type: OptionType::Put,
+            ..call
+        };
+        assert!(close(call.price(), 10.4506, 1e-4));
+        assert!(close(put.price(), 5.5735, 1e-4));
+
+        let greeks = call.greeks();
+        assert!(close(greeks.delta, 0.6368, 1e-4));
+        assert!(close(greeks.gamma, 0.018_76, 1e-5));
+        assert!(close(greeks.vega, 0.3752, 1e-4));
+        assert!(close(greeks.theta, -6.4140 / 365.0, 1e-5));
+        assert!(close(greeks.rho, 0.5323, 1e-4));
+        assert!(close(put.greeks().delta, -0.3632, 1e-4));
+    }
+
+    #[test]
+    fn test_american_binomial() {
+        let european = PricingInputs::new(OptionType::Put, 100.0, 100.0, 1.0, 0.2).rate(0.05);
+        let american = european.style(ExerciseStyle::American);
+
+        // Early exercise premium on puts, none on calls without dividends
+        assert!(close(american.price(), 6.09, 0.02));
+        assert!(american.price() > european.price());
+        let call = PricingInputs {
+            option_type: OptionType::Call,
+            ..american
+        };
+        assert!(close(call.price(), 10.4506, 0.02));
+
+        let greeks = american.greeks();
+        assert!(close(greeks.delta, -0.41, 0.02));
+        assert!(greeks.gamma > 0.0 && greeks.vega > 0.0 && greeks.theta < 0.0);
+    }
+
+    #[test]
+    fn test_implied_volatility() {
+        let inputs = PricingInputs::new(OptionType::Call, 100.0, 110.0, 0.5, 0.35).rate(0.03);
+        let iv = inputs
+            .volatility(0.0)
+            .implied_volatility(inputs.price())
+            .unwrap();
+        assert!(close(iv, 0.35, 1e-6));
+
+        let american = inputs.style(ExerciseStyle::American);
+        let iv = american.implied_volatility(american.price()).unwrap();
+        assert!(close(iv, 0.35, 1e-4));
+
+        // Below intrinsic value
+        assert_eq!(
+            PricingInputs::new(OptionType::Put, 100.0, 120.0, 0.5, 0.2).implied_volatility(5.0),
+            None
+        );
+    }
+
+    #[test]
+    fn test_time_to_expiry() {
+        let symbol: OptionSymbol = "AAPL250117C00150000".parse().unwrap();
+        let inputs = PricingInputs::from_symbol(&symbol, 150.0, 0.3, "2024-01-18").unwrap();
+        assert!(close(
+            inputs.time_to_expiry,
+            (365.0 + 6.5 / 24.0) / 365.0,
+            1e-12
+        ));
+        assert_eq!(time_to_expiry("2025-01-20", "2025-01-17"), Some(0.0));
+
+        // Same day expirations have until the close, in daylight saving time too
+        let hour = 1.0 / 24.0 / 365.0;
+        let same_day = time_to_expiry("2025-01-17", "2025-01-17").unwrap();
+        assert!(close(same_day, 6.5 * hour, 1e-12));
+        let same_day = time_to_expiry("2025-06-20T19:00:00Z", "2025-06-20").unwrap();
+        assert!(close(same_day, hour, 1e-12));
+        assert_eq!(
+            time_to_expiry("2025-06-20T20:30:00Z", "2025-06-20"),
+            Some(0.0)
+        );
+        assert_eq!(time_to_expiry("2025-06-20T15:00", "2025-06-20"), None);
+    }
+}
@@ -14,6 +14,17 @@ pub struct OptionQuote {
     pub c: String,  // Condition
 }
 
+impl OptionQuote {
+    /// Midpoint of the bid and ask, `None` when either side is missing.
+    pub fn mid(&self) -> Option<f64> {
+        if self.bp > 0.0 && self.ap > 0.0 {
+            Some((self.bp as f64 + self.ap as f64) / 2.0)
+        } else {
+            None
+        }
+    }
+}
+
 pub type LatestOptionQuotes = HashMap<String, OptionQuote>;
 
 #[derive(Deserialize, Debug)]
@@ -23,15 +34,16 @@ pub struct LatestOptionQuotesResponse {
 
 pub struct LatestOptionQuotesQuery<'a> {
     url: &'a str,
-    symbols: Vec<&'a str>,
+    symbols: Vec<String>,
     feed: Option<&'a str>,
 }
 
 impl<'a> LatestOptionQuotesQuery<'a> {
-    pub fn new(symbols: Vec<&'a str>) -> Self {
+    /// `symbols` can be OCC strings or [`OptionSymbol`](super::OptionSymbol)s.
+    pub fn new<S: ToString>(symbols: Vec<S>) -> Self {
         Self {
             url: "https://data.alpaca.markets/v1beta1/options/quotes/latest",
-            symbols,
+            symbols: symbols.iter().map(|s| s.to_string()).collect(),
             feed: None,
         }
     }
@@ -12,7 +12,7 @@ pub struct OptionSnapshot {
     pub implied_volatility: Option<f64>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
 pub struct Greeks {
     pub delta: f64,
     pub gamma: f64,
@@ -31,17 +31,18 @@ pub struct OptionSnapshotResponse {
 
 pub struct OptionSnapshotQuery<'a> {
     url: &'a str,
-    symbols: Vec<&'a str>,
+    symbols: Vec<String>,
     feed: Option<&'a str>,
     updated_since: Option<&'a str>,
     limit: Option<i32>,
 }
 
 impl<'a> OptionSnapshotQuery<'a> {
-    pub fn new(symbols: Vec<&'a str>) -> Self {
+    /// `symbols` can be OCC strings or [`OptionSymbol`](super::OptionSymbol)s.
+    pub fn new<S: ToString>(symbols: Vec<S>) -> Self {
         Self {
             url: "https://data.alpaca.markets/v1beta1/options/snapshots",
-            symbols,
+            symbols: symbols.iter().map(|s| s.to_string()).collect(),
             feed: None,
             updated_since: None,
             limit: None,
@@ -82,6 +83,7 @@ impl<'a> OptionSnapshotQuery<'a> {
         let route = self.build();
         let mut snapshots: OptionSnapshots = HashMap::new();
         let mut page_token = None;
+        let mut page = 0;
 
         loop {
             // If a token exists, append to address
@@ -89,6 +91,8 @@ impl<'a> OptionSnapshotQuery<'a> {
                 Some(token) => format!("{}&page_token={}", &route, &token),
                 _ => route.clone(),
             };
+            page += 1;
+            let _page = crate::instrument::page_span(page);
             let response = request("GET", &temp_address).call()?;
             let response: OptionSnapshotResponse = response.into_body().read_json()?;
 
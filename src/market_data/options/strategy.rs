@@ -0,0 +1,554 @@
+use super::{
+    chain::OptionChainQuery,
+    snapshot::OptionSnapshots,
+    symbol::{OptionSymbol, OptionType},
+};
+use crate::trading::order::{Order, OrderBuilder, OrderLeg, OrderSide, PositionIntent, kind};
+use std::fmt;
+
+/// How to pick a strike from the chain.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StrikeSelection {
+    /// Contract whose absolute delta is closest to this value, e.g. `0.30`.
+    Delta(f64),
+    /// Strike closest to the underlying price times this ratio, e.g. `1.05` for 5% above spot.
+    /// Requires [`StrategyChain::underlying_price`].
+    Moneyness(f64),
+    /// This exact strike.
+    Strike(f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StrategyKind {
+    Vertical,
+    Straddle,
+    Strangle,
+    IronCondor,
+    Calendar,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StrategyError {
+    /// No contracts of this type expire on this date.
+    NoContracts {
+        expiration: String,
+        option_type: OptionType,
+    },
+    /// Moneyness selection needs the underlying price.
+    MissingUnderlyingPrice,
+    /// The exact strike is not listed.
+    StrikeNotFound(f64),
+    /// Two legs of the strategy resolved to the same contract.
+    DuplicateLeg(String),
+    /// The target delta or moneyness strike is not a finite number.
+    InvalidTarget(f64),
+}
+
+impl fmt::Display for StrategyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StrategyError::NoContracts {
+                expiration,
+                option_type,
+            } => write!(f, "no {} contracts expiring {}", option_type, expiration),
+            StrategyError::MissingUnderlyingPrice => {
+                write!(f, "moneyness selection requires the underlying price")
+            }
+            StrategyError::StrikeNotFound(strike) => write!(f, "strike {} not found", strike),
+            StrategyError::DuplicateLeg(symbol) => {
+                write!(f, "{} is used by more than one leg", symbol)
+            }
+            StrategyError::InvalidTarget(target) => {
+                write!(f, "invalid selection target {}", target)
+            }
+        }
+    }
+}
+
+impl std::error::Error for StrategyError {}
+
+/// A contract from the chain with the data needed to build strategies.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainContract {
+    pub symbol: String,
+    /// YYYY-MM-DD
+    pub expiration: String,
+    pub option_type: OptionType,
+    pub strike: f64,
+    pub bid: Option<f64>,
+    pub ask: Option<f64>,
+    pub delta: Option<f64>,
+}
+
+impl ChainContract {
+    pub fn mid(&self) -> Option<f64> {
+        Some((self.bid? + self.ask?) / 2.0)
+    }
+}
+
+/// One leg of a strategy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrategyLeg {
+    pub contract: ChainContract,
+    pub side: OrderSide,
+    pub ratio_qty: String,
+}
+
+impl StrategyLeg {
+    fn new(contract: &ChainContract, side: OrderSide) -> Self {
+        Self {
+            contract: contract.clone(),
+            side,
+            ratio_qty: "1".to_string(),
+        }
+    }
+
+    /// +1 when paying for the leg, -1 when receiving.
+    fn sign(&self) -> f64 {
+        match self.side {
+            OrderSide::Buy => 1.0,
+            OrderSide::Sell => -1.0,
+        }
+    }
+
+    fn ratio(&self) -> f64 {
+        self.ratio_qty.parse().unwrap_or(1.0)
+    }
+}
+
+/// An options strategy ready to be sent as a multi-leg order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionStrategy {
+    pub kind: StrategyKind,
+    pub legs: Vec<StrategyLeg>,
+}
+
+impl OptionStrategy {
+    /// Net price per strategy unit at the quote midpoints. Positive is a debit, negative a credit.
+    pub fn net_price(&self) -> Option<f64> {
+        self.legs.iter().try_fold(0.0, |total, leg| {
+            Some(total + leg.sign() * leg.ratio() * leg.contract.mid()?)
+        })
+    }
+
+    /// Net price per strategy unit paying the ask on buys and receiving the bid on sells.
+    pub fn natural_price(&self) -> Option<f64> {
+        self.legs.iter().try_fold(0.0, |total, leg| {
+            let price = match leg.side {
+                OrderSide::Buy => leg.contract.ask?,
+                OrderSide::Sell => leg.contract.bid?,
+            };
+            Some(total + leg.sign() * leg.ratio() * price)
+        })
+    }
+
+    pub fn is_debit(&self) -> Option<bool> {
+        self.net_price().map(|price| price > 0.0)
+    }
+
+    /// Opening legs for a multi-leg order.
+    pub fn order_legs(&self) -> Vec<OrderLeg<'_>> {
+        self.legs
+            .iter()
+            .map(|leg| {
+                let intent = match leg.side {
+                    OrderSide::Buy => PositionIntent::BuyToOpen,
+                    OrderSide::Sell => PositionIntent::SellToOpen,
+                };
+                OrderLeg::new(&leg.contract.symbol, leg.side.clone(), &leg.ratio_qty)
+                    .position_intent(intent)
+            })
+            .collect()
+    }
+
+    /// A multi-leg market order for `qty` strategy units. Add `.limit_price` with a signed net price for a limit order.
+    pub fn order<'a>(&'a self, qty: &'a str) -> OrderBuilder<'a, kind::MultiLeg> {
+        Order::multi_leg(qty, self.order_legs())
+    }
+}
+
+/// Option chain contracts for one underlying, used to build strategies.
+#[derive(Debug, Clone, Default)]
+pub struct StrategyChain {
+    contracts: Vec<ChainContract>,
+    underlying_price: Option<f64>,
+}
+
+impl StrategyChain {
+    /// Load every contract of `underlying` expiring between the two dates (YYYY-MM-DD).
+    pub fn load(
+        underlying: &str,
+        expiration_gte: &str,
+        expiration_lte: &str,
+    ) -> Result<Self, ureq::Error> {
+        let snapshots = OptionChainQuery::new(underlying)
+            .expiration_date_gte(expiration_gte)
+            .expiration_date_lte(expiration_lte)
+            .all_pages()
+            .send()?;
+        Ok(Self::from_snapshots(&snapshots))
+    }
+
+    /// Build from an existing `OptionChainQuery` or `OptionSnapshotQuery` result.
+    pub fn from_snapshots(snapshots: &OptionSnapshots) -> Self {
+        let mut contracts: Vec<ChainContract> = snapshots
+            .iter()
+            .filter_map(|(symbol, snapshot)| {
+                let parsed: OptionSymbol = symbol.parse().ok()?;
+                Some(ChainContract {
+                    symbol: symbol.clone(),
+                    expiration: parsed.expiration(),
+                    option_type: parsed.option_type(),
+                    strike: parsed.strike(),
+                    bid: snapshot.latest_quote.as_ref().map(|q| q.bp as f64),
+                    ask: snapshot.latest_quote.as_ref().map(|q| q.ap as f64),
+                    delta: snapshot.greeks.as_ref().map(|g| g.delta),
+                })
+            })
+            .collect();
+        contracts.sort_by(|a, b| {
+            a.expiration
+                .cmp(&b.expiration)
+                .then(a.strike.total_cmp(&b.strike))
+        });
+
+        Self {
+            contracts,
+            underlying_price: None,
+        }
+    }
+
+    /// Underlying price used by [`StrikeSelection::Moneyness`].
+    pub fn underlying_price(mut self, underlying_price: f64) -> Self {
+        self.underlying_price = Some(underlying_price);
+        self
+    }
+
+    pub fn contracts(&self) -> &[ChainContract] {
+        &self.contracts
+    }
+
+    /// Distinct expiration dates, ascending.
+    pub fn expirations(&self) -> Vec<&str> {
+        let mut expirations: Vec<&str> = self
+            .contracts
+            .iter()
+            .map(|c| c.expiration.as_str())
+            .collect();
+        expirations.dedup();
+        expirations
+    }
+
+    pub fn select(
+        &self,
+        expiration: &str,
+        option_type: OptionType,
+        selection: StrikeSelection,
+    ) -> Result<&ChainContract, StrategyError> {
+        let candidates = self
+            .contracts
+            .iter()
+            .filter(|c| c.expiration == expiration && c.option_type == option_type);
+
+        let closest = |distance: &dyn Fn(&ChainContract) -> Option<f64>| {
+            candidates
+                .clone()
+                .filter_map(|c| Some((distance(c)?, c)))
+                .filter(|(distance, _)| distance.is_finite())
+                .min_by(|a, b| a.0.total_cmp(&b.0))
+                .map(|(_, c)| c)
+        };
+
+        let contract = match selection {
+            StrikeSelection::Delta(target) => {
+                if !target.is_finite() {
+                    return Err(StrategyError::InvalidTarget(target));
+                }
+                closest(&|c| c.delta.map(|delta| (delta.abs() - target.abs()).abs()))
+            }
+            StrikeSelection::Moneyness(ratio) => {
+                let target = self
+                    .underlying_price
+                    .ok_or(StrategyError::MissingUnderlyingPrice)?
+                    * ratio;
+                if !target.is_finite() {
+                    return Err(StrategyError::InvalidTarget(target));
+                }
+                closest(&|c| Some((c.strike - target).abs()))
+            }
+            StrikeSelection::Strike(strike) => {
+                if candidates.clone().next().is_some() {
+                    let found = closest(&|c| Some((c.strike - strike).abs()))
+                        .filter(|c| (c.strike - strike).abs() < 1e-6);
+                    return found.ok_or(StrategyError::StrikeNotFound(strike));
+                }
+                None
+            }
+        };
+
+        contract.ok_or_else(|| StrategyError::NoContracts {
+            expiration: expiration.to_string(),
+            option_type,
+        })
+    }
+
+    /// Buy one strike and sell another of the same type and expiration.
+    /// A debit spread when the long strike is closer to the money.
+    pub fn vertical(
+        &self,
+        expiration: &str,
+        option_type: OptionType,
+        long: StrikeSelection,
+        short: StrikeSelection,
+    ) -> Result<OptionStrategy, StrategyError> {
+        let long = self.select(expiration, option_type, long)?;
+        let short = self.select(expiration, option_type, short)?;
+        strategy(
+            StrategyKind::Vertical,
+            vec![
+                StrategyLeg::new(long, OrderSide::Buy),
+                StrategyLeg::new(short, OrderSide::Sell),
+            ],
+        )
+    }
+
+    /// Call and put at the same strike. `OrderSide::Buy` for a long straddle.
+    pub fn straddle(
+        &self,
+        expiration: &str,
+        strike: StrikeSelection,
+        side: OrderSide,
+    ) -> Result<OptionStrategy, StrategyError> {
+        let call = self.select(expiration, OptionType::Call, strike)?;
+        let put = self.select(
+            expiration,
+            OptionType::Put,
+            StrikeSelection::Strike(call.strike),
+        )?;
+        strategy(
+            StrategyKind::Straddle,
+            vec![
+                StrategyLeg::new(call, side.clone()),
+                StrategyLeg::new(put, side),
+            ],
+        )
+    }
+
+    /// Out of the money put and call. `OrderSide::Buy` for a long strangle.
+    pub fn strangle(
+        &self,
+        expiration: &str,
+        put: StrikeSelection,
+        call: StrikeSelection,
+        side: OrderSide,
+    ) -> Result<OptionStrategy, StrategyError> {
+        let put = self.select(expiration, OptionType::Put, put)?;
+        let call = self.select(expiration, OptionType::Call, call)?;
+        strategy(
+            StrategyKind::Strangle,
+            vec![
+                StrategyLeg::new(put, side.clone()),
+                StrategyLeg::new(call, side),
+            ],
+        )
+    }
+
+    /// Short iron condor: sell the inner put and call, buy the outer wings.
+    pub fn iron_condor(
+        &self,
+        expiration: &str,
+        long_put: StrikeSelection,
+        short_put: StrikeSelection,
+        short_call: StrikeSelection,
+        long_call: StrikeSelection,
+    ) -> Result<OptionStrategy, StrategyError> {
+        let legs = vec![
+            StrategyLeg::new(
+                self.select(expiration, OptionType::Put, long_put)?,
+                OrderSide::Buy,
+            ),
+            StrategyLeg::new(
+                self.select(expiration, OptionType::Put, short_put)?,
+                OrderSide::Sell,
+            ),
+            StrategyLeg::new(
+                self.select(expiration, OptionType::Call, short_call)?,
+                OrderSide::Sell,
+            ),
+            StrategyLeg::new(
+                self.select(expiration, OptionType::Call, long_call)?,
+                OrderSide::Buy,
+            ),
+        ];
+        strategy(StrategyKind::IronCondor, legs)
+    }
+
+    /// Sell the near expiration and buy the far expiration at the same strike.
+    pub fn calendar(
+        &self,
+        near_expiration: &str,
+        far_expiration: &str,
+        option_type: OptionType,
+        strike: StrikeSelection,
+    ) -> Result<OptionStrategy, StrategyError> {
+        let near = self.select(near_expiration, option_type, strike)?;
+        let far = self.select(
+            far_expiration,
+            option_type,
+            StrikeSelection::Strike(near.strike),
+        )?;
+        strategy(
+            StrategyKind::Calendar,
+            vec![
+                StrategyLeg::new(near, OrderSide::Sell),
+                StrategyLeg::new(far, OrderSide::Buy),
+            ],
+        )
+    }
+}
+
+fn strategy(kind: StrategyKind, legs: Vec<StrategyLeg>) -> Result<OptionStrategy, StrategyError> {
+    for (i, leg) in legs.iter().enumerate() {
+        if legs[..i]
+            .iter()
+            .any(|other| other.contract.symbol == leg.contract.symbol)
+        {
+            return Err(StrategyError::DuplicateLeg(leg.contract.symbol.clone()));
+        }
+    }
+    Ok(OptionStrategy { kind, legs })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contract(symbol: &str, bid: f64, ask: f64, delta: f64) -> ChainContract {
+        let parsed: OptionSymbol = symbol.parse().unwrap();
+        ChainContract {
+            symbol: symbol.to_string(),
+            expiration: parsed.expiration(),
+            option_type: parsed.option_type(),
+            strike: parsed.strike(),
+            bid: Some(bid),
+            ask: Some(ask),
+            delta: Some(delta),
+        }
+    }
+
+    fn chain() -> StrategyChain {
+        StrategyChain {
+            contracts: vec![
+                contract("SPY250620P00480000", 1.00, 1.10, -0.10),
+                contract("SPY250620P00490000", 2.00, 2.10, -0.20),
+                contract("SPY250620P00500000", 4.00, 4.20, -0.45),
+                contract("SPY250620C00500000", 5.00, 5.20, 0.55),
+                contract("SPY250620C00510000", 2.50, 2.60, 0.30),
+                contract("SPY250620C00520000", 1.20, 1.30, 0.15),
+                contract("SPY250718C00500000", 8.00, 8.40, 0.56),
+            ],
+            underlying_price: Some(502.0),
+        }
+    }
+
+    #[test]
+    fn test_select_strikes() {
+        let chain = chain();
+        let by_delta = chain
+            .select("2025-06-20", OptionType::Put, StrikeSelection::Delta(0.2))
+            .unwrap();
+        assert_eq!(by_delta.strike, 490.0);
+
+        let by_moneyness = chain
+            .select(
+                "2025-06-20",
+                OptionType::Call,
+                StrikeSelection::Moneyness(1.0),
+            )
+            .unwrap();
+        assert_eq!(by_moneyness.strike, 500.0);
+
+        assert_eq!(
+            chain.select(
+                "2025-06-20",
+                OptionType::Call,
+                StrikeSelection::Strike(505.0)
+            ),
+            Err(StrategyError::StrikeNotFound(505.0))
+        );
+        assert!(matches!(
+            chain.select(
+                "2025-06-27",
+                OptionType::Call,
+                StrikeSelection::Strike(500.0)
+            ),
+            Err(StrategyError::NoContracts { .. })
+        ));
+
+        assert!(matches!(
+            chain.select(
+                "2025-06-20",
+                OptionType::Put,
+                StrikeSelection::Delta(f64::NAN)
+            ),
+            Err(StrategyError::InvalidTarget(_))
+        ));
+        assert!(matches!(
+            chain.clone().underlying_price(f64::NAN).select(
+                "2025-06-20",
+                OptionType::Call,
+                StrikeSelection::Moneyness(1.0)
+            ),
+            Err(StrategyError::InvalidTarget(_))
+        ));
+    }
+
+    #[test]
+    fn test_build_strategies() {
+        let chain = chain();
+
+        let vertical = chain
+            .vertical(
+                "2025-06-20",
+                OptionType::Call,
+                StrikeSelection::Strike(500.0),
+                StrikeSelection::Delta(0.3),
+            )
+            .unwrap();
+        assert!((vertical.net_price().unwrap() - 2.55).abs() < 1e-9);
+        assert!((vertical.natural_price().unwrap() - 2.70).abs() < 1e-9);
+        assert_eq!(vertical.is_debit(), Some(true));
+
+        let condor = chain
+            .iron_condor(
+                "2025-06-20",
+                StrikeSelection::Strike(480.0),
+                StrikeSelection::Strike(490.0),
+                StrikeSelection::Strike(510.0),
+                StrikeSelection::Strike(520.0),
+            )
+            .unwrap();
+        assert!((condor.net_price().unwrap() + 2.3).abs() < 1e-9);
+        assert_eq!(condor.is_debit(), Some(false));
+
+        let calendar = chain
+            .calendar(
+                "2025-06-20",
+                "2025-07-18",
+                OptionType::Call,
+                StrikeSelection::Moneyness(1.0),
+            )
+            .unwrap();
+        assert_eq!(calendar.legs[1].contract.symbol, "SPY250718C00500000");
+
+        let straddle = chain
+            .straddle("2025-06-20", StrikeSelection::Strike(500.0), OrderSide::Buy)
+            .unwrap();
+        let legs = straddle.order_legs();
+        assert_eq!(legs.len(), 2);
+        assert_eq!(legs[1].position_intent.as_deref(), Some("buy_to_open"));
+
+        let query = straddle.order("1").limit_price("9.20").into_query();
+        assert!(query.validate().is_ok());
+    }
+}
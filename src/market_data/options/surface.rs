@@ -0,0 +1,395 @@
+use super::{
+    chain::OptionChainQuery,
+    pricing::{PricingInputs, time_to_expiry},
+    snapshot::{OptionSnapshot, OptionSnapshots},
+    symbol::{OptionSymbol, OptionType},
+};
+use crate::time::parse_rfc3339_nanos;
+use std::collections::{BTreeMap, HashMap};
+
+/// Implied volatility of one contract on the surface.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SurfacePoint {
+    pub symbol: String,
+    pub option_type: OptionType,
+    pub strike: f64,
+    /// Strike divided by the underlying price.
+    pub moneyness: f64,
+    pub implied_volatility: f64,
+}
+
+/// The volatility smile of one expiration, out of the money contracts sorted by strike.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SmileSlice {
+    /// YYYY-MM-DD
+    pub expiration: String,
+    /// Years until expiration.
+    pub time_to_expiry: f64,
+    pub points: Vec<SurfacePoint>,
+}
+
+impl SmileSlice {
+    /// Volatility at a strike, linear between listed strikes and flat beyond them.
+    pub fn volatility_at_strike(&self, strike: f64) -> Option<f64> {
+        interpolate(
+            self.points.iter().map(|p| (p.strike, p.implied_volatility)),
+            strike,
+        )
+    }
+
+    /// Volatility at a moneyness (strike / underlying price), e.g. `0.9` for the 90% strike.
+    pub fn volatility_at_moneyness(&self, moneyness: f64) -> Option<f64> {
+        interpolate(
+            self.points
+                .iter()
+                .map(|p| (p.moneyness, p.implied_volatility)),
+            moneyness,
+        )
+    }
+
+    pub fn atm_volatility(&self) -> Option<f64> {
+        self.volatility_at_moneyness(1.0)
+    }
+}
+
+/// At the money volatility of one expiration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TermPoint {
+    /// YYYY-MM-DD
+    pub expiration: String,
+    pub time_to_expiry: f64,
+    pub atm_volatility: f64,
+}
+
+#[derive(Debug, Clone)]
+struct Quote {
+    symbol: OptionSymbol,
+    implied_volatility: f64,
+}
+
+/// Implied volatility surface of one underlying, built from chain snapshots.
+///
+/// Uses the snapshot implied volatility, falling back to the volatility implied by the quote midpoint.
+/// Smiles use puts below the underlying price and calls at or above it, and the surface interpolates
+/// linearly across strikes and linearly in total variance across expirations.
+#[derive(Debug, Clone)]
+pub struct VolatilitySurface {
+    underlying: String,
+    underlying_price: f64,
+    valuation_date: String,
+    quotes: HashMap<String, Quote>,
+    last_updated: Option<(i64, String)>,
+}
+
+impl VolatilitySurface {
+    /// An empty surface. `valuation_date` (YYYY-MM-DD) is used for time to expiry.
+    pub fn new(underlying: &str, underlying_price: f64, valuation_date: &str) -> Self {
+        Self {
+            underlying: underlying.to_string(),
+            underlying_price,
+            valuation_date: valuation_date.to_string(),
+            quotes: HashMap::new(),
+            last_updated: None,
+        }
+    }
+
+    /// Load the full chain of `underlying`.
+    pub fn load(
+        underlying: &str,
+        underlying_price: f64,
+        valuation_date: &str,
+    ) -> Result<Self, ureq::Error> {
+        let mut surface = Self::new(underlying, underlying_price, valuation_date);
+        surface.refresh()?;
+        Ok(surface)
+    }
+
+    /// Build from an existing `OptionChainQuery` or `OptionSnapshotQuery` result.
+    pub fn from_snapshots(
+        underlying: &str,
+        underlying_price: f64,
+        valuation_date: &str,
+        snapshots: &OptionSnapshots,
+    ) -> Self {
+        let mut surface = Self::new(underlying, underlying_price, valuation_date);
+        surface.apply(snapshots);
+        surface
+    }
+
+    /// Fetch contracts updated since the latest quote or trade seen so far, or the full chain on an empty surface.
+    /// Returns the number of contracts updated.
+    pub fn refresh(&mut self) -> Result<usize, ureq::Error> {
+        let since = self.last_updated.as_ref().map(|(_, t)| t.clone());
+        let mut query = OptionChainQuery::new(&self.underlying).all_pages();
+        if let Some(since) = &since {
+            query = query.updated_since(since);
+        }
+        let snapshots = query.send()?;
+        Ok(self.apply(&snapshots))
+    }
+
+    /// Merge snapshots into the surface, returning the number of contracts updated.
+    /// Contracts without a usable volatility keep their previous value.
+    pub fn apply(&mut self, snapshots: &OptionSnapshots) -> usize {
+        let mut updated = 0;
+        for (symbol, snapshot) in snapshots {
+            let timestamps = [
+                snapshot.latest_quote.as_ref().map(|q| &q.t),
+                snapshot.latest_trade.as_ref().map(|t| &t.t),
+            ];
+            for timestamp in timestamps.into_iter().flatten() {
+                if let Some(nanos) = parse_rfc3339_nanos(timestamp)
+                    && self
+                        .last_updated
+                        .as_ref()
+                        .is_none_or(|(last, _)| nanos > *last)
+                {
+                    self.last_updated = Some((nanos, timestamp.clone()));
+                }
+            }
+
+            let Ok(parsed) = symbol.parse::<OptionSymbol>() else {
+                continue;
+            };
+            if let Some(implied_volatility) = self.implied_volatility(&parsed, snapshot) {
+                self.quotes.insert(
+                    symbol.clone(),
+                    Quote {
+                        symbol: parsed,
+                        implied_volatility,
+                    },
+                );
+                updated += 1;
+            }
+        }
+        updated
+    }
+
+    /// Update the underlying price used for moneyness and ATM volatility.
+    pub fn set_underlying_price(&mut self, underlying_price: f64) {
+        self.underlying_price = underlying_price;
+    }
+
+    /// Move the valuation date, e.g. at the start of a new session.
+    pub fn set_valuation_date(&mut self, valuation_date: &str) {
+        self.valuation_date = valuation_date.to_string();
+    }
+
+    pub fn underlying(&self) -> &str {
+        &self.underlying
+    }
+
+    pub fn underlying_price(&self) -> f64 {
+        self.underlying_price
+    }
+
+    /// Latest quote or trade timestamp seen, used as `updated_since` by [`VolatilitySurface::refresh`].
+    pub fn last_updated(&self) -> Option<&str> {
+        self.last_updated.as_ref().map(|(_, t)| t.as_str())
+    }
+
+    /// Number of contracts with a volatility.
+    pub fn len(&self) -> usize {
+        self.quotes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.quotes.is_empty()
+    }
+
+    /// Unexpired expiration dates, ascending.
+    pub fn expirations(&self) -> Vec<String> {
+        self.smiles().into_iter().map(|s| s.expiration).collect()
+    }
+
+    /// The smile of one expiration (YYYY-MM-DD).
+    pub fn smile(&self, expiration: &str) -> Option<SmileSlice> {
+        self.smiles()
+            .into_iter()
+            .find(|s| s.expiration == expiration)
+    }
+
+    /// Smiles of all unexpired expirations, ascending.
+    pub fn smiles(&self) -> Vec<SmileSlice> {
+        // Out of the money contract per strike: puts below spot, calls at or above, either if only one is listed
+        let mut by_expiration: BTreeMap<String, BTreeMap<u64, &Quote>> = BTreeMap::new();
+        for quote in self.quotes.values() {
+            let strikes = by_expiration.entry(quote.symbol.expiration()).or_default();
+            let key = (quote.symbol.strike() * 1000.0).round() as u64;
+            let otm = |q: &Quote| match q.symbol.option_type() {
+                OptionType::Call => q.symbol.strike() >= self.underlying_price,
+                OptionType::Put => q.symbol.strike() < self.underlying_price,
+            };
+            match strikes.get(&key) {
+                Some(existing) if otm(existing) || !otm(quote) => {}
+                _ => {
+                    strikes.insert(key, quote);
+                }
+            }
+        }
+
+        by_expiration
+            .into_iter()
+            .filter_map(|(expiration, strikes)| {
+                let time_to_expiry = time_to_expiry(&self.valuation_date, &expiration)?;
+                if time_to_expiry <= 0.0 {
+                    return None;
+                }
+                let points = strikes
+                    .into_values()
+                    .map(|q| SurfacePoint {
+                        symbol: q.symbol.to_string(),
+                        option_type: q.symbol.option_type(),
+                        strike: q.symbol.strike(),
+                        moneyness: q.symbol.strike() / self.underlying_price,
+                        implied_volatility: q.implied_volatility,
+                    })
+                    .collect();
+                Some(SmileSlice {
+                    expiration,
+                    time_to_expiry,
+                    points,
+                })
+            })
+            .collect()
+    }
+
+    /// At the money volatility of each expiration.
+    pub fn term_structure(&self) -> Vec<TermPoint> {
+        self.smiles()
+            .into_iter()
+            .filter_map(|s| {
+                Some(TermPoint {
+                    atm_volatility: s.atm_volatility()?,
+                    time_to_expiry: s.time_to_expiry,
+                    expiration: s.expiration,
+                })
+            })
+            .collect()
+    }
+
+    /// At the money volatility for any time to expiry in years.
+    pub fn atm_volatility(&self, time_to_expiry: f64) -> Option<f64> {
+        self.volatility(self.underlying_price, time_to_expiry)
+    }
+
+    /// Volatility for any strike and time to expiry in years. Smiles are read at the strike's moneyness
+    /// and joined linearly in total variance, flat before the first and after the last expiration.
+    pub fn volatility(&self, strike: f64, time_to_expiry: f64) -> Option<f64> {
+        let moneyness = strike / self.underlying_price;
+        let slices: Vec<(f64, f64)> = self
+            .smiles()
+            .iter()
+            .filter_map(|s| Some((s.time_to_expiry, s.volatility_at_moneyness(moneyness)?)))
+            .collect();
+
+        let (first, last) = (slices.first()?, slices.last()?);
+        if time_to_expiry <= first.0 {
+            return Some(first.1);
+        }
+        if time_to_expiry >= last.0 {
+            return Some(last.1);
+        }
+        let total_variance =
+            interpolate(slices.iter().map(|(t, v)| (*t, v * v * t)), time_to_expiry)?;
+        Some((total_variance / time_to_expiry).sqrt())
+    }
+
+    fn implied_volatility(&self, symbol: &OptionSymbol, snapshot: &OptionSnapshot) -> Option<f64> {
+        match snapshot.implied_volatility {
+            Some(volatility) if volatility > 0.0 => Some(volatility),
+            _ => PricingInputs::from_symbol(
+                symbol,
+                self.underlying_price,
+                0.0,
+                &self.valuation_date,
+            )?
+            .implied_volatility_from_quote(snapshot.latest_quote.as_ref()?),
+        }
+    }
+}
+
+/// Linear interpolation over points sorted by x, flat beyond the ends.
+fn interpolate(points: impl Iterator<Item = (f64, f64)>, x: f64) -> Option<f64> {
+    let mut previous: Option<(f64, f64)> = None;
+    for (x1, y1) in points {
+        match previous {
+            None if x <= x1 => return Some(y1),
+            Some((x0, y0)) if x <= x1 => {
+                if x1 == x0 {
+                    return Some(y1);
+                }
+                return Some(y0 + (y1 - y0) * (x - x0) / (x1 - x0));
+            }
+            _ => previous = Some((x1, y1)),
+        }
+    }
+    previous.map(|(_, y)| y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(iv: f64, t: &str) -> serde_json::Value {
+        serde_json::json!({
+            "impliedVolatility": iv,
+            "latestQuote": {"t": t, "ax": "C", "ap": 1.1, "as": 1, "bx": "C", "bp": 1.0, "bs": 1, "c": "A"}
+        })
+    }
+
+    fn snapshots() -> OptionSnapshots {
+        serde_json::from_value(serde_json::json!({
+            "SPY250321P00090000": snapshot(0.30, "2025-03-19T15:00:00Z"),
+            "SPY250321C00090000": snapshot(0.99, "2025-03-19T15:00:00Z"),
+            "SPY250321C00100000": snapshot(0.20, "2025-03-19T15:00:00Z"),
+            "SPY250321P00100000": snapshot(0.99, "2025-03-19T15:00:00Z"),
+            "SPY250321C00110000": snapshot(0.24, "2025-03-19T15:01:00Z"),
+            "SPY250620C00100000": snapshot(0.25, "2025-03-19T15:02:00Z"),
+            "SPY250117C00100000": snapshot(0.50, "2025-01-17T15:00:00Z"),
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_smile_and_term_structure() {
+        let surface = VolatilitySurface::from_snapshots("SPY", 100.0, "2025-03-20", &snapshots());
+        assert_eq!(surface.expirations(), vec!["2025-03-21", "2025-06-20"]);
+        assert_eq!(surface.last_updated(), Some("2025-03-19T15:02:00Z"));
+
+        // Out of the money side of each strike
+        let smile = surface.smile("2025-03-21").unwrap();
+        let vols: Vec<f64> = smile.points.iter().map(|p| p.implied_volatility).collect();
+        assert_eq!(vols, vec![0.30, 0.20, 0.24]);
+        assert_eq!(smile.points[0].moneyness, 0.9);
+        assert!((smile.volatility_at_strike(105.0).unwrap() - 0.22).abs() < 1e-12);
+        assert_eq!(smile.volatility_at_strike(80.0), Some(0.30));
+        assert_eq!(smile.atm_volatility(), Some(0.20));
+
+        let term = surface.term_structure();
+        assert_eq!(term.len(), 2);
+        assert_eq!(term[1].atm_volatility, 0.25);
+    }
+
+    #[test]
+    fn test_surface_interpolation_and_apply() {
+        let mut surface =
+            VolatilitySurface::from_snapshots("SPY", 100.0, "2025-03-20", &snapshots());
+        let near = surface.smile("2025-03-21").unwrap().time_to_expiry;
+        let far = surface.smile("2025-06-20").unwrap().time_to_expiry;
+
+        // Linear in total variance between expirations
+        let mid = (near + far) / 2.0;
+        let expected = ((0.2f64.powi(2) * near + 0.25f64.powi(2) * far) / 2.0 / mid).sqrt();
+        assert!((surface.atm_volatility(mid).unwrap() - expected).abs() < 1e-12);
+        assert_eq!(surface.atm_volatility(2.0), Some(0.25));
+
+        let update: OptionSnapshots = serde_json::from_value(serde_json::json!({
+            "SPY250620C00100000": snapshot(0.28, "2025-03-19T16:00:00Z"),
+        }))
+        .unwrap();
+        assert_eq!(surface.apply(&update), 1);
+        assert_eq!(surface.last_updated(), Some("2025-03-19T16:00:00Z"));
+        assert_eq!(surface.term_structure()[1].atm_volatility, 0.28);
+    }
+}
@@ -0,0 +1,243 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+/// Call or put.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OptionType {
+    Call,
+    Put,
+}
+
+impl fmt::Display for OptionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let option_type = match self {
+            OptionType::Call => "call",
+            OptionType::Put => "put",
+        };
+        write!(f, "{}", option_type)
+    }
+}
+
+/// An OCC option symbol: root, expiration (YYMMDD), C or P, and the strike times 1000 padded to 8 digits.
+///
+/// `AAPL250117C00150000` is the AAPL $150 call expiring 2025-01-17. Roots adjusted for corporate actions
+/// carry a trailing digit (`AAPL1`), and the space padded OCC form (`AAPL  250117C00150000`) is accepted.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OptionSymbol {
+    root: String,
+    year: u16,
+    month: u8,
+    day: u8,
+    option_type: OptionType,
+    /// Strike in thousandths of a dollar, as encoded in the symbol
+    strike: u32,
+}
+
+impl OptionSymbol {
+    /// `expiration` is YYYY-MM-DD.
+    pub fn new(
+        root: &str,
+        expiration: &str,
+        option_type: OptionType,
+        strike: f64,
+    ) -> Result<Self, ParseOptionSymbolError> {
+        let error = || {
+            ParseOptionSymbolError(format!(
+                "{} {} {} {}",
+                root, expiration, option_type, strike
+            ))
+        };
+        let (year, month, day) = parse_date(expiration).ok_or_else(error)?;
+        let strike = (strike * 1000.0).round();
+        if !is_valid_root(root) || !(0.0..100_000_000.0).contains(&strike) {
+            return Err(error());
+        }
+        Ok(Self {
+            root: root.to_string(),
+            year,
+            month,
+            day,
+            option_type,
+            strike: strike as u32,
+        })
+    }
+
+    /// The root symbol, e.g. `AAPL`, `SPXW` or the adjusted `AAPL1`.
+    pub fn root(&self) -> &str {
+        &self.root
+    }
+
+    /// The root without an adjustment digit, e.g. `AAPL` for `AAPL1`.
+    pub fn underlying(&self) -> &str {
+        self.root.trim_end_matches(|c: char| c.is_ascii_digit())
+    }
+
+    /// Whether the contract was adjusted by a corporate action, which changes its deliverable.
+    pub fn is_adjusted(&self) -> bool {
+        self.root.ends_with(|c: char| c.is_ascii_digit())
+    }
+
+    /// YYYY-MM-DD
+    pub fn expiration(&self) -> String {
+        format!("{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+
+    pub fn option_type(&self) -> OptionType {
+        self.option_type
+    }
+
+    pub fn is_call(&self) -> bool {
+        self.option_type == OptionType::Call
+    }
+
+    pub fn is_put(&self) -> bool {
+        self.option_type == OptionType::Put
+    }
+
+    pub fn strike(&self) -> f64 {
+        self.strike as f64 / 1000.0
+    }
+}
+
+impl fmt::Display for OptionSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let option_type = match self.option_type {
+            OptionType::Call => 'C',
+            OptionType::Put => 'P',
+        };
+        write!(
+            f,
+            "{}{:02}{:02}{:02}{}{:08}",
+            self.root,
+            self.year % 100,
+            self.month,
+            self.day,
+            option_type,
+            self.strike
+        )
+    }
+}
+
+/// One to six letters or digits.
+fn is_valid_root(root: &str) -> bool {
+    (1..=6).contains(&root.len()) && root.bytes().all(|b| b.is_ascii_alphanumeric())
+}
+
+impl FromStr for OptionSymbol {
+    type Err = ParseOptionSymbolError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseOptionSymbolError(s.to_string());
+        let symbol = s.trim();
+        if symbol.len() < 16 || !symbol.is_ascii() {
+            return Err(error());
+        }
+
+        let (root, tail) = symbol.split_at(symbol.len() - 15);
+        let root = root.trim_end();
+        let (date, rest) = tail.split_at(6);
+        let (option_type, strike) = rest.split_at(1);
+
+        if !is_valid_root(root) {
+            return Err(error());
+        }
+        if !date.bytes().all(|b| b.is_ascii_digit()) || !strike.bytes().all(|b| b.is_ascii_digit())
+        {
+            return Err(error());
+        }
+        let option_type = match option_type {
+            "C" => OptionType::Call,
+            "P" => OptionType::Put,
+            _ => return Err(error()),
+        };
+        let (year, month, day) =
+            parse_date(&format!("20{}-{}-{}", &date[..2], &date[2..4], &date[4..]))
+                .ok_or_else(error)?;
+
+        Ok(Self {
+            root: root.to_string(),
+            year,
+            month,
+            day,
+            option_type,
+            strike: strike.parse().map_err(|_| error())?,
+        })
+    }
+}
+
+impl Serialize for OptionSymbol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for OptionSymbol {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let symbol = String::deserialize(deserializer)?;
+        symbol.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseOptionSymbolError(String);
+
+impl fmt::Display for ParseOptionSymbolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid OCC option symbol: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseOptionSymbolError {}
+
+fn parse_date(date: &str) -> Option<(u16, u8, u8)> {
+    let mut parts = date.splitn(3, '-');
+    let year: u16 = parts.next()?.parse().ok()?;
+    let month: u8 = parts.next()?.parse().ok()?;
+    let day: u8 = parts.next()?.parse().ok()?;
+    if !(2000..2100).contains(&year) || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    Some((year, month, day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_option_symbol() {
+        let symbol: OptionSymbol = "AAPL250117C00150000".parse().unwrap();
+        assert_eq!(symbol.root(), "AAPL");
+        assert_eq!(symbol.expiration(), "2025-01-17");
+        assert_eq!(symbol.option_type(), OptionType::Call);
+        assert_eq!(symbol.strike(), 150.0);
+        assert!(!symbol.is_adjusted());
+        assert_eq!(symbol.to_string(), "AAPL250117C00150000");
+
+        let adjusted: OptionSymbol = "AAPL1  250117P00012500".parse().unwrap();
+        assert_eq!(adjusted.root(), "AAPL1");
+        assert_eq!(adjusted.underlying(), "AAPL");
+        assert!(adjusted.is_adjusted());
+        assert_eq!(adjusted.strike(), 12.5);
+        assert_eq!(adjusted.to_string(), "AAPL1250117P00012500");
+
+        assert!("AAPL".parse::<OptionSymbol>().is_err());
+        assert!("AAPL251317C00150000".parse::<OptionSymbol>().is_err());
+        assert!("AAPL250117X00150000".parse::<OptionSymbol>().is_err());
+    }
+
+    #[test]
+    fn test_option_symbol_new_and_serde() {
+        let symbol = OptionSymbol::new("SPXW", "2025-06-20", OptionType::Put, 5512.5).unwrap();
+        assert_eq!(symbol.to_string(), "SPXW250620P05512500");
+
+        let json = serde_json::to_string(&symbol).unwrap();
+        assert_eq!(json, "\"SPXW250620P05512500\"");
+        let back: OptionSymbol = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, symbol);
+
+        assert!(OptionSymbol::new("SPY", "2025-6", OptionType::Call, 500.0).is_err());
+        assert!(OptionSymbol::new("BRK.B", "2025-06-20", OptionType::Call, 500.0).is_err());
+        assert!(OptionSymbol::new("SPY 1", "2025-06-20", OptionType::Call, 500.0).is_err());
+    }
+}
@@ -27,7 +27,7 @@ pub struct LatestOptionTradesResponse {
 
 pub struct HistoricalOptionTradesQuery<'a> {
     url: &'a str,
-    symbols: Vec<&'a str>,
+    symbols: Vec<String>,
     start: Option<&'a str>,
     end: Option<&'a str>,
     limit: Option<i32>,
@@ -37,15 +37,16 @@ pub struct HistoricalOptionTradesQuery<'a> {
 
 pub struct LatestOptionTradesQuery<'a> {
     url: &'a str,
-    symbols: Vec<&'a str>,
+    symbols: Vec<String>,
     feed: Option<&'a str>,
 }
 
 impl<'a> HistoricalOptionTradesQuery<'a> {
-    pub fn new(symbols: Vec<&'a str>) -> Self {
+    /// `symbols` can be OCC strings or [`OptionSymbol`](super::OptionSymbol)s.
+    pub fn new<S: ToString>(symbols: Vec<S>) -> Self {
         Self {
             url: "https://data.alpaca.markets/v1beta1/options/trades",
-            symbols,
+            symbols: symbols.iter().map(|s| s.to_string()).collect(),
             start: None,
             end: None,
             limit: None,
@@ -116,6 +117,7 @@ impl<'a> HistoricalOptionTradesQuery<'a> {
         } else {
             1000
         };
+        let mut page = 0;
         loop {
             if i >= data_limit {
                 break;
@@ -126,6 +128,8 @@ impl<'a> HistoricalOptionTradesQuery<'a> {
                 Some(token) => format!("{}&page_token={}", &route, &token),
                 _ => route.clone(),
             };
+            page += 1;
+            let _page = crate::instrument::page_span(page);
             let response = request("GET", &temp_address).call()?;
             let response: HistoricalOptionTradesResponse = response.into_body().read_json()?;
 
@@ -147,10 +151,11 @@ impl<'a> HistoricalOptionTradesQuery<'a> {
 }
 
 impl<'a> LatestOptionTradesQuery<'a> {
-    pub fn new(symbols: Vec<&'a str>) -> Self {
+    /// `symbols` can be OCC strings or [`OptionSymbol`](super::OptionSymbol)s.
+    pub fn new<S: ToString>(symbols: Vec<S>) -> Self {
         Self {
             url: "https://data.alpaca.markets/v1beta1/options/trades/latest",
-            symbols,
+            symbols: symbols.iter().map(|s| s.to_string()).collect(),
             feed: None,
         }
     }
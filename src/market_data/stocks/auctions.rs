@@ -1,3 +1,4 @@
+use super::types::StockFeed;
 use crate::request;
 use serde::Deserialize;
 use std::collections::HashMap;
@@ -32,7 +33,7 @@ pub struct HistoricalAuctionsQuery<'a> {
     symbols: Vec<&'a str>,
     start: Option<&'a str>,
     end: Option<&'a str>,
-    feed: Option<&'a str>,
+    feed: Option<StockFeed>,
     currency: Option<&'a str>,
     limit: Option<i32>,
     asof: Option<&'a str>,
@@ -66,7 +67,7 @@ impl<'a> HistoricalAuctionsQuery<'a> {
         self
     }
 
-    pub fn feed(mut self, feed: &'a str) -> Self {
+    pub fn feed(mut self, feed: StockFeed) -> Self {
         self.feed = Some(feed);
         self
     }
@@ -138,6 +139,7 @@ impl<'a> HistoricalAuctionsQuery<'a> {
         let route = self.build();
         let mut auctions: HistoricalAuctions = HashMap::new();
         let mut page_token = None;
+        let mut page = 0;
 
         loop {
             // If a token exists, append to address
@@ -145,6 +147,8 @@ impl<'a> HistoricalAuctionsQuery<'a> {
                 Some(token) => format!("{}&page_token={}", &route, &token),
                 _ => route.clone(),
             };
+            page += 1;
+            let _page = crate::instrument::page_span(page);
             let response = request("GET", &temp_address).call()?;
             let response: HistoricalAuctionResponse = response.into_body().read_json()?;
 
@@ -171,7 +175,7 @@ mod tests {
     #[test]
     fn test_historical_auction_query() {
         let res = HistoricalAuctionsQuery::new(vec!["AAPL"])
-            .feed("sip")
+            .feed(StockFeed::Sip)
             .send()
             .unwrap();
 
@@ -1,3 +1,4 @@
+use super::types::{Adjustment, StockFeed, page_limit};
 use crate::{request, TimeFrame};
 use serde::Deserialize;
 use std::collections::HashMap;
@@ -38,9 +39,12 @@ pub struct HistoricalBarsQuery<'a> {
     timeframe: TimeFrame,
     start: Option<&'a str>,
     end: Option<&'a str>,
-    feed: Option<&'a str>,
+    feed: Option<StockFeed>,
+    adjustment: Option<Adjustment>,
     currency: Option<&'a str>,
     limit: Option<i32>,
+    page_size: Option<i32>,
+    page_token: Option<&'a str>,
     asof: Option<&'a str>,
     sort_asc: bool,
     sort_desc: bool,
@@ -49,7 +53,7 @@ pub struct HistoricalBarsQuery<'a> {
 pub struct LatestBarsQuery<'a> {
     url: &'a str,
     symbols: Vec<&'a str>,
-    feed: Option<&'a str>,
+    feed: Option<StockFeed>,
     currency: Option<&'a str>,
 }
 
@@ -62,8 +66,11 @@ impl<'a> HistoricalBarsQuery<'a> {
             start: None,
             end: None,
             feed: None,
+            adjustment: None,
             currency: None,
             limit: None,
+            page_size: None,
+            page_token: None,
             asof: None,
             sort_asc: false,
             sort_desc: false,
@@ -80,21 +87,39 @@ impl<'a> HistoricalBarsQuery<'a> {
         self
     }
 
-    pub fn feed(mut self, feed: &'a str) -> Self {
+    pub fn feed(mut self, feed: StockFeed) -> Self {
         self.feed = Some(feed);
         self
     }
 
+    pub fn adjustment(mut self, adjustment: Adjustment) -> Self {
+        self.adjustment = Some(adjustment);
+        self
+    }
+
     pub fn currency(mut self, currency: &'a str) -> Self {
         self.currency = Some(currency);
         self
     }
 
+    /// Maximum number of bars returned in total, across all symbols and pages.
     pub fn limit(mut self, limit: i32) -> Self {
         self.limit = Some(limit);
         self
     }
 
+    /// Number of bars requested per page, up to 10,000.
+    pub fn page_size(mut self, page_size: i32) -> Self {
+        self.page_size = Some(page_size);
+        self
+    }
+
+    /// Resume from a `next_page_token` returned by an earlier request.
+    pub fn page_token(mut self, page_token: &'a str) -> Self {
+        self.page_token = Some(page_token);
+        self
+    }
+
     pub fn asof(mut self, asof: &'a str) -> Self {
         self.asof = Some(asof);
         self
@@ -124,12 +149,12 @@ impl<'a> HistoricalBarsQuery<'a> {
         if let Some(end) = self.end {
             query.push_str(&format!("&end={end}"));
         }
-        if let Some(limit) = self.limit {
-            query.push_str(&format!("&limit={limit}"));
-        }
         if let Some(feed) = self.feed {
             query.push_str(&format!("&feed={feed}"));
         }
+        if let Some(adjustment) = self.adjustment {
+            query.push_str(&format!("&adjustment={adjustment}"));
+        }
         if let Some(currency) = self.currency {
             query.push_str(&format!("&currency={currency}"));
         }
@@ -148,22 +173,34 @@ impl<'a> HistoricalBarsQuery<'a> {
     pub fn send(&self) -> Result<HistoricalBars, ureq::Error> {
         let route = self.build();
         let mut multi_bars: HistoricalBars = HashMap::new();
-        let mut page_token = None;
+        let mut page_token = self.page_token.map(String::from);
+        let mut received = 0;
+        let mut page = 0;
 
         loop {
+            let mut temp_address = route.clone();
+            if let Some(limit) = page_limit(self.page_size, self.limit, received) {
+                temp_address.push_str(&format!("&limit={}", limit));
+            }
             // If a token exists, append to address
-            let temp_address = match page_token {
-                Some(token) => format!("{}&page_token={}", &route, &token),
-                _ => route.clone(),
-            };
+            if let Some(token) = &page_token {
+                temp_address.push_str(&format!("&page_token={}", token));
+            }
+            page += 1;
+            let _page = crate::instrument::page_span(page);
             let response = request("GET", &temp_address).call()?;
             let response: HistoricalBarsResponse = response.into_body().read_json()?;
 
             // Add multi_bars to collection
             for (symbol, bars) in response.bars {
+                received += bars.len() as i32;
                 multi_bars.entry(symbol).or_insert(Vec::new()).extend(bars);
             }
 
+            if self.limit.is_some_and(|limit| received >= limit) {
+                break;
+            }
+
             // If a token is in response, assign to page_token for next loop
             match response.next_page_token {
                 Some(next_page_token) => page_token = Some(next_page_token.clone()),
@@ -185,7 +222,7 @@ impl<'a> LatestBarsQuery<'a> {
         }
     }
 
-    pub fn feed(mut self, feed: &'a str) -> Self {
+    pub fn feed(mut self, feed: StockFeed) -> Self {
         self.feed = Some(feed);
         self
     }
@@ -232,19 +269,33 @@ mod tests {
         let res = HistoricalBarsQuery::new(vec!["AAPL"], TimeFrame::OneDay)
             .start("2022-02-01")
             .end("2022-03-10")
-            .feed("iex")
+            .feed(StockFeed::Iex)
             .limit(2)
             .send()
             .unwrap();
 
         dbg!(&res);
         assert!(res.contains_key("AAPL"));
+        assert!(res["AAPL"].len() <= 2);
+    }
+
+    #[test]
+    fn test_historical_bar_query_build() {
+        let route = HistoricalBarsQuery::new(vec!["AAPL", "TSLA"], TimeFrame::OneDay)
+            .feed(StockFeed::Sip)
+            .adjustment(Adjustment::All)
+            .build();
+
+        assert_eq!(
+            route,
+            "https://data.alpaca.markets/v2/stocks/bars?symbols=AAPL,TSLA&timeframe=1D&feed=sip&adjustment=all"
+        );
     }
 
     #[test]
     fn test_latest_bar_query() {
         let res = LatestBarsQuery::new(vec!["AAPL", "TSLA"])
-            .feed("iex")
+            .feed(StockFeed::Iex)
             .send()
             .unwrap();
 
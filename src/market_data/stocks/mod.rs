@@ -12,3 +12,6 @@ pub use snapshot::*;
 
 mod trades;
 pub use trades::*;
+
+mod types;
+pub use types::*;
@@ -1,3 +1,4 @@
+use super::types::{StockFeed, page_limit};
 use crate::request;
 use serde::Deserialize;
 use std::collections::HashMap;
@@ -15,8 +16,8 @@ pub struct StockQuote {
     pub z: String,      // Condition
 }
 
-type HistoricalQuotes = HashMap<String, Vec<StockQuote>>;
-type LatestQuotes = HashMap<String, StockQuote>;
+pub type HistoricalQuotes = HashMap<String, Vec<StockQuote>>;
+pub type LatestQuotes = HashMap<String, StockQuote>;
 
 #[derive(Deserialize, Debug)]
 pub struct HistoricalQuotesResponse {
@@ -34,9 +35,11 @@ pub struct HistoricalQuotesQuery<'a> {
     symbols: Vec<&'a str>,
     start: Option<&'a str>,
     end: Option<&'a str>,
-    feed: Option<&'a str>,
+    feed: Option<StockFeed>,
     currency: Option<&'a str>,
     limit: Option<i32>,
+    page_size: Option<i32>,
+    page_token: Option<&'a str>,
     asof: Option<&'a str>,
     sort_asc: bool,
     sort_desc: bool,
@@ -45,7 +48,7 @@ pub struct HistoricalQuotesQuery<'a> {
 pub struct LatestQuotesQuery<'a> {
     url: &'a str,
     symbols: Vec<&'a str>,
-    feed: Option<&'a str>,
+    feed: Option<StockFeed>,
     currency: Option<&'a str>,
 }
 
@@ -59,6 +62,8 @@ impl<'a> HistoricalQuotesQuery<'a> {
             feed: None,
             currency: None,
             limit: None,
+            page_size: None,
+            page_token: None,
             asof: None,
             sort_asc: false,
             sort_desc: false,
@@ -75,7 +80,7 @@ impl<'a> HistoricalQuotesQuery<'a> {
         self
     }
 
-    pub fn feed(mut self, feed: &'a str) -> Self {
+    pub fn feed(mut self, feed: StockFeed) -> Self {
         self.feed = Some(feed);
         self
     }
@@ -85,11 +90,24 @@ impl<'a> HistoricalQuotesQuery<'a> {
         self
     }
 
+    /// Maximum number of quotes returned in total, across all symbols and pages. Defaults to 1000.
     pub fn limit(mut self, limit: i32) -> Self {
         self.limit = Some(limit);
         self
     }
 
+    /// Number of quotes requested per page, up to 10,000.
+    pub fn page_size(mut self, page_size: i32) -> Self {
+        self.page_size = Some(page_size);
+        self
+    }
+
+    /// Resume from a `next_page_token` returned by an earlier request.
+    pub fn page_token(mut self, page_token: &'a str) -> Self {
+        self.page_token = Some(page_token);
+        self
+    }
+
     pub fn asof(mut self, asof: &'a str) -> Self {
         self.asof = Some(asof);
         self
@@ -126,10 +144,6 @@ impl<'a> HistoricalQuotesQuery<'a> {
             query.push_str(&format!("&currency={}", currency));
         }
 
-        if let Some(limit) = self.limit {
-            query.push_str(&format!("&limit={}", limit));
-        }
-
         if let Some(asof) = self.asof {
             query.push_str(&format!("&asof={}", asof));
         }
@@ -146,24 +160,27 @@ impl<'a> HistoricalQuotesQuery<'a> {
     pub fn send(&self) -> Result<HistoricalQuotes, ureq::Error> {
         let route = self.build();
         let mut quotes: HistoricalQuotes = HashMap::new();
-        let mut page_token: Option<String> = None;
+        let mut page_token = self.page_token.map(String::from);
 
         // this endpoint returns page tokens no matter what.so we need to apply the limit. Default is 1000.
         let mut i = 0;
-        let data_limit = if let Some(limit) = self.limit {
-            limit
-        } else {
-            1000
-        };
+        let data_limit = self.limit.unwrap_or(1000);
+        let mut page = 0;
         loop {
             if i >= data_limit {
                 break;
             }
+
+            let mut temp_address = route.clone();
+            if let Some(limit) = page_limit(self.page_size, Some(data_limit), i) {
+                temp_address.push_str(&format!("&limit={}", limit));
+            }
             // If a token exists, append to address
-            let temp_address = match page_token {
-                Some(token) => format!("{}&page_token={}", &route, &token),
-                _ => route.clone(),
-            };
+            if let Some(token) = &page_token {
+                temp_address.push_str(&format!("&page_token={}", token));
+            }
+            page += 1;
+            let _page = crate::instrument::page_span(page);
             let response = request("GET", &temp_address).call()?;
             let response: HistoricalQuotesResponse = response.into_body().read_json()?;
 
@@ -194,7 +211,7 @@ impl<'a> LatestQuotesQuery<'a> {
         }
     }
 
-    pub fn feed(mut self, feed: &'a str) -> Self {
+    pub fn feed(mut self, feed: StockFeed) -> Self {
         self.feed = Some(feed);
         self
     }
@@ -238,7 +255,7 @@ mod tests {
     #[test]
     fn test_historical_quotes_query() {
         let res = HistoricalQuotesQuery::new(vec!["AAPL"])
-            .feed("sip")
+            .feed(StockFeed::Sip)
             .limit(5)
             .send()
             .unwrap();
@@ -250,7 +267,7 @@ mod tests {
     #[test]
     fn test_latest_quotes_query() {
         let res = LatestQuotesQuery::new(vec!["AAPL"])
-            .feed("iex")
+            .feed(StockFeed::Iex)
             .send()
             .unwrap();
 
@@ -1,4 +1,4 @@
-use super::{bars::StockBar, quotes::StockQuote, trades::StockTrade};
+use super::{bars::StockBar, quotes::StockQuote, trades::StockTrade, types::StockFeed};
 use crate::request;
 use serde::Deserialize;
 use std::collections::HashMap;
@@ -18,7 +18,7 @@ pub type Snapshots = HashMap<String, StockSnapshot>;
 pub struct SnapshotsQuery<'a> {
     url: &'a str,
     symbols: Vec<&'a str>,
-    feed: Option<&'a str>,
+    feed: Option<StockFeed>,
     currency: Option<&'a str>,
 }
 
@@ -32,7 +32,7 @@ impl<'a> SnapshotsQuery<'a> {
         }
     }
 
-    pub fn feed(mut self, feed: &'a str) -> Self {
+    pub fn feed(mut self, feed: StockFeed) -> Self {
         self.feed = Some(feed);
         self
     }
@@ -69,7 +69,7 @@ mod tests {
     #[test]
     fn test_snapshot_query() {
         let res = SnapshotsQuery::new(vec!["AAPL"])
-            .feed("iex")
+            .feed(StockFeed::Iex)
             .send()
             .unwrap();
 
@@ -1,3 +1,4 @@
+use super::types::{StockFeed, page_limit};
 use crate::request;
 use serde::Deserialize;
 use std::collections::HashMap;
@@ -32,9 +33,11 @@ pub struct HistoricalTradesQuery<'a> {
     symbols: Vec<&'a str>,
     start: Option<&'a str>,
     end: Option<&'a str>,
-    feed: Option<&'a str>,
+    feed: Option<StockFeed>,
     currency: Option<&'a str>,
     limit: Option<i32>,
+    page_size: Option<i32>,
+    page_token: Option<&'a str>,
     asof: Option<&'a str>,
     sort_asc: bool,
     sort_desc: bool,
@@ -43,7 +46,7 @@ pub struct HistoricalTradesQuery<'a> {
 pub struct LatestTradesQuery<'a> {
     url: &'a str,
     symbols: Vec<&'a str>,
-    feed: Option<&'a str>,
+    feed: Option<StockFeed>,
     currency: Option<&'a str>,
 }
 
@@ -57,6 +60,8 @@ impl<'a> HistoricalTradesQuery<'a> {
             feed: None,
             currency: None,
             limit: None,
+            page_size: None,
+            page_token: None,
             asof: None,
             sort_asc: false,
             sort_desc: false,
@@ -73,7 +78,7 @@ impl<'a> HistoricalTradesQuery<'a> {
         self
     }
 
-    pub fn feed(mut self, feed: &'a str) -> Self {
+    pub fn feed(mut self, feed: StockFeed) -> Self {
         self.feed = Some(feed);
         self
     }
@@ -83,11 +88,24 @@ impl<'a> HistoricalTradesQuery<'a> {
         self
     }
 
+    /// Maximum number of trades returned in total, across all symbols and pages. Defaults to 1000.
     pub fn limit(mut self, limit: i32) -> Self {
         self.limit = Some(limit);
         self
     }
 
+    /// Number of trades requested per page, up to 10,000.
+    pub fn page_size(mut self, page_size: i32) -> Self {
+        self.page_size = Some(page_size);
+        self
+    }
+
+    /// Resume from a `next_page_token` returned by an earlier request.
+    pub fn page_token(mut self, page_token: &'a str) -> Self {
+        self.page_token = Some(page_token);
+        self
+    }
+
     pub fn asof(mut self, asof: &'a str) -> Self {
         self.asof = Some(asof);
         self
@@ -124,10 +142,6 @@ impl<'a> HistoricalTradesQuery<'a> {
             query.push_str(&format!("&currency={}", currency));
         }
 
-        if let Some(limit) = self.limit {
-            query.push_str(&format!("&limit={}", limit));
-        }
-
         if let Some(asof) = self.asof {
             query.push_str(&format!("&asof={}", asof));
         }
@@ -144,24 +158,26 @@ impl<'a> HistoricalTradesQuery<'a> {
     pub fn send(&self) -> Result<HistoricalTrades, ureq::Error> {
         let route = self.build();
         let mut trades: HistoricalTrades = HashMap::new();
-        let mut page_token = None;
+        let mut page_token = self.page_token.map(String::from);
 
         let mut i = 0;
-        let data_limit = if let Some(limit) = self.limit {
-            limit
-        } else {
-            1000
-        };
+        let data_limit = self.limit.unwrap_or(1000);
+        let mut page = 0;
         loop {
             if i >= data_limit {
                 break;
             }
 
+            let mut temp_address = route.clone();
+            if let Some(limit) = page_limit(self.page_size, Some(data_limit), i) {
+                temp_address.push_str(&format!("&limit={}", limit));
+            }
             // If a token exists, append to address
-            let temp_address = match page_token {
-                Some(token) => format!("{}&page_token={}", &route, &token),
-                _ => route.clone(),
-            };
+            if let Some(token) = &page_token {
+                temp_address.push_str(&format!("&page_token={}", token));
+            }
+            page += 1;
+            let _page = crate::instrument::page_span(page);
             let response = request("GET", &temp_address).call()?;
             let response: HistoricalTradesResponse = response.into_body().read_json()?;
 
@@ -192,7 +208,7 @@ impl<'a> LatestTradesQuery<'a> {
         }
     }
 
-    pub fn feed(mut self, feed: &'a str) -> Self {
+    pub fn feed(mut self, feed: StockFeed) -> Self {
         self.feed = Some(feed);
         self
     }
@@ -228,7 +244,7 @@ mod tests {
     #[test]
     fn test_historical_trade_query() {
         let res = HistoricalTradesQuery::new(vec!["AAPL"])
-            .feed("sip")
+            .feed(StockFeed::Sip)
             .limit(10)
             .send()
             .unwrap();
@@ -0,0 +1,86 @@
+use std::fmt;
+
+/// Maximum number of data points the stock endpoints return per request.
+pub(crate) const MAX_PAGE_SIZE: i32 = 10_000;
+
+/// Data feed options for historical and latest stock market data.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StockFeed {
+    /// Investors Exchange only. Available on the free plan.
+    Iex,
+    /// All US exchanges. Requires a paid subscription for recent data.
+    Sip,
+    /// SIP data delayed by 15 minutes.
+    DelayedSip,
+    /// Blue Ocean ATS, the overnight trading venue.
+    Boats,
+    /// Alpaca's derived overnight feed.
+    Overnight,
+    /// Over-the-counter exchanges.
+    Otc,
+}
+
+impl fmt::Display for StockFeed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let feed = match self {
+            StockFeed::Iex => "iex",
+            StockFeed::Sip => "sip",
+            StockFeed::DelayedSip => "delayed_sip",
+            StockFeed::Boats => "boats",
+            StockFeed::Overnight => "overnight",
+            StockFeed::Otc => "otc",
+        };
+        write!(f, "{}", feed)
+    }
+}
+
+/// Corporate action adjustment applied to historical bars.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Adjustment {
+    /// No adjustment.
+    Raw,
+    /// Adjust prices and volumes for splits.
+    Split,
+    /// Adjust prices for dividends.
+    Dividend,
+    /// Adjust for both splits and dividends.
+    All,
+}
+
+impl fmt::Display for Adjustment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let adjustment = match self {
+            Adjustment::Raw => "raw",
+            Adjustment::Split => "split",
+            Adjustment::Dividend => "dividend",
+            Adjustment::All => "all",
+        };
+        write!(f, "{}", adjustment)
+    }
+}
+
+/// The `limit` to request for the next page, given the per request page size,
+/// the overall limit, and how many data points have been received so far.
+pub(crate) fn page_limit(page_size: Option<i32>, limit: Option<i32>, received: i32) -> Option<i32> {
+    let remaining = limit.map(|limit| (limit - received).max(0));
+    match (page_size, remaining) {
+        (Some(size), Some(remaining)) => Some(size.min(remaining)),
+        (Some(size), None) => Some(size),
+        (None, Some(remaining)) => Some(remaining.min(MAX_PAGE_SIZE)),
+        (None, None) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_limit() {
+        assert_eq!(page_limit(None, None, 0), None);
+        assert_eq!(page_limit(Some(100), None, 500), Some(100));
+        assert_eq!(page_limit(Some(100), Some(250), 200), Some(50));
+        assert_eq!(page_limit(None, Some(25_000), 0), Some(MAX_PAGE_SIZE));
+        assert_eq!(page_limit(None, Some(25_000), 20_000), Some(5_000));
+    }
+}
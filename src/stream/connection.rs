@@ -1,8 +1,11 @@
+use std::io::{self, ErrorKind};
 use std::net::TcpStream;
+use std::time::Duration;
 use tungstenite::stream::MaybeTlsStream;
 use tungstenite::{connect, Message, WebSocket};
 
 use super::error::StreamError;
+use super::recording::Recorder;
 use super::types::*;
 
 pub(crate) type WsStream = WebSocket<MaybeTlsStream<TcpStream>>;
@@ -20,10 +23,53 @@ pub(crate) fn ws_send(socket: &mut WsStream, json: &serde_json::Value) -> Result
     Ok(())
 }
 
-/// Read the next text message from the socket. Handles ping/pong internally.
-pub(crate) fn ws_read_text(socket: &mut WsStream) -> Result<String, StreamError> {
+/// Set the timeout for reads on the socket, `None` blocks indefinitely.
+/// Fails on a stream type whose underlying socket can't be reached.
+pub(crate) fn ws_set_read_timeout(
+    socket: &mut WsStream,
+    timeout: Option<Duration>,
+) -> Result<(), StreamError> {
+    let stream = match socket.get_mut() {
+        MaybeTlsStream::Plain(stream) => stream,
+        MaybeTlsStream::NativeTls(stream) => stream.get_mut(),
+        _ => {
+            return Err(StreamError::WebSocket(
+                io::Error::new(
+                    ErrorKind::Unsupported,
+                    "read timeouts are not supported on this stream type",
+                )
+                .into(),
+            ));
+        }
+    };
+    stream
+        .set_read_timeout(timeout)
+        .map_err(|e| StreamError::WebSocket(e.into()))
+}
+
+/// Read the next text message from the socket, writing it to `recorder` if given. Handles ping/pong internally.
+/// Returns `StreamError::Timeout` when a read timeout is set and expires.
+pub(crate) fn ws_read_text(
+    socket: &mut WsStream,
+    recorder: Option<&Recorder>,
+) -> Result<String, StreamError> {
+    let text = ws_read_frame(socket)?;
+    if let Some(recorder) = recorder {
+        recorder.record(&text);
+    }
+    Ok(text)
+}
+
+fn ws_read_frame(socket: &mut WsStream) -> Result<String, StreamError> {
     loop {
-        let msg = socket.read()?;
+        let msg = match socket.read() {
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+            {
+                return Err(StreamError::Timeout);
+            }
+            msg => msg?,
+        };
         match msg {
             Message::Text(text) => return Ok(text),
             Message::Binary(data) => {
@@ -46,9 +92,10 @@ pub(crate) fn auth_market_data(
     socket: &mut WsStream,
     key: &str,
     secret: &str,
+    recorder: Option<&Recorder>,
 ) -> Result<(), StreamError> {
     // Read the initial welcome message [{"T":"success","msg":"connected"}]
-    let _welcome = ws_read_text(socket)?;
+    let _welcome = ws_read_text(socket, recorder)?;
 
     let auth_msg = serde_json::json!({
         "action": "auth",
@@ -57,7 +104,7 @@ pub(crate) fn auth_market_data(
     });
     ws_send(socket, &auth_msg)?;
 
-    let response = ws_read_text(socket)?;
+    let response = ws_read_text(socket, recorder)?;
     let parsed: serde_json::Value = serde_json::from_str(&response)?;
 
     if let Some(arr) = parsed.as_array() {
@@ -79,6 +126,7 @@ pub(crate) fn auth_trade_updates(
     socket: &mut WsStream,
     key: &str,
     secret: &str,
+    recorder: Option<&Recorder>,
 ) -> Result<(), StreamError> {
     let auth_msg = serde_json::json!({
         "action": "authenticate",
@@ -89,7 +137,7 @@ pub(crate) fn auth_trade_updates(
     });
     ws_send(socket, &auth_msg)?;
 
-    let response = ws_read_text(socket)?;
+    let response = ws_read_text(socket, recorder)?;
     let parsed: serde_json::Value = serde_json::from_str(&response)?;
 
     if parsed.get("stream").and_then(|v| v.as_str()) == Some("authorization") {
@@ -128,6 +176,17 @@ pub(crate) fn parse_market_data_messages(
     Ok(messages)
 }
 
+/// Parse a trade updates frame, `None` for frames from other streams such as `listening`.
+pub(crate) fn parse_trade_update(text: &str) -> Result<Option<TradeUpdate>, StreamError> {
+    let parsed: serde_json::Value = serde_json::from_str(text)?;
+    match parsed.get("data") {
+        Some(data) if parsed.get("stream").and_then(|v| v.as_str()) == Some("trade_updates") => {
+            Ok(Some(serde_json::from_value(data.clone())?))
+        }
+        _ => Ok(None),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -137,6 +196,8 @@ mod tests {
         let json = r#"[{"T":"t","S":"AAPL","i":1,"x":"V","p":126.55,"s":1,"t":"2021-02-22T15:51:44.208Z"},{"T":"b","S":"SPY","o":388.0,"h":389.0,"l":388.0,"c":389.0,"v":100,"t":"2021-02-22T19:15:00Z","n":10,"vw":388.5}]"#;
         let messages = parse_market_data_messages(json).unwrap();
         assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].kind(), "trade");
+        assert_eq!(messages[1].kind(), "bar");
     }
 
     #[test]
@@ -10,6 +10,8 @@ pub enum StreamError {
     AuthFailed(String),
     /// The connection was closed by the server.
     ConnectionClosed,
+    /// No message arrived before the read timeout.
+    Timeout,
 }
 
 impl fmt::Display for StreamError {
@@ -19,6 +21,7 @@ impl fmt::Display for StreamError {
             StreamError::Json(e) => write!(f, "JSON error: {}", e),
             StreamError::AuthFailed(msg) => write!(f, "Authentication failed: {}", msg),
             StreamError::ConnectionClosed => write!(f, "Connection closed"),
+            StreamError::Timeout => write!(f, "Timed out waiting for a message"),
         }
     }
 }
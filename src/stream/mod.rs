@@ -13,4 +13,7 @@ mod crypto;
 pub use crypto::CryptoStream;
 
 mod trade_updates;
-pub use trade_updates::TradeUpdateStream;
+pub use trade_updates::{TradeUpdateStream, TradeUpdateSubscription};
+
+mod recording;
+pub use recording::{Pace, RecordedFrame, Recorder, Replayer, read_recording};
@@ -0,0 +1,289 @@
+//! Record the raw frames a stream receives to a gzip-compressed file, and replay them later through the
+//! same parsing and handler signature as the live streams.
+//!
+//! ```no_run
+//! use alpaca_api_client::stream::{Feed, Pace, Recorder, Replayer, StockStream};
+//!
+//! let recorder = Recorder::create("session.jsonl.gz").unwrap();
+//! StockStream::new(Feed::Iex)
+//!     .subscribe_trades(vec!["AAPL"])
+//!     .record(&recorder)
+//!     .start(|msg| println!("{:?}", msg))
+//!     .unwrap();
+//!
+//! // Later, replay the session ten times faster than it was recorded
+//! Replayer::open("session.jsonl.gz")
+//!     .unwrap()
+//!     .pace(Pace::Accelerated(10.0))
+//!     .start(|msg| println!("{:?}", msg))
+//!     .unwrap();
+//! ```
+
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use super::connection::{parse_market_data_messages, parse_trade_update};
+use super::error::StreamError;
+use super::types::{MarketDataMessage, TradeUpdate};
+use crate::time::{now_rfc3339, parse_rfc3339_nanos};
+
+/// How often buffered frames are flushed, so a recording cut short by a crash is still readable.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// One frame as it was received, one JSON object per line of the recording.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    /// When the frame was read, RFC 3339 in UTC.
+    pub received_at: String,
+    /// `stock`, `crypto` or `trade_updates`.
+    pub stream: String,
+    /// The frame exactly as the server sent it.
+    pub text: String,
+}
+
+struct FrameWriter {
+    encoder: GzEncoder<BufWriter<File>>,
+    last_flush: Instant,
+    /// The first write that failed since the last `flush`.
+    error: Option<io::Error>,
+}
+
+/// Writes every frame read by the streams it is attached to. Clones share the same file, which is
+/// completed when the last clone is dropped.
+#[derive(Clone)]
+pub struct Recorder {
+    writer: Arc<Mutex<FrameWriter>>,
+    stream: &'static str,
+}
+
+impl Recorder {
+    /// Create the recording file, replacing any existing one.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = BufWriter::new(File::create(path)?);
+        Ok(Self {
+            writer: Arc::new(Mutex::new(FrameWriter {
+                encoder: GzEncoder::new(file, Compression::default()),
+                last_flush: Instant::now(),
+                error: None,
+            })),
+            stream: "",
+        })
+    }
+
+    /// Write out buffered frames. Fails with the first error writing a frame since the last call, in
+    /// which case frames are missing from the recording.
+    pub fn flush(&self) -> io::Result<()> {
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(e) = writer.error.take() {
+            return Err(e);
+        }
+        writer.last_flush = Instant::now();
+        writer.encoder.flush()
+    }
+
+    /// A handle that labels its frames with `stream`.
+    pub(crate) fn for_stream(&self, stream: &'static str) -> Self {
+        Self {
+            writer: Arc::clone(&self.writer),
+            stream,
+        }
+    }
+
+    pub(crate) fn record(&self, text: &str) {
+        let frame = RecordedFrame {
+            received_at: now_rfc3339(),
+            stream: self.stream.to_string(),
+            text: text.to_string(),
+        };
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        let result = serde_json::to_vec(&frame)
+            .map_err(io::Error::from)
+            .and_then(|mut line| {
+                line.push(b'\n');
+                writer.encoder.write_all(&line)?;
+                if writer.last_flush.elapsed() >= FLUSH_INTERVAL {
+                    writer.last_flush = Instant::now();
+                    writer.encoder.flush()?;
+                }
+                Ok(())
+            });
+        if let Err(e) = result {
+            writer.error.get_or_insert(e);
+        }
+    }
+}
+
+/// Read every frame of a recording. A recording that was cut short, or is still being written, is read
+/// up to its last complete flush.
+pub fn read_recording(path: impl AsRef<Path>) -> io::Result<Vec<RecordedFrame>> {
+    let reader = BufReader::new(GzDecoder::new(BufReader::new(File::open(path)?)));
+    let mut frames = Vec::new();
+    for line in reader.lines() {
+        let line = match line {
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+            line => line?,
+        };
+        if !line.trim().is_empty() {
+            frames.push(serde_json::from_str(&line)?);
+        }
+    }
+    Ok(frames)
+}
+
+/// How fast a `Replayer` delivers frames.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pace {
+    /// With the same gaps between frames as when they were recorded.
+    Original,
+    /// With the gaps divided by the given factor. A factor of zero or less is as fast as possible.
+    Accelerated(f64),
+    /// Without waiting between frames.
+    AsFastAsPossible,
+}
+
+/// Feeds recorded frames to a handler, like `StockStream::start` and `TradeUpdateStream::start`.
+pub struct Replayer {
+    frames: Vec<RecordedFrame>,
+    pace: Pace,
+}
+
+impl Replayer {
+    pub fn new(frames: Vec<RecordedFrame>) -> Self {
+        Self {
+            frames,
+            pace: Pace::Original,
+        }
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(read_recording(path)?))
+    }
+
+    pub fn pace(mut self, pace: Pace) -> Self {
+        self.pace = pace;
+        self
+    }
+
+    /// Only replay frames recorded from `stream`, for recordings shared by several streams.
+    pub fn stream(mut self, stream: &str) -> Self {
+        self.frames.retain(|frame| frame.stream == stream);
+        self
+    }
+
+    pub fn frames(&self) -> &[RecordedFrame] {
+        &self.frames
+    }
+
+    /// Call the closure for every market data message in the stock and crypto frames.
+    /// Blocks the calling thread until the recording ends, and fails on a frame that does not parse.
+    pub fn start<F>(self, mut handler: F) -> Result<(), StreamError>
+    where
+        F: FnMut(MarketDataMessage),
+    {
+        self.play(
+            |frame| frame.stream != "trade_updates",
+            |text| {
+                parse_market_data_messages(text)?
+                    .into_iter()
+                    .for_each(&mut handler);
+                Ok(())
+            },
+        )
+    }
+
+    /// Call the closure for every trade update in the trade updates frames.
+    pub fn start_trade_updates<F>(self, mut handler: F) -> Result<(), StreamError>
+    where
+        F: FnMut(TradeUpdate),
+    {
+        self.play(
+            |frame| frame.stream == "trade_updates",
+            |text| {
+                if let Some(update) = parse_trade_update(text)? {
+                    handler(update);
+                }
+                Ok(())
+            },
+        )
+    }
+
+    fn play<P, F>(self, include: P, mut deliver: F) -> Result<(), StreamError>
+    where
+        P: Fn(&RecordedFrame) -> bool,
+        F: FnMut(&str) -> Result<(), StreamError>,
+    {
+        let speed = match self.pace {
+            Pace::Original => Some(1.0),
+            Pace::Accelerated(factor) if factor > 0.0 => Some(factor),
+            Pace::Accelerated(_) | Pace::AsFastAsPossible => None,
+        };
+        let started = Instant::now();
+        let mut first = None;
+        for frame in self.frames.iter().filter(|frame| include(frame)) {
+            if let Some(speed) = speed
+                && let Some(at) = parse_rfc3339_nanos(&frame.received_at)
+            {
+                let offset = at - *first.get_or_insert(at);
+                let due = started + Duration::from_secs_f64(offset.max(0) as f64 / 1e9 / speed);
+                thread::sleep(due.saturating_duration_since(Instant::now()));
+            }
+            deliver(&frame.text)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_record_and_replay() {
+        let dir = std::env::temp_dir().join(format!("apca-recording-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("session.jsonl.gz");
+
+        let recorder = Recorder::create(&path).unwrap();
+        let stock = recorder.for_stream("stock");
+        stock.record(r#"[{"T":"success","msg":"connected"}]"#);
+        stock.record(r#"[{"T":"t","S":"AAPL","i":1,"x":"V","p":126.55,"s":1,"t":"2021-02-22T15:51:44.208Z"},{"T":"q","S":"AAPL","bx":"V","bp":126.5,"bs":1,"ax":"V","ap":126.6,"as":2,"t":"2021-02-22T15:51:44.209Z"}]"#);
+        recorder.for_stream("trade_updates").record(
+            r#"{"stream":"trade_updates","data":{"event":"new","timestamp":"2021-02-22T15:51:44.210Z","order":{"id":"o1","client_order_id":"c1","created_at":"2021-02-22T15:51:44.210Z","updated_at":"2021-02-22T15:51:44.210Z","submitted_at":"2021-02-22T15:51:44.210Z","asset_id":"a1","symbol":"AAPL","asset_class":"us_equity","qty":"1","filled_qty":"0","order_type":"market","type":"market","side":"buy","time_in_force":"day","status":"new","extended_hours":false}}}"#,
+        );
+
+        // Readable before the recorder is dropped once flushed
+        recorder.flush().unwrap();
+        assert_eq!(read_recording(&path).unwrap().len(), 3);
+        drop((recorder, stock));
+
+        let frames = read_recording(&path).unwrap();
+        assert_eq!(frames[0].stream, "stock");
+        assert_eq!(frames[0].text, r#"[{"T":"success","msg":"connected"}]"#);
+
+        let mut kinds = Vec::new();
+        Replayer::new(frames.clone())
+            .pace(Pace::AsFastAsPossible)
+            .start(|msg| kinds.push(msg.kind()))
+            .unwrap();
+        assert_eq!(kinds, ["trade", "quote"]);
+
+        let mut events = Vec::new();
+        Replayer::new(frames)
+            .stream("trade_updates")
+            .pace(Pace::Accelerated(100.0))
+            .start_trade_updates(|update| events.push(update.event))
+            .unwrap();
+        assert_eq!(events, ["new"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,7 +1,8 @@
-use crate::get_auth;
+use crate::{get_auth, instrument::StreamMonitor};
 
 use super::connection::*;
 use super::error::StreamError;
+use super::recording::Recorder;
 use super::types::*;
 
 /// Builder for a stock market data WebSocket stream.
@@ -34,6 +35,7 @@ pub struct StockStream {
     daily_bars: Vec<String>,
     updated_bars: Vec<String>,
     statuses: Vec<String>,
+    recorder: Option<Recorder>,
 }
 
 impl StockStream {
@@ -46,6 +48,7 @@ impl StockStream {
             daily_bars: Vec::new(),
             updated_bars: Vec::new(),
             statuses: Vec::new(),
+            recorder: None,
         }
     }
 
@@ -79,6 +82,12 @@ impl StockStream {
         self
     }
 
+    /// Write every frame received to `recorder`, for replay with `Replayer`.
+    pub fn record(mut self, recorder: &Recorder) -> Self {
+        self.recorder = Some(recorder.for_stream("stock"));
+        self
+    }
+
     /// Connect, authenticate, subscribe, and begin the message loop.
     /// Calls the provided closure for every received market data message.
     /// This method blocks the calling thread until the connection closes or an error occurs.
@@ -92,31 +101,39 @@ impl StockStream {
         );
         let (key, secret) = get_auth();
 
-        let mut socket = ws_connect(&url)?;
-        auth_market_data(&mut socket, &key, &secret)?;
-
-        let sub_msg = serde_json::json!({
-            "action": "subscribe",
-            "trades": self.trades,
-            "quotes": self.quotes,
-            "bars": self.bars,
-            "dailyBars": self.daily_bars,
-            "updatedBars": self.updated_bars,
-            "statuses": self.statuses,
-        });
-        ws_send(&mut socket, &sub_msg)?;
-
-        // Read subscription confirmation
-        let _sub_response = ws_read_text(&mut socket)?;
-
-        // Message loop
-        loop {
-            let text = ws_read_text(&mut socket)?;
-            let messages = parse_market_data_messages(&text)?;
-            for msg in messages {
-                handler(msg);
+        let mut monitor = StreamMonitor::new("stock", &url);
+        monitor.watch(|monitor| {
+            let mut socket = ws_connect(&url)?;
+            monitor.connected();
+            auth_market_data(&mut socket, &key, &secret, self.recorder.as_ref())?;
+            monitor.authenticated();
+
+            let sub_msg = serde_json::json!({
+                "action": "subscribe",
+                "trades": self.trades,
+                "quotes": self.quotes,
+                "bars": self.bars,
+                "dailyBars": self.daily_bars,
+                "updatedBars": self.updated_bars,
+                "statuses": self.statuses,
+            });
+            ws_send(&mut socket, &sub_msg)?;
+            monitor.subscribed(&sub_msg);
+
+            // Read subscription confirmation
+            let _sub_response = ws_read_text(&mut socket, self.recorder.as_ref())?;
+
+            // Message loop
+            loop {
+                let text = ws_read_text(&mut socket, self.recorder.as_ref())?;
+                let messages = parse_market_data_messages(&text)?;
+                for msg in messages {
+                    monitor.market_data(&msg);
+                    let _timer = monitor.time_handler();
+                    handler(msg);
+                }
             }
-        }
+        })
     }
 }
 
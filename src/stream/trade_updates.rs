@@ -1,8 +1,12 @@
+use std::time::{Duration, Instant};
+
 use crate::get_auth;
+use crate::instrument::StreamMonitor;
 use crate::trading::AccountType;
 
 use super::connection::*;
 use super::error::StreamError;
+use super::recording::Recorder;
 use super::types::TradeUpdate;
 
 /// Builder for the trade updates (account events) WebSocket stream.
@@ -20,11 +24,55 @@ use super::types::TradeUpdate;
 /// ```
 pub struct TradeUpdateStream {
     account_type: AccountType,
+    recorder: Option<Recorder>,
 }
 
 impl TradeUpdateStream {
     pub fn new(account_type: AccountType) -> Self {
-        Self { account_type }
+        Self {
+            account_type,
+            recorder: None,
+        }
+    }
+
+    /// Write every frame received to `recorder`, for replay with `Replayer::start_trade_updates`.
+    pub fn record(mut self, recorder: &Recorder) -> Self {
+        self.recorder = Some(recorder.for_stream("trade_updates"));
+        self
+    }
+
+    /// Connect, authenticate and subscribe to trade_updates, returning a subscription to read updates from.
+    /// Use this to subscribe before submitting an order, so no update is missed.
+    pub fn connect(self) -> Result<TradeUpdateSubscription, StreamError> {
+        let url = match self.account_type {
+            AccountType::Paper => "wss://paper-api.alpaca.markets/stream",
+            AccountType::Live => "wss://api.alpaca.markets/stream",
+        };
+        let (key, secret) = get_auth();
+
+        let mut monitor = StreamMonitor::new("trade_updates", url);
+        let socket = monitor.watch(|monitor| {
+            let mut socket = ws_connect(url)?;
+            monitor.connected();
+            auth_trade_updates(&mut socket, &key, &secret, self.recorder.as_ref())?;
+            monitor.authenticated();
+
+            let listen_msg = serde_json::json!({
+                "action": "listen",
+                "data": {
+                    "streams": ["trade_updates"]
+                }
+            });
+            ws_send(&mut socket, &listen_msg)?;
+            monitor.subscribed(&listen_msg);
+            Ok(socket)
+        })?;
+
+        Ok(TradeUpdateSubscription {
+            socket,
+            monitor,
+            recorder: self.recorder,
+        })
     }
 
     /// Connect, authenticate, subscribe to trade_updates, and begin the message loop.
@@ -34,33 +82,86 @@ impl TradeUpdateStream {
     where
         F: FnMut(TradeUpdate),
     {
-        let url = match self.account_type {
-            AccountType::Paper => "wss://paper-api.alpaca.markets/stream",
-            AccountType::Live => "wss://api.alpaca.markets/stream",
-        };
-        let (key, secret) = get_auth();
+        self.start_while(|update| {
+            handler(update);
+            true
+        })
+    }
+
+    /// Like `start`, but returns `Ok(())` as soon as the closure returns `false`.
+    pub fn start_while<F>(self, handler: F) -> Result<(), StreamError>
+    where
+        F: FnMut(TradeUpdate) -> bool,
+    {
+        self.connect()?.start_while(handler)
+    }
+}
+
+/// A connected trade updates stream, read one update at a time.
+pub struct TradeUpdateSubscription {
+    socket: WsStream,
+    monitor: StreamMonitor,
+    recorder: Option<Recorder>,
+}
 
-        let mut socket = ws_connect(url)?;
-        auth_trade_updates(&mut socket, &key, &secret)?;
+impl TradeUpdateSubscription {
+    /// The next trade update, or `Ok(None)` if none arrives within `timeout`. `None` waits indefinitely.
+    pub fn next(&mut self, timeout: Option<Duration>) -> Result<Option<TradeUpdate>, StreamError> {
+        let update = self.read(timeout);
+        match &update {
+            Ok(Some(update)) => self
+                .monitor
+                .message("trade_update", update.timestamp.as_deref()),
+            Ok(None) => {}
+            Err(e) => self.monitor.error(e),
+        }
+        update
+    }
+
+    /// Call the closure for every trade update until the connection closes, like `TradeUpdateStream::start`.
+    pub fn start<F>(self, mut handler: F) -> Result<(), StreamError>
+    where
+        F: FnMut(TradeUpdate),
+    {
+        self.start_while(|update| {
+            handler(update);
+            true
+        })
+    }
 
-        let listen_msg = serde_json::json!({
-            "action": "listen",
-            "data": {
-                "streams": ["trade_updates"]
+    /// Like `start`, but returns `Ok(())` as soon as the closure returns `false`.
+    pub fn start_while<F>(mut self, mut handler: F) -> Result<(), StreamError>
+    where
+        F: FnMut(TradeUpdate) -> bool,
+    {
+        loop {
+            if let Some(update) = self.next(None)? {
+                let _timer = self.monitor.time_handler();
+                if !handler(update) {
+                    return Ok(());
+                }
             }
-        });
-        ws_send(&mut socket, &listen_msg)?;
+        }
+    }
 
-        // Message loop
+    fn read(&mut self, timeout: Option<Duration>) -> Result<Option<TradeUpdate>, StreamError> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
         loop {
-            let text = ws_read_text(&mut socket)?;
-            let parsed: serde_json::Value = serde_json::from_str(&text)?;
+            let remaining = match deadline {
+                Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                    Some(remaining) if !remaining.is_zero() => Some(remaining),
+                    _ => return Ok(None),
+                },
+                None => None,
+            };
+            ws_set_read_timeout(&mut self.socket, remaining)?;
 
-            if parsed.get("stream").and_then(|v| v.as_str()) == Some("trade_updates") {
-                if let Some(data) = parsed.get("data") {
-                    let update: TradeUpdate = serde_json::from_value(data.clone())?;
-                    handler(update);
-                }
+            let text = match ws_read_text(&mut self.socket, self.recorder.as_ref()) {
+                Err(StreamError::Timeout) => return Ok(None),
+                text => text?,
+            };
+            if let Some(update) = parse_trade_update(&text)? {
+                return Ok(Some(update));
             }
         }
     }
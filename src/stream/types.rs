@@ -99,8 +99,22 @@ pub enum MarketDataMessage {
     Status(StreamStatus),
 }
 
+impl MarketDataMessage {
+    /// The message type, e.g. `trade` or `daily_bar`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            MarketDataMessage::Trade(_) => "trade",
+            MarketDataMessage::Quote(_) => "quote",
+            MarketDataMessage::Bar(_) => "bar",
+            MarketDataMessage::DailyBar(_) => "daily_bar",
+            MarketDataMessage::UpdatedBar(_) => "updated_bar",
+            MarketDataMessage::Status(_) => "status",
+        }
+    }
+}
+
 /// A trade update message from the account stream.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 pub struct TradeUpdate {
     pub event: String,
     pub order: Order,
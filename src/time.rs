@@ -0,0 +1,169 @@
+//! Date and timestamp helpers shared by the modules that do arithmetic on API timestamps.
+
+/// Parse an RFC 3339 timestamp such as `2021-02-22T15:51:44.208Z` into nanoseconds since the Unix epoch.
+pub(crate) fn parse_rfc3339_nanos(s: &str) -> Option<i64> {
+    let bytes = s.as_bytes();
+    if bytes.len() < 20 || bytes[4] != b'-' || bytes[7] != b'-' || bytes[10] != b'T' {
+        return None;
+    }
+    let num = |range: std::ops::Range<usize>| -> Option<i64> { s.get(range)?.parse().ok() };
+    let (year, month, day) = (num(0..4)?, num(5..7)?, num(8..10)?);
+    let (hour, minute, second) = (num(11..13)?, num(14..16)?, num(17..19)?);
+
+    let mut rest = s.get(19..)?;
+    let mut nanos = 0;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 {
+            return None;
+        }
+        let padded = format!("{:0<9}", &fraction[..digits.min(9)]);
+        nanos = padded.parse::<i64>().ok()?;
+        rest = &fraction[digits..];
+    }
+
+    let offset_seconds = match rest {
+        "Z" | "z" => 0,
+        _ if rest.len() == 6
+            && (rest.starts_with('+') || rest.starts_with('-'))
+            && rest.get(3..4) == Some(":") =>
+        {
+            let sign = if rest.starts_with('-') { -1 } else { 1 };
+            let hours = rest.get(1..3)?.parse::<i64>().ok()?;
+            let minutes = rest.get(4..6)?.parse::<i64>().ok()?;
+            sign * (hours * 3600 + minutes * 60)
+        }
+        _ => return None,
+    };
+
+    let seconds = days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second
+        - offset_seconds;
+    Some(seconds * 1_000_000_000 + nanos)
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+pub(crate) fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Proleptic Gregorian (year, month, day) for days since 1970-01-01.
+pub(crate) fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// The current time as an RFC 3339 timestamp in UTC with microseconds, like the API returns.
+pub(crate) fn now_rfc3339() -> String {
+    let since_epoch = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    let seconds = since_epoch.as_secs() as i64;
+    let (year, month, day) = civil_from_days(seconds.div_euclid(86_400));
+    let time = seconds.rem_euclid(86_400);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:06}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60,
+        since_epoch.subsec_micros()
+    )
+}
+
+/// Days since the Unix epoch for a YYYY-MM-DD date, ignoring anything after the date.
+pub(crate) fn parse_date_days(date: &str) -> Option<i64> {
+    let num = |range: std::ops::Range<usize>| -> Option<i64> { date.get(range)?.parse().ok() };
+    if date.as_bytes().get(4) != Some(&b'-') || date.as_bytes().get(7) != Some(&b'-') {
+        return None;
+    }
+    Some(days_from_civil(num(0..4)?, num(5..7)?, num(8..10)?))
+}
+
+/// Nanoseconds since the Unix epoch for a wall clock time in New York on a YYYY-MM-DD date.
+pub(crate) fn new_york_nanos(date: &str, hour: i64, minute: i64) -> Option<i64> {
+    let days = parse_date_days(date)?;
+    let (year, _, _) = civil_from_days(days);
+    // Daylight saving time runs from the second Sunday in March to the first Sunday in November
+    let first_sunday = |month| {
+        let first = days_from_civil(year, month, 1);
+        // Day 3, 1970-01-04, was a Sunday
+        first + (3 - first).rem_euclid(7)
+    };
+    let dst = days >= first_sunday(3) + 7 && days < first_sunday(11);
+    let utc_hour = hour + if dst { 4 } else { 5 };
+    Some((days * 86_400 + utc_hour * 3600 + minute * 60) * 1_000_000_000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rfc3339_nanos() {
+        assert_eq!(parse_rfc3339_nanos("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(
+            parse_rfc3339_nanos("2021-02-22T15:51:44.208Z"),
+            Some(1_614_009_104_208_000_000)
+        );
+        assert_eq!(
+            parse_rfc3339_nanos("2021-02-22T10:51:44.208123456-05:00"),
+            Some(1_614_009_104_208_123_456)
+        );
+        assert_eq!(parse_rfc3339_nanos("2021-02-22"), None);
+        assert_eq!(parse_rfc3339_nanos("2021-02-22T15:51:44+0é00"), None);
+        assert_eq!(parse_rfc3339_nanos("2021-02-22T15:51:44+05000"), None);
+        assert_eq!(parse_rfc3339_nanos("2021-02-22T15:51:4é+05:00"), None);
+    }
+
+    #[test]
+    fn test_parse_date_days() {
+        let days = |date| parse_date_days(date).unwrap();
+        assert_eq!(days("2024-03-01") - days("2024-02-28"), 2);
+        assert_eq!(days("2024-12-31") - days("2025-01-17"), -17);
+        assert_eq!(parse_date_days("2024-1-2"), None);
+        assert_eq!(parse_date_days("1970-01-02T00:00:00Z"), Some(1));
+    }
+
+    #[test]
+    fn test_civil_from_days() {
+        for date in ["1970-01-01", "2000-02-29", "2024-12-31", "1969-07-20"] {
+            let (year, month, day) = civil_from_days(parse_date_days(date).unwrap());
+            assert_eq!(format!("{:04}-{:02}-{:02}", year, month, day), date);
+        }
+        assert_eq!(
+            new_york_nanos("2025-01-17", 16, 0),
+            parse_rfc3339_nanos("2025-01-17T16:00:00-05:00")
+        );
+        assert_eq!(
+            new_york_nanos("2025-03-09", 16, 0),
+            parse_rfc3339_nanos("2025-03-09T16:00:00-04:00")
+        );
+        assert_eq!(
+            new_york_nanos("2025-11-01", 9, 30),
+            parse_rfc3339_nanos("2025-11-01T09:30:00-04:00")
+        );
+        assert_eq!(
+            new_york_nanos("2025-11-02", 9, 30),
+            parse_rfc3339_nanos("2025-11-02T09:30:00-05:00")
+        );
+
+        let now = now_rfc3339();
+        assert!(parse_rfc3339_nanos(&now).is_some(), "{}", now);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{json_request, request};
+use crate::{dry_run, json_request, request};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -172,11 +172,25 @@ impl<'a> PatchAccountConfigQuery<'a> {
         self
     }
 
+    /// In dry-run mode only the fields set on the query are returned, the rest are `None`.
     pub fn send(self, account_type: AccountType) -> Result<AccountConfiguration, ureq::Error> {
         let url = match account_type {
             AccountType::Live => "https://api.alpaca.markets/v2/account/configurations",
             AccountType::Paper => "https://paper-api.alpaca.markets/v2/account/configurations",
         };
+        if dry_run::intercept_json("PATCH", url, &self) {
+            return Ok(AccountConfiguration {
+                dtbp_check: self.dtbp_check.map(str::to_string),
+                trade_confirm_email: self.trade_confirm_email.map(str::to_string),
+                suspend_trade: self.suspend_trade,
+                no_shorting: self.no_shorting,
+                fractional_trading: self.fractional_trading,
+                max_margin_multiplier: self.max_margin_multiplier.map(str::to_string),
+                max_options_trading_level: self.max_options_trading_level,
+                pdt_check: self.pdt_check.map(str::to_string),
+                ptp_no_exception_entry: self.ptp_no_exception_entry,
+            });
+        }
 
         let response = json_request("PATCH", url)
             .header("Content-Type", "application/json")
@@ -1,29 +1,229 @@
 use super::AccountType;
-use crate::request;
-use serde::Deserialize;
+use crate::{market_data::options::OptionSymbol, request};
+use serde::{Deserialize, Deserializer, de};
 
-#[derive(Deserialize, Debug)]
+/// A trade fill (`FILL`) activity.
+#[derive(Deserialize, Debug, Clone)]
 pub struct TradeActivity {
-    pub activity_type: Option<String>,
     pub id: String,
-    pub cum_qty: Option<String>,
-    pub leaves_qty: Option<String>,
-    pub price: Option<String>,
+    pub activity_type: String,
+    pub transaction_time: String,
+    /// `fill` or `partial_fill`
+    pub r#type: String,
+    pub price: String,
+    pub qty: String,
+    pub side: String,
+    pub symbol: String,
+    pub leaves_qty: String,
+    pub cum_qty: String,
+    pub order_id: String,
+    pub order_status: Option<String>,
+}
+
+/// A dividend activity (`DIV`, `DIVCGL`, `DIVCGS`, `DIVFEE`, `DIVFT`, `DIVNRA`, `DIVROC`, `DIVTW`, `DIVTXEX`).
+#[derive(Deserialize, Debug, Clone)]
+pub struct DividendActivity {
+    pub id: String,
+    pub activity_type: String,
+    pub date: String,
+    pub net_amount: String,
+    pub symbol: String,
     pub qty: Option<String>,
-    pub side: Option<String>,
+    pub per_share_amount: Option<String>,
+    pub description: Option<String>,
+    pub status: Option<String>,
+}
+
+/// A fee activity (`FEE`, `CFEE`, `PTC`).
+#[derive(Deserialize, Debug, Clone)]
+pub struct FeeActivity {
+    pub id: String,
+    pub activity_type: String,
+    pub date: String,
+    pub net_amount: String,
     pub symbol: Option<String>,
-    pub transaction_time: Option<String>,
-    pub order_id: Option<String>,
-    pub r#type: Option<String>,
-    pub order_status: Option<String>,
+    pub qty: Option<String>,
+    pub description: Option<String>,
+    pub status: Option<String>,
+}
+
+/// A journal activity (`JNL`, `JNLC`, `JNLS`).
+#[derive(Deserialize, Debug, Clone)]
+pub struct JournalActivity {
+    pub id: String,
+    pub activity_type: String,
+    pub date: String,
+    pub net_amount: String,
+    pub symbol: Option<String>,
+    pub qty: Option<String>,
+    pub description: Option<String>,
+    pub status: Option<String>,
+    pub group_id: Option<String>,
+}
+
+/// A cash or asset transfer activity (`CSD`, `CSW`, `TRANS`, `ACATC`, `ACATS`).
+#[derive(Deserialize, Debug, Clone)]
+pub struct TransferActivity {
+    pub id: String,
+    pub activity_type: String,
+    pub date: String,
+    pub net_amount: String,
+    pub symbol: Option<String>,
+    pub qty: Option<String>,
+    pub description: Option<String>,
+    pub status: Option<String>,
+}
+
+/// An interest activity (`INT`, `INTNRA`, `INTTW`).
+#[derive(Deserialize, Debug, Clone)]
+pub struct InterestActivity {
+    pub id: String,
+    pub activity_type: String,
+    pub date: String,
+    pub net_amount: String,
+    pub description: Option<String>,
+    pub status: Option<String>,
+}
+
+/// Option lifecycle event of an [`OptionActivity`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OptionEvent {
+    /// `OPASN`, a short contract was assigned.
+    Assignment,
+    /// `OPEXP`, a contract expired worthless.
+    Expiration,
+    /// `OPEXC` or `OPXRC`, a long contract was exercised.
+    Exercise,
+}
+
+/// An option assignment (`OPASN`), expiration (`OPEXP`) or exercise (`OPEXC`, `OPXRC`) activity.
+#[derive(Deserialize, Debug, Clone)]
+pub struct OptionActivity {
+    pub id: String,
+    pub activity_type: String,
+    pub date: String,
+    /// The contract symbol.
+    pub symbol: String,
+    pub qty: Option<String>,
+    pub price: Option<String>,
+    pub net_amount: Option<String>,
+    pub description: Option<String>,
+    pub status: Option<String>,
+    pub group_id: Option<String>,
+}
+
+impl OptionActivity {
+    pub fn event(&self) -> OptionEvent {
+        match self.activity_type.as_str() {
+            "OPASN" => OptionEvent::Assignment,
+            "OPEXP" => OptionEvent::Expiration,
+            _ => OptionEvent::Exercise,
+        }
+    }
+
+    /// The parsed OCC `symbol`.
+    pub fn option_symbol(&self) -> Option<OptionSymbol> {
+        self.symbol.parse().ok()
+    }
+}
+
+/// Any other non-trade activity, such as mergers (`MA`), name changes (`NC`) or splits (`SSP`, `SSO`).
+#[derive(Deserialize, Debug, Clone)]
+pub struct NonTradeActivity {
+    pub id: String,
+    pub activity_type: String,
     pub date: Option<String>,
     pub net_amount: Option<String>,
+    pub symbol: Option<String>,
+    pub qty: Option<String>,
     pub per_share_amount: Option<String>,
-    pub group_id: Option<String>,
+    pub description: Option<String>,
     pub status: Option<String>,
 }
 
-pub type TradeActivities = Vec<TradeActivity>;
+/// An account activity, typed by its `activity_type`.
+#[derive(Debug, Clone)]
+pub enum Activity {
+    Fill(TradeActivity),
+    Dividend(DividendActivity),
+    Fee(FeeActivity),
+    Journal(JournalActivity),
+    Transfer(TransferActivity),
+    Interest(InterestActivity),
+    Option(OptionActivity),
+    Other(NonTradeActivity),
+}
+
+impl Activity {
+    pub fn id(&self) -> &str {
+        match self {
+            Activity::Fill(a) => &a.id,
+            Activity::Dividend(a) => &a.id,
+            Activity::Fee(a) => &a.id,
+            Activity::Journal(a) => &a.id,
+            Activity::Transfer(a) => &a.id,
+            Activity::Interest(a) => &a.id,
+            Activity::Option(a) => &a.id,
+            Activity::Other(a) => &a.id,
+        }
+    }
+
+    pub fn activity_type(&self) -> &str {
+        match self {
+            Activity::Fill(a) => &a.activity_type,
+            Activity::Dividend(a) => &a.activity_type,
+            Activity::Fee(a) => &a.activity_type,
+            Activity::Journal(a) => &a.activity_type,
+            Activity::Transfer(a) => &a.activity_type,
+            Activity::Interest(a) => &a.activity_type,
+            Activity::Option(a) => &a.activity_type,
+            Activity::Other(a) => &a.activity_type,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Activity {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let activity_type = value
+            .get("activity_type")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+
+        let v = value.clone();
+        let typed = match activity_type.as_str() {
+            "FILL" => serde_json::from_value(v).map(Activity::Fill).ok(),
+            t if t.starts_with("DIV") => serde_json::from_value(v).map(Activity::Dividend).ok(),
+            "FEE" | "CFEE" | "PTC" => serde_json::from_value(v).map(Activity::Fee).ok(),
+            "JNL" | "JNLC" | "JNLS" => serde_json::from_value(v).map(Activity::Journal).ok(),
+            "CSD" | "CSW" | "TRANS" | "ACATC" | "ACATS" => {
+                serde_json::from_value(v).map(Activity::Transfer).ok()
+            }
+            "INT" | "INTNRA" | "INTTW" => serde_json::from_value(v).map(Activity::Interest).ok(),
+            "OPASN" | "OPEXP" | "OPEXC" | "OPXRC" => {
+                serde_json::from_value(v).map(Activity::Option).ok()
+            }
+            _ => None,
+        };
+
+        // Unknown types, and known types missing one of their typed fields, are kept untyped
+        match typed {
+            Some(activity) => Ok(activity),
+            None => serde_json::from_value(value)
+                .map(Activity::Other)
+                .map_err(de::Error::custom),
+        }
+    }
+}
+
+pub type Activities = Vec<Activity>;
+
+/// The most activities the API returns per page.
+const MAX_PAGE_SIZE: usize = 100;
 
 pub struct ActivitiesQuery<'a> {
     url: &'a str,
@@ -34,6 +234,7 @@ pub struct ActivitiesQuery<'a> {
     after: Option<&'a str>,
     direction: Option<&'a str>,
     page_size: Option<usize>,
+    page_token: Option<&'a str>,
     limit: Option<usize>,
 }
 
@@ -51,6 +252,7 @@ impl<'a> ActivitiesQuery<'a> {
             after: None,
             direction: None,
             page_size: None,
+            page_token: None,
             limit: None,
         }
     }
@@ -87,11 +289,19 @@ impl<'a> ActivitiesQuery<'a> {
         self
     }
 
+    /// Activities per request, at most 100.
     pub fn page_size(mut self, page_size: usize) -> Self {
-        self.page_size = Some(page_size);
+        self.page_size = Some(page_size.min(MAX_PAGE_SIZE));
         self
     }
 
+    /// Resume after the activity with this id.
+    pub fn page_token(mut self, page_token: &'a str) -> Self {
+        self.page_token = Some(page_token);
+        self
+    }
+
+    /// Maximum number of activities returned in total, across all pages. Defaults to 1000.
     pub fn limit(mut self, limit: usize) -> Self {
         self.limit = Some(limit);
         self
@@ -123,29 +333,15 @@ impl<'a> ActivitiesQuery<'a> {
         format!("{}?{}", self.url, query)
     }
 
-    pub fn send(&self) -> Result<TradeActivities, ureq::Error> {
+    pub fn send(&self) -> Result<Activities, ureq::Error> {
         let route = self.build();
-        let mut trade_activities: TradeActivities = Vec::new();
-        let mut page_token = None;
-
-        let mut i = 0;
-        let data_limit = if let Some(limit) = self.limit {
-            limit
-        } else {
-            1000
-        };
-
-        let expected_page_size = if let Some(size) = self.page_size {
-            size
-        } else {
-            100
-        };
+        let mut activities: Activities = Vec::new();
+        let mut page_token = self.page_token.map(String::from);
 
-        loop {
-            if i >= data_limit {
-                break;
-            }
+        let data_limit = self.limit.unwrap_or(1000);
+        let expected_page_size = self.page_size.unwrap_or(MAX_PAGE_SIZE);
 
+        while activities.len() < data_limit {
             // If a token exists, append to address
             let temp_address = match page_token {
                 Some(ref token) => format!("{}&page_token={}", &route, &token),
@@ -153,23 +349,22 @@ impl<'a> ActivitiesQuery<'a> {
             };
 
             let response = request("GET", &temp_address).call()?;
-            let response: TradeActivities = response.into_body().read_json()?;
-            let returned_page_size = response.len();
-
-            for (index, item) in response.into_iter().enumerate() {
-                i += 1;
-                if index == returned_page_size && returned_page_size == expected_page_size {
-                    page_token = Some(item.id.clone());
-                }
-                trade_activities.push(item);
-            }
+            let response: Activities = response.into_body().read_json()?;
+
+            // A full page means there may be more, continue after the last activity
+            page_token = match response.last() {
+                Some(last) if response.len() == expected_page_size => Some(last.id().to_string()),
+                _ => None,
+            };
+            activities.extend(response);
 
             if page_token.is_none() {
                 break;
             }
         }
+        activities.truncate(data_limit);
 
-        Ok(trade_activities)
+        Ok(activities)
     }
 }
 
@@ -184,4 +379,41 @@ mod tests {
         dbg!(&query);
         assert!(query.len() > 0);
     }
+
+    #[test]
+    fn test_deserialize_activities() {
+        let json = r#"[
+            {"id":"20190524113406977::8efc7b9a","activity_type":"FILL","transaction_time":"2019-05-24T15:34:06.977Z","type":"fill","price":"1.63","qty":"1","side":"buy","symbol":"LPCN","leaves_qty":"0","order_id":"904837e3","cum_qty":"1","order_status":"filled"},
+            {"id":"20190801011955195::5f596936","activity_type":"DIV","date":"2019-08-01","net_amount":"1.02","symbol":"T","qty":"2","per_share_amount":"0.51"},
+            {"id":"20190802011955195::5f596937","activity_type":"JNLC","date":"2019-08-02","net_amount":"-100","description":"journal out","status":"executed"},
+            {"id":"20190803011955195::5f596938","activity_type":"SSP","date":"2019-08-03","symbol":"NVDA","qty":"9"},
+            {"id":"20240920011955195::5f596939","activity_type":"OPEXP","date":"2024-09-20","net_amount":"0","symbol":"AAPL240920C00250000","qty":"1","price":"0","description":"Option Expiry","status":"executed"},
+            {"id":"20240920011955195::5f596940","activity_type":"OPASN","date":"2024-09-20","net_amount":"0","symbol":"AAPL240920P00230000","qty":"-1","price":"0","description":"Option Assignment","status":"executed"},
+            {"id":"20190805011955195::5f596941","activity_type":"FILL","transaction_time":"2019-08-05T15:34:06.977Z","symbol":"LPCN"}
+        ]"#;
+        let activities: Activities = serde_json::from_str(json).unwrap();
+
+        assert!(matches!(&activities[0], Activity::Fill(fill) if fill.price == "1.63"));
+        assert!(matches!(&activities[1], Activity::Dividend(div) if div.symbol == "T"));
+        assert!(matches!(&activities[2], Activity::Journal(jnl) if jnl.net_amount == "-100"));
+        assert!(matches!(&activities[3], Activity::Other(other) if other.activity_type == "SSP"));
+        assert_eq!(activities[3].id(), "20190803011955195::5f596938");
+        assert!(
+            matches!(&activities[4], Activity::Option(op) if op.event() == OptionEvent::Expiration)
+        );
+        let Activity::Option(assignment) = &activities[5] else {
+            panic!("expected an option activity");
+        };
+        assert_eq!(assignment.event(), OptionEvent::Assignment);
+        assert_eq!(assignment.option_symbol().unwrap().strike(), 230.0);
+
+        // A fill without its trade fields doesn't fail the page
+        assert!(matches!(&activities[6], Activity::Other(other) if other.activity_type == "FILL"));
+        assert_eq!(
+            ActivitiesQuery::new(AccountType::Paper)
+                .page_size(500)
+                .page_size,
+            Some(100)
+        );
+    }
 }
@@ -0,0 +1,159 @@
+use super::AccountType;
+use crate::request;
+use serde::Deserialize;
+use std::fmt;
+
+/// Announcement types accepted by the `ca_types` filter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnnouncementType {
+    Dividend,
+    Merger,
+    Spinoff,
+    Split,
+}
+
+impl fmt::Display for AnnouncementType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ca_type = match self {
+            AnnouncementType::Dividend => "Dividend",
+            AnnouncementType::Merger => "Merger",
+            AnnouncementType::Spinoff => "Spinoff",
+            AnnouncementType::Split => "Split",
+        };
+        write!(f, "{}", ca_type)
+    }
+}
+
+/// API object for a corporate action announcement
+#[derive(Deserialize, Debug, Clone)]
+pub struct Announcement {
+    pub id: String,
+    pub corporate_action_id: String,
+    pub ca_type: String,
+    pub ca_sub_type: String,
+    pub initiating_symbol: Option<String>,
+    pub initiating_original_cusip: Option<String>,
+    pub target_symbol: Option<String>,
+    pub target_original_cusip: Option<String>,
+    pub declaration_date: Option<String>,
+    pub expiration_date: Option<String>,
+    pub record_date: Option<String>,
+    pub payable_date: Option<String>,
+    pub ex_date: Option<String>,
+    pub cash: Option<String>,
+    pub old_rate: Option<String>,
+    pub new_rate: Option<String>,
+}
+
+pub type Announcements = Vec<Announcement>;
+
+pub struct AnnouncementsQuery<'a> {
+    url: &'a str,
+    ca_types: Vec<AnnouncementType>,
+    since: &'a str,
+    until: &'a str,
+    symbol: Option<&'a str>,
+    cusip: Option<&'a str>,
+    date_type: Option<&'a str>,
+}
+
+impl<'a> AnnouncementsQuery<'a> {
+    /// `since` and `until` are dates (YYYY-MM-DD) at most 90 days apart.
+    pub fn new(
+        account_type: AccountType,
+        ca_types: Vec<AnnouncementType>,
+        since: &'a str,
+        until: &'a str,
+    ) -> Self {
+        Self {
+            url: match account_type {
+                AccountType::Live => {
+                    "https://api.alpaca.markets/v2/corporate_actions/announcements"
+                }
+                AccountType::Paper => {
+                    "https://paper-api.alpaca.markets/v2/corporate_actions/announcements"
+                }
+            },
+            ca_types,
+            since,
+            until,
+            symbol: None,
+            cusip: None,
+            date_type: None,
+        }
+    }
+
+    pub fn symbol(mut self, symbol: &'a str) -> Self {
+        self.symbol = Some(symbol);
+        self
+    }
+
+    pub fn cusip(mut self, cusip: &'a str) -> Self {
+        self.cusip = Some(cusip);
+        self
+    }
+
+    /// Which date `since` and `until` apply to: declaration_date, ex_date, record_date or payable_date.
+    pub fn date_type(mut self, date_type: &'a str) -> Self {
+        self.date_type = Some(date_type);
+        self
+    }
+
+    fn build(&self) -> String {
+        let ca_types: Vec<String> = self.ca_types.iter().map(|t| t.to_string()).collect();
+        let mut query = format!(
+            "ca_types={}&since={}&until={}",
+            ca_types.join(","),
+            self.since,
+            self.until
+        );
+        if let Some(symbol) = self.symbol {
+            query.push_str(&format!("&symbol={}", symbol));
+        }
+        if let Some(cusip) = self.cusip {
+            query.push_str(&format!("&cusip={}", cusip));
+        }
+        if let Some(date_type) = self.date_type {
+            query.push_str(&format!("&date_type={}", date_type));
+        }
+        format!("{}?{}", self.url, query)
+    }
+
+    pub fn get_by_id(self, id: &'a str) -> Result<Announcement, ureq::Error> {
+        let route = format!("{}/{}", self.url, id);
+        let response = request("GET", &route).call()?;
+        let announcement: Announcement = response.into_body().read_json()?;
+        Ok(announcement)
+    }
+
+    pub fn send(self) -> Result<Announcements, ureq::Error> {
+        let route = self.build();
+        let response = request("GET", &route).call()?;
+        let announcements: Announcements = response.into_body().read_json()?;
+        Ok(announcements)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_announcements_query() {
+        let res = AnnouncementsQuery::new(
+            AccountType::Paper,
+            vec![AnnouncementType::Dividend, AnnouncementType::Split],
+            "2024-05-01",
+            "2024-06-30",
+        )
+        .symbol("AAPL")
+        .send()
+        .unwrap();
+
+        dbg!(&res);
+        assert!(
+            res.iter()
+                .all(|a| a.ca_type == "dividend" || a.ca_type == "split")
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use super::{AllAssets, Asset, AssetsQuery};
+use crate::trading::{AccountType, positions::AllPositions};
+use std::collections::HashMap;
+
+/// Asset metadata keyed by symbol, used to validate orders before they are sent.
+///
+/// Load it once with [`AssetCache::load`] or build it from an existing [`AssetsQuery`] result,
+/// then reuse it across orders. Held positions are optional and only needed to detect short sells.
+#[derive(Debug, Default)]
+pub struct AssetCache {
+    assets: HashMap<String, Asset>,
+    positions: Option<HashMap<String, f64>>,
+}
+
+impl AssetCache {
+    pub fn load(account_type: AccountType) -> Result<Self, ureq::Error> {
+        let assets = AssetsQuery::new(account_type).status("active").send()?;
+        Ok(Self::from_assets(assets))
+    }
+
+    pub fn from_assets(assets: AllAssets) -> Self {
+        Self {
+            assets: assets
+                .into_iter()
+                .map(|asset| (normalize(&asset.symbol), asset))
+                .collect(),
+            positions: None,
+        }
+    }
+
+    /// Record currently held quantities so sell orders larger than the position are treated as shorts.
+    pub fn with_positions(mut self, positions: &AllPositions) -> Self {
+        self.positions = Some(
+            positions
+                .iter()
+                .map(|p| (normalize(&p.symbol), p.qty.parse().unwrap_or(0.0)))
+                .collect(),
+        );
+        self
+    }
+
+    /// Look up an asset by symbol. Crypto pairs match with or without the slash, e.g. `BTC/USD` or `BTCUSD`.
+    pub fn get(&self, symbol: &str) -> Option<&Asset> {
+        self.assets.get(&normalize(symbol))
+    }
+
+    /// Held quantity for a symbol, or `None` if positions were not provided.
+    pub fn held_qty(&self, symbol: &str) -> Option<f64> {
+        self.positions
+            .as_ref()
+            .map(|positions| positions.get(&normalize(symbol)).copied().unwrap_or(0.0))
+    }
+
+    pub fn len(&self) -> usize {
+        self.assets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty()
+    }
+}
+
+fn normalize(symbol: &str) -> String {
+    symbol.replace('/', "")
+}
@@ -3,3 +3,6 @@ pub use assets::*;
 
 mod option_contracts;
 pub use option_contracts::*;
+
+mod cache;
+pub use cache::*;
@@ -1,6 +1,6 @@
 use serde::Deserialize;
 
-use crate::{request, trading::AccountType};
+use crate::{market_data::options::OptionSymbol, request, trading::AccountType};
 
 #[derive(Deserialize, Debug)]
 pub struct OptionContract {
@@ -25,6 +25,13 @@ pub struct OptionContract {
     pub deliverables: Option<Vec<Deliverable>>,
 }
 
+impl OptionContract {
+    /// The parsed OCC `symbol`.
+    pub fn option_symbol(&self) -> Option<OptionSymbol> {
+        self.symbol.parse().ok()
+    }
+}
+
 #[derive(Deserialize, Debug)]
 pub struct Deliverable {
     pub r#type: String,
@@ -209,6 +216,7 @@ impl<'a> OptionContractsQuery<'a> {
         } else {
             100
         };
+        let mut page = 0;
         loop {
             if i >= data_limit {
                 break;
@@ -219,6 +227,8 @@ impl<'a> OptionContractsQuery<'a> {
                 Some(token) => format!("{}&page_token={}", &route, &token),
                 _ => route.clone(),
             };
+            page += 1;
+            let _page = crate::instrument::page_span(page);
             let response = request("GET", &temp_address).call()?;
             let response: OptionContractsResponse = response.into_body().read_json()?;
 
@@ -245,8 +255,9 @@ impl<'a> OptionContractsQuery<'a> {
         Ok(asset)
     }
 
-    pub fn get_by_symbol(self, symbol: &'a str) -> Result<OptionContract, ureq::Error> {
-        let route = format!("{}/{}", self.url, symbol);
+    /// `symbol` can be an OCC string or an [`OptionSymbol`](crate::market_data::options::OptionSymbol).
+    pub fn get_by_symbol(self, symbol: impl ToString) -> Result<OptionContract, ureq::Error> {
+        let route = format!("{}/{}", self.url, symbol.to_string());
         let response = request("GET", &route).call()?;
         let asset: OptionContract = response.into_body().read_json()?;
         Ok(asset)
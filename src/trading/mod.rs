@@ -1,12 +1,18 @@
 pub mod account;
 pub mod activities;
+pub mod announcements;
 pub mod assets;
 pub mod calendar;
 pub mod clock;
+pub mod options_risk;
 pub mod order;
 pub mod portfolio;
+pub mod position_book;
 pub mod positions;
+pub mod risk;
+pub mod watchlists;
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum AccountType {
     Live,
     Paper,
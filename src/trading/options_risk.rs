@@ -0,0 +1,572 @@
+use super::{
+    AccountType,
+    assets::{OptionContract, OptionContractsQuery},
+    positions::{AllPositions, Position, PositionsQuery},
+};
+use crate::market_data::{
+    options::{
+        ExerciseStyle, Greeks, OptionSnapshotQuery, OptionSnapshots, OptionSymbol, PricingInputs,
+    },
+    stocks::{LatestTrades, LatestTradesQuery},
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Contract size used when the contract details are unknown.
+const DEFAULT_MULTIPLIER: f64 = 100.0;
+
+/// The most contracts the contracts endpoint returns per request.
+const MAX_CONTRACTS_PER_REQUEST: usize = 10_000;
+
+/// Where the Greeks of a position came from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GreeksSource {
+    /// Stock, delta of one per share.
+    Stock,
+    /// Server side Greeks from the option snapshot.
+    Snapshot,
+    /// Computed locally from the implied volatility of the snapshot, quote or position price.
+    Computed,
+    /// Not enough data, the position does not contribute to the totals.
+    Missing,
+}
+
+/// Exposures of a group of positions. Delta and gamma are in shares of the underlying,
+/// the dollar variants are in dollars per 1% move, theta is dollars per day and vega dollars per volatility point.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RiskTotals {
+    pub delta: f64,
+    pub dollar_delta: f64,
+    pub gamma: f64,
+    pub dollar_gamma: f64,
+    pub theta: f64,
+    pub vega: f64,
+}
+
+impl RiskTotals {
+    fn add(&mut self, other: &RiskTotals) {
+        self.delta += other.delta;
+        self.dollar_delta += other.dollar_delta;
+        self.gamma += other.gamma;
+        self.dollar_gamma += other.dollar_gamma;
+        self.theta += other.theta;
+        self.vega += other.vega;
+    }
+}
+
+/// Risk of a single stock or option position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionRisk {
+    pub symbol: String,
+    /// Signed, negative for short positions.
+    pub qty: f64,
+    /// Shares per contract, 1 for stock.
+    pub multiplier: f64,
+    /// Per share Greeks of the contract.
+    pub greeks: Option<Greeks>,
+    pub source: GreeksSource,
+    /// Volatility used to reprice the contract in scenarios.
+    pub implied_volatility: Option<f64>,
+    /// Position exposures, the Greeks scaled by quantity and multiplier.
+    pub exposure: RiskTotals,
+    pricing: Option<PricingInputs>,
+}
+
+impl PositionRisk {
+    /// Profit or loss if the underlying moves from `spot` by `change` (0.05 for +5%).
+    fn scenario_pnl(&self, spot: Option<f64>, change: f64) -> f64 {
+        let Some(spot) = spot else { return 0.0 };
+        let move_size = spot * change;
+        match (self.pricing, self.implied_volatility) {
+            _ if self.source == GreeksSource::Stock => self.qty * move_size,
+            (Some(pricing), Some(volatility)) => {
+                let pricing = pricing.volatility(volatility);
+                let now = pricing.price();
+                let shocked = pricing.spot(spot + move_size).price();
+                (shocked - now) * self.qty * self.multiplier
+            }
+            // Delta-gamma approximation when the contract can not be repriced
+            _ => {
+                self.exposure.delta * move_size + 0.5 * self.exposure.gamma * move_size * move_size
+            }
+        }
+    }
+}
+
+/// Risk of all positions on one underlying.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnderlyingRisk {
+    pub underlying: String,
+    pub spot: Option<f64>,
+    pub positions: Vec<PositionRisk>,
+    pub totals: RiskTotals,
+}
+
+/// One row of the scenario grid: P&L when every underlying moves by `change`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioRow {
+    /// Fractional move, 0.05 for +5%.
+    pub change: f64,
+    /// P&L per underlying, in the order of [`OptionsRiskReport::underlyings`].
+    pub underlyings: Vec<(String, f64)>,
+    pub total: f64,
+}
+
+/// Net Greeks per underlying and for the whole portfolio.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionsRiskReport {
+    /// Sorted by underlying symbol.
+    pub underlyings: Vec<UnderlyingRisk>,
+    pub totals: RiskTotals,
+    /// Underlyings without a price, e.g. index roots with no stock trades. Their options only have
+    /// snapshot Greeks and no dollar exposures.
+    pub unpriced: Vec<String>,
+}
+
+impl OptionsRiskReport {
+    /// Fetch open positions, their option snapshots and contracts, and the latest underlying trades.
+    /// `valuation_date` (YYYY-MM-DD) is used for locally computed Greeks. Underlyings without a latest
+    /// trade are left unpriced and listed in `unpriced`.
+    pub fn load(account_type: AccountType, valuation_date: &str) -> Result<Self, ureq::Error> {
+        let positions = PositionsQuery::new(account_type).get_all_open_positions()?;
+
+        let option_symbols: Vec<&str> = positions
+            .iter()
+            .filter(|p| p.option_symbol().is_some())
+            .map(|p| p.symbol.as_str())
+            .collect();
+        let snapshots = if option_symbols.is_empty() {
+            OptionSnapshots::new()
+        } else {
+            OptionSnapshotQuery::new(option_symbols.clone()).send()?
+        };
+        let contracts = option_contracts(account_type, &positions)?;
+
+        let mut risk = OptionsRisk::new(&positions, valuation_date)
+            .snapshots(&snapshots)
+            .contracts(&contracts);
+        let underlyings = risk.underlying_symbols();
+        if !underlyings.is_empty() {
+            for (symbol, trade) in latest_trades(&underlyings) {
+                risk = risk.underlying_price(&symbol, trade.p);
+            }
+        }
+
+        Ok(risk.report())
+    }
+
+    pub fn underlying(&self, underlying: &str) -> Option<&UnderlyingRisk> {
+        self.underlyings.iter().find(|u| u.underlying == underlying)
+    }
+
+    /// P&L of the portfolio for each fractional underlying move, e.g. `&[-0.10, -0.05, 0.0, 0.05, 0.10]`.
+    /// Options are repriced at their implied volatility, falling back to a delta-gamma approximation.
+    pub fn scenario_grid(&self, changes: &[f64]) -> Vec<ScenarioRow> {
+        changes
+            .iter()
+            .map(|&change| {
+                let underlyings: Vec<(String, f64)> = self
+                    .underlyings
+                    .iter()
+                    .map(|u| {
+                        let pnl = u
+                            .positions
+                            .iter()
+                            .map(|p| p.scenario_pnl(u.spot, change))
+                            .sum();
+                        (u.underlying.clone(), pnl)
+                    })
+                    .collect();
+                ScenarioRow {
+                    change,
+                    total: underlyings.iter().map(|(_, pnl)| pnl).sum(),
+                    underlyings,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Contract details of the option positions, one request per underlying. Adjusted contracts listed
+/// under another underlying are looked up by symbol.
+fn option_contracts(
+    account_type: AccountType,
+    positions: &AllPositions,
+) -> Result<Vec<OptionContract>, ureq::Error> {
+    let mut held = HashSet::new();
+    let mut by_underlying: BTreeMap<String, Vec<OptionSymbol>> = BTreeMap::new();
+    for position in positions {
+        if let Some(symbol) = position.option_symbol() {
+            held.insert(position.symbol.as_str());
+            by_underlying
+                .entry(symbol.underlying().to_string())
+                .or_default()
+                .push(symbol);
+        }
+    }
+
+    let mut contracts = Vec::new();
+    for (underlying, symbols) in &by_underlying {
+        let first = symbols
+            .iter()
+            .map(|s| s.expiration())
+            .min()
+            .unwrap_or_default();
+        let last = symbols
+            .iter()
+            .map(|s| s.expiration())
+            .max()
+            .unwrap_or_default();
+        let low = symbols
+            .iter()
+            .map(|s| s.strike())
+            .fold(f64::INFINITY, f64::min);
+        let high = symbols.iter().map(|s| s.strike()).fold(0.0, f64::max);
+        let (low, high) = (low.to_string(), high.to_string());
+        let found = OptionContractsQuery::new(account_type)
+            .underlying_symbols(vec![underlying])
+            .expiration_date_gte(&first)
+            .expiration_date_lte(&last)
+            .strike_price_gte(&low)
+            .strike_price_lte(&high)
+            .limit(MAX_CONTRACTS_PER_REQUEST)
+            .send()?;
+        contracts.extend(
+            found
+                .into_iter()
+                .filter(|c| held.contains(c.symbol.as_str())),
+        );
+    }
+
+    for symbol in held {
+        if !contracts.iter().any(|c| c.symbol == symbol) {
+            contracts.push(OptionContractsQuery::new(account_type).get_by_symbol(symbol)?);
+        }
+    }
+    Ok(contracts)
+}
+
+/// Latest trades of the underlyings that have them. One symbol without trades, such as an index,
+/// fails the whole request, so the symbols are then requested one at a time.
+fn latest_trades(underlyings: &[String]) -> LatestTrades {
+    let symbols: Vec<&str> = underlyings.iter().map(|s| s.as_str()).collect();
+    match LatestTradesQuery::new(symbols.clone()).send() {
+        Ok(trades) => trades,
+        Err(_) => symbols
+            .into_iter()
+            .filter_map(|symbol| LatestTradesQuery::new(vec![symbol]).send().ok())
+            .flatten()
+            .collect(),
+    }
+}
+
+/// Builds an [`OptionsRiskReport`] from positions and the market data you already have.
+pub struct OptionsRisk<'a> {
+    positions: &'a AllPositions,
+    valuation_date: &'a str,
+    snapshots: Option<&'a OptionSnapshots>,
+    contracts: HashMap<String, &'a OptionContract>,
+    underlying_prices: HashMap<String, f64>,
+    rate: f64,
+}
+
+impl<'a> OptionsRisk<'a> {
+    pub fn new(positions: &'a AllPositions, valuation_date: &'a str) -> Self {
+        Self {
+            positions,
+            valuation_date,
+            snapshots: None,
+            contracts: HashMap::new(),
+            underlying_prices: HashMap::new(),
+            rate: 0.0,
+        }
+    }
+
+    /// Option snapshots keyed by contract symbol, the source of server side Greeks and implied volatility.
+    pub fn snapshots(mut self, snapshots: &'a OptionSnapshots) -> Self {
+        self.snapshots = Some(snapshots);
+        self
+    }
+
+    /// Contract details for multipliers, exercise style and the underlying of adjusted contracts.
+    /// Without them contracts are American with a multiplier of 100.
+    pub fn contracts(mut self, contracts: &'a [OptionContract]) -> Self {
+        self.contracts
+            .extend(contracts.iter().map(|c| (c.symbol.clone(), c)));
+        self
+    }
+
+    /// Underlying price for local Greeks and scenarios. Defaults to the price of a stock position in the underlying.
+    pub fn underlying_price(mut self, underlying: &str, price: f64) -> Self {
+        self.underlying_prices.insert(underlying.to_string(), price);
+        self
+    }
+
+    /// Risk free rate for locally computed Greeks.
+    pub fn rate(mut self, rate: f64) -> Self {
+        self.rate = rate;
+        self
+    }
+
+    /// Underlyings of all positions, sorted.
+    pub fn underlying_symbols(&self) -> Vec<String> {
+        let mut underlyings: Vec<String> = self
+            .positions
+            .iter()
+            .map(|p| self.underlying_of(p))
+            .collect();
+        underlyings.sort();
+        underlyings.dedup();
+        underlyings
+    }
+
+    pub fn report(&self) -> OptionsRiskReport {
+        let mut groups: BTreeMap<String, Vec<&Position>> = BTreeMap::new();
+        for position in self.positions {
+            groups
+                .entry(self.underlying_of(position))
+                .or_default()
+                .push(position);
+        }
+
+        let mut totals = RiskTotals::default();
+        let underlyings: Vec<UnderlyingRisk> = groups
+            .into_iter()
+            .map(|(underlying, positions)| {
+                let spot = self
+                    .underlying_prices
+                    .get(&underlying)
+                    .copied()
+                    .or_else(|| {
+                        positions
+                            .iter()
+                            .find(|p| p.option_symbol().is_none())
+                            .and_then(|p| p.current_price.parse().ok())
+                    });
+                let positions: Vec<PositionRisk> = positions
+                    .into_iter()
+                    .map(|p| self.position_risk(p, spot))
+                    .collect();
+                let mut underlying_totals = RiskTotals::default();
+                for position in &positions {
+                    underlying_totals.add(&position.exposure);
+                }
+                totals.add(&underlying_totals);
+                UnderlyingRisk {
+                    underlying,
+                    spot,
+                    positions,
+                    totals: underlying_totals,
+                }
+            })
+            .collect();
+
+        let unpriced = underlyings
+            .iter()
+            .filter(|u| u.spot.is_none())
+            .map(|u| u.underlying.clone())
+            .collect();
+        OptionsRiskReport {
+            underlyings,
+            totals,
+            unpriced,
+        }
+    }
+
+    fn underlying_of(&self, position: &Position) -> String {
+        match position.option_symbol() {
+            Some(symbol) => match self.contracts.get(&position.symbol) {
+                Some(contract) => contract.underlying_symbol.clone(),
+                None => symbol.underlying().to_string(),
+            },
+            None => position.symbol.clone(),
+        }
+    }
+
+    fn position_risk(&self, position: &Position, spot: Option<f64>) -> PositionRisk {
+        let mut qty: f64 = position.qty.parse().unwrap_or(0.0);
+        if position.side == "short" && qty > 0.0 {
+            qty = -qty;
+        }
+
+        let Some(symbol) = position.option_symbol() else {
+            return PositionRisk {
+                symbol: position.symbol.clone(),
+                qty,
+                multiplier: 1.0,
+                greeks: None,
+                source: GreeksSource::Stock,
+                implied_volatility: None,
+                exposure: exposure(
+                    &Greeks {
+                        delta: 1.0,
+                        gamma: 0.0,
+                        rho: 0.0,
+                        theta: 0.0,
+                        vega: 0.0,
+                    },
+                    qty,
+                    spot,
+                ),
+                pricing: None,
+            };
+        };
+
+        let contract = self.contracts.get(&position.symbol);
+        let multiplier = contract
+            .and_then(|c| c.multiplier.parse().ok())
+            .unwrap_or(DEFAULT_MULTIPLIER);
+        let style = contract
+            .and_then(|c| c.style.parse().ok())
+            .unwrap_or(ExerciseStyle::American);
+        let pricing = spot.and_then(|spot| {
+            Some(
+                PricingInputs::from_symbol(&symbol, spot, 0.0, self.valuation_date)?
+                    .style(style)
+                    .rate(self.rate),
+            )
+        });
+        let snapshot = self.snapshots.and_then(|s| s.get(&position.symbol));
+
+        // Volatility from the snapshot, its quote midpoint, or the position's last price
+        let implied_volatility = snapshot
+            .and_then(|s| s.implied_volatility)
+            .filter(|v| *v > 0.0)
+            .or_else(|| {
+                let pricing = pricing?;
+                snapshot
+                    .and_then(|s| pricing.implied_volatility_from_quote(s.latest_quote.as_ref()?))
+                    .or_else(|| pricing.implied_volatility(position.current_price.parse().ok()?))
+            });
+
+        let (greeks, source) = match (snapshot.and_then(|s| s.greeks), pricing, implied_volatility)
+        {
+            (Some(greeks), _, _) => (Some(greeks), GreeksSource::Snapshot),
+            (None, Some(pricing), Some(volatility)) => (
+                Some(pricing.volatility(volatility).greeks()),
+                GreeksSource::Computed,
+            ),
+            _ => (None, GreeksSource::Missing),
+        };
+
+        PositionRisk {
+            symbol: position.symbol.clone(),
+            qty,
+            multiplier,
+            exposure: greeks
+                .map(|g| exposure(&g, qty * multiplier, spot))
+                .unwrap_or_default(),
+            greeks,
+            source,
+            implied_volatility,
+            pricing,
+        }
+    }
+}
+
+/// Scale per share Greeks by a number of shares.
+fn exposure(greeks: &Greeks, shares: f64, spot: Option<f64>) -> RiskTotals {
+    let spot = spot.unwrap_or(0.0);
+    RiskTotals {
+        delta: greeks.delta * shares,
+        dollar_delta: greeks.delta * shares * spot * 0.01,
+        gamma: greeks.gamma * shares,
+        dollar_gamma: greeks.gamma * shares * spot * spot * 0.0001,
+        theta: greeks.theta * shares,
+        vega: greeks.vega * shares,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market_data::options::OptionSnapshot;
+
+    fn position(symbol: &str, asset_class: &str, qty: &str, side: &str, price: &str) -> Position {
+        serde_json::from_value(serde_json::json!({
+            "asset_id": "id", "symbol": symbol, "exchange": "", "asset_class": asset_class,
+            "avg_entry_price": price, "qty": qty, "qty_available": qty, "side": side,
+            "market_value": "0", "cost_basis": "0", "unrealized_pl": "0", "unrealized_plpc": "0",
+            "unrealized_intraday_pl": "0", "unrealized_intraday_plpc": "0",
+            "current_price": price, "lastday_price": price, "change_today": "0"
+        }))
+        .unwrap()
+    }
+
+    fn positions() -> AllPositions {
+        vec![
+            position("AAPL", "us_equity", "100", "long", "200"),
+            position("AAPL250620C00210000", "us_option", "-2", "short", "4.5"),
+            position("SPY250620P00500000", "us_option", "1", "long", "8"),
+        ]
+    }
+
+    #[test]
+    fn test_options_risk_report() {
+        let positions = positions();
+        let snapshots: OptionSnapshots = serde_json::from_value(serde_json::json!({
+            "AAPL250620C00210000": {
+                "greeks": {"delta": 0.4, "gamma": 0.02, "rho": 0.1, "theta": -0.05, "vega": 0.3},
+                "impliedVolatility": 0.25
+            }
+        }))
+        .unwrap();
+
+        let report = OptionsRisk::new(&positions, "2025-03-20")
+            .snapshots(&snapshots)
+            .underlying_price("SPY", 510.0)
+            .report();
+
+        let aapl = report.underlying("AAPL").unwrap();
+        assert_eq!(aapl.spot, Some(200.0));
+        assert_eq!(aapl.positions[1].source, GreeksSource::Snapshot);
+        // 100 shares minus 2 short calls at 0.4 delta x 100
+        assert!((aapl.totals.delta - 20.0).abs() < 1e-9);
+        assert!((aapl.totals.theta - 10.0).abs() < 1e-9);
+        assert!((aapl.totals.vega + 60.0).abs() < 1e-9);
+
+        // No snapshot, Greeks from the volatility implied by the position price
+        let spy = report.underlying("SPY").unwrap();
+        let put = &spy.positions[0];
+        assert_eq!(put.source, GreeksSource::Computed);
+        assert!(put.implied_volatility.is_some());
+        assert!(spy.totals.delta < 0.0 && spy.totals.delta > -100.0);
+
+        assert!((report.totals.vega - (aapl.totals.vega + spy.totals.vega)).abs() < 1e-9);
+        assert!(report.unpriced.is_empty());
+
+        // Without a SPY price the put has no local Greeks, and SPY is reported as unpriced
+        let report = OptionsRisk::new(&positions, "2025-03-20")
+            .snapshots(&snapshots)
+            .report();
+        let spy = report.underlying("SPY").unwrap();
+        assert_eq!(spy.positions[0].source, GreeksSource::Missing);
+        assert_eq!(report.unpriced, vec!["SPY"]);
+    }
+
+    #[test]
+    fn test_scenario_grid() {
+        let positions = positions();
+        let snapshots: HashMap<String, OptionSnapshot> = HashMap::new();
+        let report = OptionsRisk::new(&positions, "2025-03-20")
+            .snapshots(&snapshots)
+            .underlying_price("SPY", 510.0)
+            .report();
+
+        let grid = report.scenario_grid(&[-0.1, 0.0, 0.1]);
+        assert_eq!(grid.len(), 3);
+        assert!(grid[1].total.abs() < 1e-6);
+
+        // Long put gains when SPY falls
+        let spy_down = grid[0]
+            .underlyings
+            .iter()
+            .find(|(u, _)| u == "SPY")
+            .unwrap()
+            .1;
+        assert!(spy_down > 0.0);
+        // Covered calls cushion the downside and cap the upside of the 100 shares
+        let aapl = |row: &ScenarioRow| row.underlyings.iter().find(|(u, _)| u == "AAPL").unwrap().1;
+        assert!(aapl(&grid[0]) > -2000.0);
+        assert!(aapl(&grid[2]) < 2000.0);
+    }
+}
@@ -0,0 +1,383 @@
+use super::{
+    CreateOrderQuery, Order, OrderClass, OrderLeg, OrderSide, OrderType, StopLoss, TakeProfit,
+    TimeInForce,
+};
+use crate::trading::{AccountType, assets::AssetCache, risk::RiskManager};
+use std::marker::PhantomData;
+
+/// How much to buy or sell.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrderAmount<'a> {
+    /// Number of shares or coins, may be fractional.
+    Qty(&'a str),
+    /// Dollar amount to trade.
+    Notional(&'a str),
+}
+
+/// Distance a trailing stop follows the high water mark by.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrailAmount<'a> {
+    /// Fixed dollar offset.
+    Price(&'a str),
+    /// Percentage offset.
+    Percent(&'a str),
+}
+
+/// The exit leg of a one-triggers-other order.
+#[derive(Debug)]
+pub enum OtoExit<'a> {
+    TakeProfit(TakeProfit<'a>),
+    StopLoss(StopLoss<'a>),
+}
+
+/// Order kinds used as the type parameter of [`OrderBuilder`].
+pub mod kind {
+    #[derive(Debug)]
+    pub struct Market;
+    #[derive(Debug)]
+    pub struct Limit;
+    #[derive(Debug)]
+    pub struct Stop;
+    #[derive(Debug)]
+    pub struct StopLimit;
+    #[derive(Debug)]
+    pub struct TrailingStop;
+    #[derive(Debug)]
+    pub struct Bracket;
+    #[derive(Debug)]
+    pub struct OneCancelsOther;
+    #[derive(Debug)]
+    pub struct OneTriggersOther;
+    #[derive(Debug)]
+    pub struct MultiLeg;
+}
+
+/// A [`CreateOrderQuery`] under construction, which only exposes the setters valid for order kind `K`.
+///
+/// Created with the constructors on [`Order`], e.g. `Order::limit("AAPL", OrderSide::Buy, OrderAmount::Qty("1"), "150")`.
+#[derive(Debug)]
+pub struct OrderBuilder<'a, K> {
+    query: CreateOrderQuery<'a>,
+    kind: PhantomData<K>,
+}
+
+impl Order {
+    pub fn market<'a>(
+        symbol: &'a str,
+        side: OrderSide,
+        amount: OrderAmount<'a>,
+    ) -> OrderBuilder<'a, kind::Market> {
+        OrderBuilder::new(symbol, side, OrderType::Market, amount)
+    }
+
+    pub fn limit<'a>(
+        symbol: &'a str,
+        side: OrderSide,
+        amount: OrderAmount<'a>,
+        limit_price: &'a str,
+    ) -> OrderBuilder<'a, kind::Limit> {
+        let mut builder = OrderBuilder::new(symbol, side, OrderType::Limit, amount);
+        builder.query.limit_price = Some(limit_price);
+        builder
+    }
+
+    pub fn stop<'a>(
+        symbol: &'a str,
+        side: OrderSide,
+        amount: OrderAmount<'a>,
+        stop_price: &'a str,
+    ) -> OrderBuilder<'a, kind::Stop> {
+        let mut builder = OrderBuilder::new(symbol, side, OrderType::Stop, amount);
+        builder.query.stop_price = Some(stop_price);
+        builder
+    }
+
+    pub fn stop_limit<'a>(
+        symbol: &'a str,
+        side: OrderSide,
+        amount: OrderAmount<'a>,
+        stop_price: &'a str,
+        limit_price: &'a str,
+    ) -> OrderBuilder<'a, kind::StopLimit> {
+        let mut builder = OrderBuilder::new(symbol, side, OrderType::StopLimit, amount);
+        builder.query.stop_price = Some(stop_price);
+        builder.query.limit_price = Some(limit_price);
+        builder
+    }
+
+    /// Trailing stops only accept a whole `qty`.
+    pub fn trailing_stop<'a>(
+        symbol: &'a str,
+        side: OrderSide,
+        qty: &'a str,
+        trail: TrailAmount<'a>,
+    ) -> OrderBuilder<'a, kind::TrailingStop> {
+        let mut builder =
+            OrderBuilder::new(symbol, side, OrderType::TrailingStop, OrderAmount::Qty(qty));
+        match trail {
+            TrailAmount::Price(price) => builder.query.trail_price = Some(price),
+            TrailAmount::Percent(percent) => builder.query.trail_percent = Some(percent),
+        }
+        builder
+    }
+
+    /// Market entry with take profit and stop loss exits. Use `entry_limit_price` for a limit entry.
+    pub fn bracket<'a>(
+        symbol: &'a str,
+        side: OrderSide,
+        qty: &'a str,
+        take_profit: TakeProfit<'a>,
+        stop_loss: StopLoss<'a>,
+    ) -> OrderBuilder<'a, kind::Bracket> {
+        let mut builder = OrderBuilder::new(symbol, side, OrderType::Market, OrderAmount::Qty(qty));
+        builder.query.order_class = Some(OrderClass::Bracket.to_string());
+        builder.query.take_profit = Some(take_profit);
+        builder.query.stop_loss = Some(stop_loss);
+        builder
+    }
+
+    /// Take profit and stop loss exits for an existing position, whichever fills first cancels the other.
+    pub fn oco<'a>(
+        symbol: &'a str,
+        side: OrderSide,
+        qty: &'a str,
+        take_profit: TakeProfit<'a>,
+        stop_loss: StopLoss<'a>,
+    ) -> OrderBuilder<'a, kind::OneCancelsOther> {
+        let mut builder = OrderBuilder::new(symbol, side, OrderType::Limit, OrderAmount::Qty(qty));
+        builder.query.order_class = Some(OrderClass::OneCancelsOther.to_string());
+        builder.query.limit_price = Some(take_profit.limit_price);
+        builder.query.take_profit = Some(take_profit);
+        builder.query.stop_loss = Some(stop_loss);
+        builder
+    }
+
+    /// Market entry that submits a single exit once filled. Use `entry_limit_price` for a limit entry.
+    pub fn oto<'a>(
+        symbol: &'a str,
+        side: OrderSide,
+        qty: &'a str,
+        exit: OtoExit<'a>,
+    ) -> OrderBuilder<'a, kind::OneTriggersOther> {
+        let mut builder = OrderBuilder::new(symbol, side, OrderType::Market, OrderAmount::Qty(qty));
+        builder.query.order_class = Some(OrderClass::OneTriggersOther.to_string());
+        match exit {
+            OtoExit::TakeProfit(take_profit) => builder.query.take_profit = Some(take_profit),
+            OtoExit::StopLoss(stop_loss) => builder.query.stop_loss = Some(stop_loss),
+        }
+        builder
+    }
+
+    /// Market order for `qty` units of a multi-leg options strategy. Use `limit_price` for a net debit (positive) or credit (negative) limit.
+    pub fn multi_leg<'a>(
+        qty: &'a str,
+        legs: Vec<OrderLeg<'a>>,
+    ) -> OrderBuilder<'a, kind::MultiLeg> {
+        OrderBuilder {
+            query: CreateOrderQuery::multi_leg(legs, OrderType::Market, TimeInForce::Day).qty(qty),
+            kind: PhantomData,
+        }
+    }
+}
+
+impl<'a, K> OrderBuilder<'a, K> {
+    fn new(
+        symbol: &'a str,
+        side: OrderSide,
+        order_type: OrderType,
+        amount: OrderAmount<'a>,
+    ) -> Self {
+        let mut query = CreateOrderQuery::new(symbol, side, order_type, TimeInForce::Day);
+        match amount {
+            OrderAmount::Qty(qty) => query.qty = Some(qty),
+            OrderAmount::Notional(notional) => query.notional = Some(notional),
+        }
+        Self {
+            query,
+            kind: PhantomData,
+        }
+    }
+
+    /// Defaults to `Day`.
+    pub fn time_in_force(mut self, time_in_force: TimeInForce) -> Self {
+        self.query.time_in_force = time_in_force.to_string();
+        self
+    }
+
+    pub fn client_order_id(mut self, client_order_id: &'a str) -> Self {
+        self.query = self.query.client_order_id(client_order_id);
+        self
+    }
+
+    pub fn generate_client_order_id(mut self) -> Self {
+        self.query = self.query.generate_client_order_id();
+        self
+    }
+
+    pub fn asset_cache(mut self, assets: &'a AssetCache) -> Self {
+        self.query = self.query.asset_cache(assets);
+        self
+    }
+
+    pub fn validate_on_send(mut self, validate_on_send: bool) -> Self {
+        self.query = self.query.validate_on_send(validate_on_send);
+        self
+    }
+
+    pub fn risk_manager(mut self, risk: &'a RiskManager) -> Self {
+        self.query = self.query.risk_manager(risk);
+        self
+    }
+
+    pub fn into_query(self) -> CreateOrderQuery<'a> {
+        self.query
+    }
+
+    pub fn send(self, account_type: AccountType) -> Result<Order, ureq::Error> {
+        self.query.send(account_type)
+    }
+}
+
+impl<'a, K> From<OrderBuilder<'a, K>> for CreateOrderQuery<'a> {
+    fn from(builder: OrderBuilder<'a, K>) -> Self {
+        builder.query
+    }
+}
+
+impl OrderBuilder<'_, kind::Limit> {
+    /// Only limit orders with `Day` time in force can trade outside regular hours.
+    pub fn extend_hours(mut self, extend_hours: bool) -> Self {
+        self.query.extend_hours = extend_hours;
+        self
+    }
+}
+
+impl<'a> OrderBuilder<'a, kind::Bracket> {
+    pub fn entry_limit_price(mut self, limit_price: &'a str) -> Self {
+        self.query.r#type = OrderType::Limit.to_string();
+        self.query.limit_price = Some(limit_price);
+        self
+    }
+}
+
+impl<'a> OrderBuilder<'a, kind::OneTriggersOther> {
+    pub fn entry_limit_price(mut self, limit_price: &'a str) -> Self {
+        self.query.r#type = OrderType::Limit.to_string();
+        self.query.limit_price = Some(limit_price);
+        self
+    }
+}
+
+impl<'a> OrderBuilder<'a, kind::MultiLeg> {
+    pub fn limit_price(mut self, limit_price: &'a str) -> Self {
+        self.query.r#type = OrderType::Limit.to_string();
+        self.query.limit_price = Some(limit_price);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::order::OrderValidationError;
+    use serde_json::json;
+
+    #[test]
+    fn test_order_builders() {
+        let query = Order::stop_limit(
+            "AAPL",
+            OrderSide::Sell,
+            OrderAmount::Qty("10"),
+            "140",
+            "139",
+        )
+        .time_in_force(TimeInForce::GoodTilCanceled)
+        .into_query();
+        assert_eq!(
+            serde_json::to_value(&query).unwrap(),
+            json!({
+                "symbol": "AAPL",
+                "side": "sell",
+                "type": "stop_limit",
+                "time_in_force": "gtc",
+                "extend_hours": false,
+                "qty": "10",
+                "stop_price": "140",
+                "limit_price": "139",
+            })
+        );
+        assert!(query.validate().is_ok());
+
+        let query: CreateOrderQuery =
+            Order::trailing_stop("AAPL", OrderSide::Sell, "10", TrailAmount::Percent("5")).into();
+        assert_eq!(query.trail_percent, Some("5"));
+        assert_eq!(query.trail_price, None);
+
+        let query =
+            Order::market("AAPL", OrderSide::Buy, OrderAmount::Notional("250")).into_query();
+        assert_eq!(query.notional, Some("250"));
+        assert_eq!(query.qty, None);
+    }
+
+    #[test]
+    fn test_advanced_order_builders() {
+        let query = Order::bracket(
+            "AAPL",
+            OrderSide::Buy,
+            "10",
+            TakeProfit::new("200"),
+            StopLoss::new("140", "139"),
+        )
+        .entry_limit_price("150")
+        .time_in_force(TimeInForce::GoodTilCanceled)
+        .into_query();
+        assert_eq!(query.r#type, "limit");
+        assert_eq!(query.order_class.as_deref(), Some("bracket"));
+        assert!(query.validate().is_ok());
+
+        let query = Order::oco(
+            "AAPL",
+            OrderSide::Sell,
+            "10",
+            TakeProfit::new("210"),
+            StopLoss::new("190", "189"),
+        )
+        .into_query();
+        assert_eq!(query.limit_price, Some("210"));
+        assert!(query.validate().is_ok());
+
+        let query = Order::oto(
+            "AAPL",
+            OrderSide::Buy,
+            "10",
+            OtoExit::StopLoss(StopLoss::new("140", "139")),
+        )
+        .into_query();
+        assert!(query.take_profit.is_none());
+        assert!(query.validate().is_ok());
+    }
+
+    #[test]
+    fn test_multi_leg_builder() {
+        let legs = vec![
+            OrderLeg::new("SPY250620P00500000", OrderSide::Sell, "1"),
+            OrderLeg::new("SPY250620P00490000", OrderSide::Buy, "1"),
+        ];
+        let query = Order::multi_leg("1", legs)
+            .limit_price("-1.20")
+            .into_query();
+        assert_eq!(query.symbol, "");
+        assert_eq!(query.r#type, "limit");
+        assert!(query.validate().is_ok());
+
+        let query = Order::multi_leg(
+            "1",
+            vec![OrderLeg::new("SPY250620P00500000", OrderSide::Sell, "1")],
+        )
+        .into_query();
+        assert_eq!(
+            query.validate().unwrap_err().0,
+            vec![OrderValidationError::InvalidLegCount(1)]
+        );
+    }
+}
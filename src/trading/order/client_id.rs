@@ -0,0 +1,128 @@
+use std::{
+    collections::hash_map::RandomState,
+    fmt,
+    hash::{BuildHasher, Hasher},
+    sync::{
+        RwLock,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Longest `client_order_id` the API accepts.
+pub const MAX_CLIENT_ORDER_ID_LEN: usize = 128;
+
+/// Longest prefix kept, leaving room for the unique part of a generated id.
+const MAX_PREFIX_LEN: usize = 64;
+
+static PREFIX: RwLock<String> = RwLock::new(String::new());
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Prefix of generated client order ids, e.g. a strategy or service name. Defaults to `apc`.
+/// Prefixes longer than 64 bytes are cut to fit.
+pub fn set_client_order_id_prefix(prefix: &str) {
+    *PREFIX.write().unwrap_or_else(|e| e.into_inner()) = truncate_prefix(prefix).to_string();
+}
+
+fn truncate_prefix(prefix: &str) -> &str {
+    let end = (0..=prefix.len().min(MAX_PREFIX_LEN))
+        .rev()
+        .find(|&end| prefix.is_char_boundary(end))
+        .unwrap_or(0);
+    &prefix[..end]
+}
+
+/// A unique client order id: `{prefix}-{millis}-{counter}-{random}`.
+///
+/// The timestamp and process-wide counter keep ids unique within a process, the random part across processes.
+pub fn generate_client_order_id() -> String {
+    let prefix = PREFIX.read().unwrap_or_else(|e| e.into_inner());
+    let prefix = if prefix.is_empty() { "apc" } else { &prefix };
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default();
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(millis);
+    hasher.write_u64(count);
+    hasher.write_u32(std::process::id());
+
+    format!(
+        "{}-{}-{}-{:08x}",
+        prefix,
+        base36(millis),
+        base36(count),
+        hasher.finish() as u32
+    )
+}
+
+fn base36(mut n: u64) -> String {
+    const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut out = Vec::new();
+    loop {
+        out.push(DIGITS[(n % 36) as usize]);
+        n /= 36;
+        if n == 0 {
+            break;
+        }
+    }
+    out.reverse();
+    String::from_utf8(out).unwrap()
+}
+
+/// `send_idempotent` needs a client order id that stays the same across retries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissingClientOrderId;
+
+impl fmt::Display for MissingClientOrderId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "idempotent submission requires a client_order_id, set or generate one first"
+        )
+    }
+}
+
+impl std::error::Error for MissingClientOrderId {}
+
+/// An error status from `send_idempotent`, with the message the API returned.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderRejected {
+    pub status: u16,
+    pub message: String,
+}
+
+impl fmt::Display for OrderRejected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "order rejected with status {}: {}",
+            self.status, self.message
+        )
+    }
+}
+
+impl std::error::Error for OrderRejected {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_client_order_id() {
+        let first = generate_client_order_id();
+        let second = generate_client_order_id();
+        assert_ne!(first, second);
+        assert!(first.starts_with("apc-"));
+        assert_eq!(first.split('-').count(), 4);
+        assert_eq!(base36(0), "0");
+        assert_eq!(base36(36 * 36 + 35), "10z");
+
+        // The 64th byte falls inside a two-byte character
+        let prefix = format!("{}é", "a".repeat(63));
+        assert_eq!(truncate_prefix(&prefix), "a".repeat(63));
+        assert_eq!(truncate_prefix("momentum"), "momentum");
+    }
+}
@@ -1,44 +1,68 @@
-use super::{Order, OrderSide};
-use crate::{json_request, trading::AccountType};
+use super::{
+    GetOrdersQuery, MissingClientOrderId, Order, OrderRejected, OrderSide, generate_client_order_id,
+};
+use crate::{
+    dry_run, json_request, raw_status,
+    trading::{AccountType, assets::AssetCache, risk::RiskManager},
+};
 use serde::{Deserialize, Serialize};
+use std::{borrow::Cow, fmt};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct CreateOrderQuery<'a> {
-    symbol: &'a str,
-    side: String,
-    r#type: String,
-    time_in_force: String,
-    extend_hours: bool,
+    /// Empty for multi-leg orders, where each leg has its own symbol
+    #[serde(skip_serializing_if = "str::is_empty")]
+    pub(crate) symbol: &'a str,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub(crate) side: String,
+    pub(crate) r#type: String,
+    pub(crate) time_in_force: String,
+    pub(crate) extend_hours: bool,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    qty: Option<&'a str>,
+    pub(crate) qty: Option<&'a str>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    notional: Option<&'a str>,
+    pub(crate) notional: Option<&'a str>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    limit_price: Option<&'a str>,
+    pub(crate) limit_price: Option<&'a str>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    stop_price: Option<&'a str>,
+    pub(crate) stop_price: Option<&'a str>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    trail_price: Option<&'a str>,
+    pub(crate) trail_price: Option<&'a str>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    trail_percent: Option<&'a str>,
+    pub(crate) trail_percent: Option<&'a str>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    client_order_id: Option<&'a str>,
+    pub(crate) client_order_id: Option<Cow<'a, str>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    order_class: Option<String>,
+    pub(crate) order_class: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    take_profit: Option<TakeProfit<'a>>,
+    pub(crate) take_profit: Option<TakeProfit<'a>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    stop_loss: Option<StopLoss<'a>>,
+    pub(crate) stop_loss: Option<StopLoss<'a>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) position_intent: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) legs: Option<Vec<OrderLeg<'a>>>,
+
+    #[serde(skip)]
+    pub(super) assets: Option<&'a AssetCache>,
+
+    #[serde(skip)]
+    validate_on_send: bool,
+
+    #[serde(skip)]
+    risk: Option<&'a RiskManager>,
 }
 
 impl<'a> CreateOrderQuery<'a> {
@@ -64,9 +88,59 @@ impl<'a> CreateOrderQuery<'a> {
             order_class: None,
             take_profit: None,
             stop_loss: None,
+            position_intent: None,
+            legs: None,
+            assets: None,
+            validate_on_send: false,
+            risk: None,
         }
     }
 
+    /// A multi-leg (`mleg`) options order. `qty` is the number of strategy units, each leg trades `ratio_qty` times that.
+    pub fn multi_leg(
+        legs: Vec<OrderLeg<'a>>,
+        order_type: OrderType,
+        time_in_force: TimeInForce,
+    ) -> Self {
+        let mut query = Self::new("", OrderSide::Buy, order_type, time_in_force);
+        query.side = String::new();
+        query.order_class = Some(OrderClass::MultiLeg.to_string());
+        query.legs = Some(legs);
+        query
+    }
+
+    /// The order a replacement leaves working, for risk checks: `original` with the replacement's quantity and prices.
+    pub(super) fn replacement(
+        original: &'a Order,
+        qty: Option<&'a str>,
+        limit_price: Option<&'a str>,
+        stop_price: Option<&'a str>,
+    ) -> Self {
+        let mut query = Self::new(
+            &original.symbol,
+            OrderSide::Buy,
+            OrderType::Market,
+            TimeInForce::Day,
+        );
+        query.side = original.side.clone();
+        query.r#type = original.r#type.clone();
+        query.order_class = original.order_class.clone();
+        query.qty = qty;
+        query.limit_price = limit_price.or(original.limit_price.as_deref());
+        query.stop_price = stop_price.or(original.stop_price.as_deref());
+        query.legs = original.legs.as_ref().map(|legs| {
+            legs.iter()
+                .map(|leg| OrderLeg {
+                    symbol: &leg.symbol,
+                    side: leg.side.clone(),
+                    ratio_qty: leg.ratio_qty.as_deref().unwrap_or("1"),
+                    position_intent: None,
+                })
+                .collect()
+        });
+        query
+    }
+
     pub fn qty(mut self, qty: &'a str) -> Self {
         self.qty = Some(qty);
         self
@@ -103,10 +177,21 @@ impl<'a> CreateOrderQuery<'a> {
     }
 
     pub fn client_order_id(mut self, client_order_id: &'a str) -> Self {
-        self.client_order_id = Some(client_order_id);
+        self.client_order_id = Some(Cow::Borrowed(client_order_id));
         self
     }
 
+    /// Set a unique client order id, see [`generate_client_order_id`](super::generate_client_order_id).
+    pub fn generate_client_order_id(mut self) -> Self {
+        self.client_order_id = Some(Cow::Owned(generate_client_order_id()));
+        self
+    }
+
+    /// The client order id that will be sent, if any.
+    pub fn get_client_order_id(&self) -> Option<&str> {
+        self.client_order_id.as_deref()
+    }
+
     pub fn order_class(mut self, order_class: OrderClass) -> Self {
         self.order_class = Some(order_class.to_string());
         self
@@ -122,18 +207,155 @@ impl<'a> CreateOrderQuery<'a> {
         self
     }
 
+    /// Options only, whether the order opens or closes a position.
+    pub fn position_intent(mut self, position_intent: PositionIntent) -> Self {
+        self.position_intent = Some(position_intent.to_string());
+        self
+    }
+
+    pub fn legs(mut self, legs: Vec<OrderLeg<'a>>) -> Self {
+        self.legs = Some(legs);
+        self
+    }
+
+    /// Asset metadata used by `validate` for tradable, fractionable and shortable checks.
+    pub fn asset_cache(mut self, assets: &'a AssetCache) -> Self {
+        self.assets = Some(assets);
+        self
+    }
+
+    /// Run `validate` before sending. Failures are returned as `ureq::Error::Other(ValidationErrors)`.
+    pub fn validate_on_send(mut self, validate_on_send: bool) -> Self {
+        self.validate_on_send = validate_on_send;
+        self
+    }
+
+    /// Check the order against the risk manager's limits before sending.
+    /// A refused order is returned as `ureq::Error::Other(RiskViolation)` and never reaches the API.
+    /// Orders count towards `max_orders_per_minute` from the check, and stop counting if the send fails.
+    pub fn risk_manager(mut self, risk: &'a RiskManager) -> Self {
+        self.risk = Some(risk);
+        self
+    }
+
     pub fn send(self, account_type: AccountType) -> Result<Order, ureq::Error> {
+        self.submit(account_type, None)
+    }
+
+    /// Send the order, or return the existing one if an order with the same client order id was already accepted.
+    ///
+    /// Safe to retry with the same query after a timeout or dropped connection, since a resubmission is
+    /// rejected with "client_order_id must be unique" and the original order is fetched instead. The order
+    /// counts once towards the risk manager's rate limit, when a submission or resubmission returns it.
+    /// Other error statuses are returned as `ureq::Error::Other(OrderRejected)` with the response message.
+    pub fn send_idempotent(&self, account_type: AccountType) -> Result<Order, ureq::Error> {
+        let Some(client_order_id) = self.get_client_order_id() else {
+            return Err(ureq::Error::Other(Box::new(MissingClientOrderId)));
+        };
+        self.submit(account_type, Some(client_order_id))
+    }
+
+    /// Validate, check the risk limits, then send the order or log it in dry-run mode.
+    fn submit(
+        &self,
+        account_type: AccountType,
+        client_order_id: Option<&str>,
+    ) -> Result<Order, ureq::Error> {
+        if self.validate_on_send {
+            self.validate()
+                .map_err(|e| ureq::Error::Other(Box::new(e)))?;
+        }
+        let reserved = match self.risk {
+            Some(risk) => Some(
+                risk.reserve(self)
+                    .map_err(|e| ureq::Error::Other(Box::new(e)))?,
+            ),
+            None => None,
+        };
+        let order = self.post(account_type, client_order_id);
+        if let (Some(risk), Some(reserved), Err(_)) = (self.risk, reserved, &order) {
+            risk.release(reserved);
+        }
+        order
+    }
+
+    fn post(
+        &self,
+        account_type: AccountType,
+        client_order_id: Option<&str>,
+    ) -> Result<Order, ureq::Error> {
         let url = match account_type {
             AccountType::Live => "https://api.alpaca.markets/v2/orders",
             AccountType::Paper => "https://paper-api.alpaca.markets/v2/orders",
         };
 
-        let response = json_request("POST", url)
+        if dry_run::intercept_json("POST", url, self) {
+            Ok(self.dry_run_order())
+        } else if let Some(client_order_id) = client_order_id {
+            self.post_idempotent(account_type, url, client_order_id)
+        } else {
+            json_request("POST", url)
+                .header("Content-Type", "application/json")
+                .send_json(self)?
+                .into_body()
+                .read_json()
+        }
+    }
+
+    fn post_idempotent(
+        &self,
+        account_type: AccountType,
+        url: &str,
+        client_order_id: &str,
+    ) -> Result<Order, ureq::Error> {
+        let mut response = raw_status(json_request("POST", url))
             .header("Content-Type", "application/json")
-            .send_json(&self)?;
+            .send_json(self)?;
 
-        let order = response.into_body().read_json()?;
-        Ok(order)
+        let status = response.status().as_u16();
+        if status < 400 {
+            return response.body_mut().read_json();
+        }
+        let message = response.body_mut().read_to_string().unwrap_or_default();
+        if status == 422 && message.contains("client_order_id must be unique") {
+            crate::instrument::idempotent_retry();
+            return GetOrdersQuery::new(account_type).get_by_client_order_id(client_order_id);
+        }
+        Err(ureq::Error::Other(Box::new(OrderRejected {
+            status,
+            message,
+        })))
+    }
+
+    fn dry_run_order(&self) -> Order {
+        let mut order = Order::dry_run(self.symbol, &self.side, &self.r#type);
+        let owned = |value: Option<&str>| value.map(str::to_string);
+        order.client_order_id = Some(
+            self.get_client_order_id()
+                .map_or_else(generate_client_order_id, str::to_string),
+        );
+        order.qty = owned(self.qty);
+        order.notional = owned(self.notional);
+        order.limit_price = owned(self.limit_price);
+        order.stop_price = owned(self.stop_price);
+        order.trail_price = owned(self.trail_price);
+        order.trail_percent = owned(self.trail_percent);
+        order.time_in_force = Some(self.time_in_force.clone());
+        order.extended_hours = self.extend_hours;
+        order.order_class = self.order_class.clone();
+        order.position_intent = self.position_intent.clone();
+        order.legs = self.legs.as_ref().map(|legs| {
+            legs.iter()
+                .map(|leg| {
+                    let mut child = Order::dry_run(leg.symbol, &leg.side, "");
+                    child.ratio_qty = Some(leg.ratio_qty.to_string());
+                    child.position_intent = leg.position_intent.clone();
+                    child.order_class = order.order_class.clone();
+                    child
+                })
+                .collect()
+        });
+        order
     }
 }
 
@@ -187,6 +409,7 @@ pub enum OrderClass {
     Bracket,
     OneCancelsOther,
     OneTriggersOther,
+    MultiLeg,
 }
 
 impl ToString for OrderClass {
@@ -196,6 +419,7 @@ impl ToString for OrderClass {
             OrderClass::Bracket => "bracket".to_string(),
             OrderClass::OneCancelsOther => "oco".to_string(),
             OrderClass::OneTriggersOther => "oto".to_string(),
+            OrderClass::MultiLeg => "mleg".to_string(),
         }
     }
 }
@@ -226,6 +450,54 @@ impl<'a> StopLoss<'a> {
     }
 }
 
+/// Whether an options order opens or closes a position.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum PositionIntent {
+    BuyToOpen,
+    BuyToClose,
+    SellToOpen,
+    SellToClose,
+}
+
+impl fmt::Display for PositionIntent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let intent = match self {
+            PositionIntent::BuyToOpen => "buy_to_open",
+            PositionIntent::BuyToClose => "buy_to_close",
+            PositionIntent::SellToOpen => "sell_to_open",
+            PositionIntent::SellToClose => "sell_to_close",
+        };
+        write!(f, "{}", intent)
+    }
+}
+
+/// One leg of a multi-leg options order.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OrderLeg<'a> {
+    pub symbol: &'a str,
+    pub side: String,
+    pub ratio_qty: &'a str,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position_intent: Option<String>,
+}
+
+impl<'a> OrderLeg<'a> {
+    pub fn new(symbol: &'a str, side: OrderSide, ratio_qty: &'a str) -> Self {
+        Self {
+            symbol,
+            side: side.to_string(),
+            ratio_qty,
+            position_intent: None,
+        }
+    }
+
+    pub fn position_intent(mut self, position_intent: PositionIntent) -> Self {
+        self.position_intent = Some(position_intent.to_string());
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -242,6 +514,46 @@ mod tests {
         assert!(order.symbol == "AAPL");
     }
 
+    #[test]
+    fn test_dry_run_order() {
+        // Built directly, toggling the global switch would affect tests running in parallel
+        let query =
+            CreateOrderQuery::new("AAPL", OrderSide::Buy, OrderType::Limit, TimeInForce::Day)
+                .qty("3")
+                .limit_price("190.5")
+                .client_order_id("strategy-1");
+        let order = query.dry_run_order();
+
+        assert!(order.id.starts_with("dry-run-"));
+        assert_eq!(order.status, "accepted");
+        assert_eq!(
+            (order.symbol.as_str(), order.side.as_str()),
+            ("AAPL", "buy")
+        );
+        assert_eq!(order.qty.as_deref(), Some("3"));
+        assert_eq!(order.limit_price.as_deref(), Some("190.5"));
+        assert_eq!(order.client_order_id.as_deref(), Some("strategy-1"));
+        assert_eq!(order.time_in_force.as_deref(), Some("day"));
+        assert!(crate::time::parse_rfc3339_nanos(order.created_at.as_deref().unwrap()).is_some());
+    }
+
+    #[test]
+    fn test_send_idempotent() {
+        let query =
+            CreateOrderQuery::new("AAPL", OrderSide::Buy, OrderType::Market, TimeInForce::Day)
+                .qty("1")
+                .generate_client_order_id();
+
+        let first = query.send_idempotent(AccountType::Paper).unwrap();
+        let second = query.send_idempotent(AccountType::Paper).unwrap();
+        dbg!(&second);
+        assert_eq!(first.id, second.id);
+        assert_eq!(
+            first.client_order_id.as_deref(),
+            query.get_client_order_id()
+        );
+    }
+
     #[test]
     fn test_create_limit_order() {
         let order = CreateOrderQuery::new(
@@ -369,4 +681,36 @@ mod tests {
         dbg!(&order);
         assert!(order.symbol == "AAPL");
     }
+
+    #[test]
+    fn test_serialize_multi_leg_order() {
+        let query = CreateOrderQuery::multi_leg(
+            vec![
+                OrderLeg::new("AAPL250620C00200000", OrderSide::Buy, "1")
+                    .position_intent(PositionIntent::BuyToOpen),
+                OrderLeg::new("AAPL250620C00210000", OrderSide::Sell, "1")
+                    .position_intent(PositionIntent::SellToOpen),
+            ],
+            OrderType::Limit,
+            TimeInForce::Day,
+        )
+        .qty("2")
+        .limit_price("3.25");
+
+        assert_eq!(
+            serde_json::to_value(&query).unwrap(),
+            serde_json::json!({
+                "type": "limit",
+                "time_in_force": "day",
+                "extend_hours": false,
+                "qty": "2",
+                "limit_price": "3.25",
+                "order_class": "mleg",
+                "legs": [
+                    {"symbol": "AAPL250620C00200000", "side": "buy", "ratio_qty": "1", "position_intent": "buy_to_open"},
+                    {"symbol": "AAPL250620C00210000", "side": "sell", "ratio_qty": "1", "position_intent": "sell_to_open"},
+                ],
+            })
+        );
+    }
 }
@@ -1,6 +1,7 @@
 use serde::Deserialize;
 
-use crate::{request, trading::AccountType};
+use super::GetOrdersQuery;
+use crate::{dry_run, request, trading::AccountType};
 
 #[derive(Deserialize, Debug)]
 pub struct DeleteOrderResult {
@@ -8,11 +9,26 @@ pub struct DeleteOrderResult {
     pub status: i32,
 }
 
+/// In dry-run mode the open orders are listed and returned as if each cancel was accepted. The listing is
+/// capped at 500 orders, the most the API returns.
 pub fn delete_all_orders(account_type: AccountType) -> Result<Vec<DeleteOrderResult>, ureq::Error> {
     let url = match account_type {
         AccountType::Live => "https://api.alpaca.markets/v2/orders",
         AccountType::Paper => "https://paper-api.alpaca.markets/v2/orders",
     };
+    if dry_run::intercept("DELETE", url) {
+        let open = GetOrdersQuery::new(account_type)
+            .status("open")
+            .limit(500)
+            .send()?;
+        return Ok(open
+            .into_iter()
+            .map(|order| DeleteOrderResult {
+                id: order.id,
+                status: 200,
+            })
+            .collect());
+    }
     let response = request("DELETE", url).call()?;
     let orders: Vec<DeleteOrderResult> = response.into_body().read_json()?;
     Ok(orders)
@@ -24,10 +40,22 @@ pub fn delete_by_id(id: &str, account_type: AccountType) -> Result<u16, ureq::Er
         AccountType::Live => format!("https://api.alpaca.markets/v2/orders/{}", id),
         AccountType::Paper => format!("https://paper-api.alpaca.markets/v2/orders/{}", id),
     };
+    if dry_run::intercept("DELETE", &url) {
+        return Ok(204);
+    }
     let response = request("DELETE", &url).call()?;
     Ok(response.status().as_u16())
 }
 
+/// Cancel an order by its `client_order_id`. Returns the HTTP status code on success
+pub fn delete_by_client_order_id(
+    client_order_id: &str,
+    account_type: AccountType,
+) -> Result<u16, ureq::Error> {
+    let order = GetOrdersQuery::new(account_type).get_by_client_order_id(client_order_id)?;
+    delete_by_id(&order.id, account_type)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
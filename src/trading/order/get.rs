@@ -108,6 +108,16 @@ impl<'a> GetOrdersQuery<'a> {
         Ok(orders)
     }
 
+    /// Look up an order by the `client_order_id` it was submitted with.
+    pub fn get_by_client_order_id(self, client_order_id: &str) -> Result<Order, ureq::Error> {
+        let route = format!("{}:by_client_order_id", self.url);
+        let response = request("GET", &route)
+            .query("client_order_id", client_order_id)
+            .call()?;
+        let order: Order = response.into_body().read_json()?;
+        Ok(order)
+    }
+
     pub fn send(self) -> Result<AllOrders, ureq::Error> {
         let route = self.build();
         let response = request("GET", &route).call()?;
@@ -1,3 +1,9 @@
+mod builder;
+pub use builder::*;
+
+mod client_id;
+pub use client_id::*;
+
 mod create;
 use std::str::FromStr;
 
@@ -12,12 +18,21 @@ pub use get::*;
 mod replace;
 pub use replace::*;
 
+mod tracker;
+pub use tracker::*;
+
+mod validate;
+pub use validate::*;
+
+mod wait;
+pub use wait::*;
+
 use serde::{Deserialize, Serialize};
 
 pub type AllOrders = Vec<Order>;
 
 /// API object for an Order
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Order {
     pub id: String,
     pub client_order_id: Option<String>,
@@ -32,6 +47,8 @@ pub struct Order {
     pub replaced_by: Option<String>,
     pub replaces: Option<String>,
     pub asset_id: Option<String>,
+    /// Empty for the parent of a multi-leg order
+    #[serde(default)]
     pub symbol: String,
     pub asset_class: Option<String>,
     pub notional: Option<String>,
@@ -41,7 +58,10 @@ pub struct Order {
     pub order_class: Option<String>,
     pub order_type: String,
     pub r#type: String,
+    #[serde(default)]
     pub side: String,
+    pub position_intent: Option<String>,
+    pub ratio_qty: Option<String>,
     pub time_in_force: Option<String>,
     pub limit_price: Option<String>,
     pub stop_price: Option<String>,
@@ -53,7 +73,50 @@ pub struct Order {
     pub hwm: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+impl Order {
+    /// An `accepted` order with a generated id, standing in for one that was not sent in dry-run mode.
+    pub(crate) fn dry_run(symbol: &str, side: &str, order_type: &str) -> Self {
+        let now = crate::time::now_rfc3339();
+        Self {
+            id: format!("dry-run-{}", generate_client_order_id()),
+            client_order_id: None,
+            created_at: Some(now.clone()),
+            updated_at: Some(now.clone()),
+            submitted_at: Some(now),
+            filled_at: None,
+            expired_at: None,
+            canceled_at: None,
+            failed_at: None,
+            replaced_at: None,
+            replaced_by: None,
+            replaces: None,
+            asset_id: None,
+            symbol: symbol.to_string(),
+            asset_class: None,
+            notional: None,
+            qty: None,
+            filled_qty: Some("0".to_string()),
+            filled_avg_price: None,
+            order_class: None,
+            order_type: order_type.to_string(),
+            r#type: order_type.to_string(),
+            side: side.to_string(),
+            position_intent: None,
+            ratio_qty: None,
+            time_in_force: None,
+            limit_price: None,
+            stop_price: None,
+            status: "accepted".to_string(),
+            extended_hours: false,
+            legs: None,
+            trail_percent: None,
+            trail_price: None,
+            hwm: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum OrderSide {
     Buy,
     Sell,
@@ -81,3 +144,32 @@ impl FromStr for OrderSide {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_multi_leg_order() {
+        let json = r#"{
+            "id": "parent", "client_order_id": "c1", "symbol": "", "asset_class": "", "qty": "2",
+            "filled_qty": "0", "order_class": "mleg", "order_type": "limit", "type": "limit", "side": "",
+            "time_in_force": "day", "limit_price": "3.25", "status": "new", "extended_hours": false,
+            "legs": [
+                {"id": "leg1", "symbol": "AAPL250620C00200000", "asset_class": "us_option", "qty": "2",
+                 "order_class": "mleg", "order_type": "", "type": "", "side": "buy", "position_intent": "buy_to_open",
+                 "ratio_qty": "1", "status": "new", "extended_hours": false, "legs": null},
+                {"id": "leg2", "symbol": "AAPL250620C00210000", "asset_class": "us_option", "qty": "2",
+                 "order_class": "mleg", "order_type": "", "type": "", "side": "sell", "position_intent": "sell_to_open",
+                 "ratio_qty": "1", "status": "new", "extended_hours": false, "legs": null}
+            ]
+        }"#;
+
+        let order: Order = serde_json::from_str(json).unwrap();
+        let legs = order.legs.unwrap();
+        assert_eq!(order.order_class.as_deref(), Some("mleg"));
+        assert_eq!(legs.len(), 2);
+        assert_eq!(legs[1].position_intent.as_deref(), Some("sell_to_open"));
+        assert_eq!(legs[1].ratio_qty.as_deref(), Some("1"));
+    }
+}
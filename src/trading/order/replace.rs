@@ -1,7 +1,11 @@
 use serde::Serialize;
 
-use super::{create::TimeInForce, Order};
-use crate::{json_request, trading::AccountType};
+use super::{CreateOrderQuery, GetOrdersQuery, Order, create::TimeInForce};
+use crate::{
+    dry_run, json_request,
+    trading::{AccountType, risk::RiskManager},
+};
+use std::time::Instant;
 
 #[derive(Serialize, Debug)]
 pub struct ReplaceOrderQuery<'a> {
@@ -25,6 +29,9 @@ pub struct ReplaceOrderQuery<'a> {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub client_order_id: Option<&'a str>,
+
+    #[serde(skip)]
+    risk: Option<&'a RiskManager>,
 }
 
 impl<'a> ReplaceOrderQuery<'a> {
@@ -37,6 +44,7 @@ impl<'a> ReplaceOrderQuery<'a> {
             stop_price: None,
             trail: None,
             client_order_id: None,
+            risk: None,
         }
     }
 
@@ -70,7 +78,50 @@ impl<'a> ReplaceOrderQuery<'a> {
         self
     }
 
+    /// Check the replacement against the risk manager's limits before sending, as an order for the unfilled
+    /// quantity at the new prices. The original order is fetched for its symbol, side and fills. A refused
+    /// replacement is returned as `ureq::Error::Other(RiskViolation)` and the original order stays as it is.
+    pub fn risk_manager(mut self, risk: &'a RiskManager) -> Self {
+        self.risk = Some(risk);
+        self
+    }
+
+    /// In dry-run mode the returned order only has the fields set on the query, and `replaces` set to `order_id`.
     pub fn send(self, account_type: AccountType) -> Result<Order, ureq::Error> {
+        let reserved = match self.risk {
+            Some(risk) => Some(self.reserve(risk, account_type)?),
+            None => None,
+        };
+        let risk = self.risk;
+        let order = self.patch(account_type);
+        if let (Some(risk), Some(reserved), Err(_)) = (risk, reserved, &order) {
+            risk.release(reserved);
+        }
+        order
+    }
+
+    fn reserve(
+        &self,
+        risk: &RiskManager,
+        account_type: AccountType,
+    ) -> Result<Instant, ureq::Error> {
+        let original = GetOrdersQuery::new(account_type).get_by_id(self.order_id, true)?;
+        let number = |value: Option<&str>| value.and_then(|v| v.parse::<f64>().ok());
+        let unfilled = number(self.qty.or(original.qty.as_deref())).map(|qty| {
+            let filled = number(original.filled_qty.as_deref()).unwrap_or(0.0);
+            (qty - filled).max(0.0).to_string()
+        });
+        let query = CreateOrderQuery::replacement(
+            &original,
+            unfilled.as_deref(),
+            self.limit_price,
+            self.stop_price,
+        );
+        risk.reserve(&query)
+            .map_err(|e| ureq::Error::Other(Box::new(e)))
+    }
+
+    fn patch(self, account_type: AccountType) -> Result<Order, ureq::Error> {
         let url = match account_type {
             AccountType::Live => format!("https://api.alpaca.markets/v2/orders/{}", self.order_id),
             AccountType::Paper => format!(
@@ -78,6 +129,19 @@ impl<'a> ReplaceOrderQuery<'a> {
                 self.order_id
             ),
         };
+        if dry_run::intercept_json("PATCH", &url, &self) {
+            let owned = |value: Option<&str>| value.map(str::to_string);
+            let mut order = Order::dry_run("", "", "");
+            order.replaces = Some(self.order_id.to_string());
+            order.client_order_id = owned(self.client_order_id);
+            order.qty = owned(self.qty);
+            order.time_in_force = self.time_in_force;
+            order.limit_price = owned(self.limit_price);
+            order.stop_price = owned(self.stop_price);
+            order.trail_price = owned(self.trail);
+            return Ok(order);
+        }
+
         let response = json_request("PATCH", &url)
             .header("Content-Type", "application/json")
             .send_json(&self)?;
@@ -90,6 +154,30 @@ impl<'a> ReplaceOrderQuery<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::trading::risk::{RiskLimits, RiskViolation};
+
+    #[test]
+    fn test_replacement_risk_check() {
+        let original: Order = serde_json::from_value(serde_json::json!({
+            "id": "o", "symbol": "AAPL", "order_type": "limit", "type": "limit", "side": "buy",
+            "status": "partially_filled", "extended_hours": false,
+            "qty": "10", "filled_qty": "4", "limit_price": "100"
+        }))
+        .unwrap();
+        let risk = RiskManager::new(RiskLimits::new().max_order_notional(2_000.0));
+
+        // 16 unfilled at the new limit
+        let query = CreateOrderQuery::replacement(&original, Some("16"), Some("150"), None);
+        assert!(matches!(
+            risk.check(&query),
+            Err(RiskViolation::OrderNotional { notional, .. }) if notional == 2_400.0
+        ));
+        let query = CreateOrderQuery::replacement(&original, Some("6"), None, None);
+        assert!(risk.check(&query).is_ok());
+
+        risk.engage_kill_switch();
+        assert_eq!(risk.check(&query), Err(RiskViolation::KillSwitch));
+    }
 
     #[test]
     #[ignore] // Requires a valid open limit/stop order ID - run manually
@@ -0,0 +1,418 @@
+use super::{AllOrders, GetOrdersQuery, Order};
+use crate::{
+    stream::{StreamError, TradeUpdate, TradeUpdateStream, TradeUpdateSubscription},
+    time::parse_rfc3339_nanos,
+    trading::AccountType,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+/// Order statuses after which an order can no longer fill.
+const TERMINAL_STATUSES: [&str; 5] = ["filled", "canceled", "expired", "rejected", "replaced"];
+
+/// Whether an order `status` is final.
+pub fn is_terminal_status(status: &str) -> bool {
+    TERMINAL_STATUSES.contains(&status)
+}
+
+/// One trade update applied to an order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderEvent {
+    pub event: String,
+    /// Order status after the event.
+    pub status: String,
+    pub timestamp: Option<String>,
+    pub price: Option<f64>,
+    pub qty: Option<f64>,
+}
+
+/// A `fill` or `partial_fill` execution.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fill {
+    pub order_id: String,
+    pub client_order_id: Option<String>,
+    pub symbol: String,
+    pub side: String,
+    pub price: f64,
+    pub qty: f64,
+    /// Position size after the fill.
+    pub position_qty: Option<f64>,
+    pub timestamp: Option<String>,
+    /// `false` for the fill that completes the order.
+    pub partial: bool,
+}
+
+#[derive(Debug, Default)]
+struct TrackerState {
+    orders: HashMap<String, Order>,
+    history: HashMap<String, Vec<OrderEvent>>,
+    fills: Vec<Fill>,
+    /// Leg id to the id of its bracket, OCO, OTO or multi-leg parent.
+    parents: HashMap<String, String>,
+    client_order_ids: HashMap<String, String>,
+}
+
+impl TrackerState {
+    /// Store an order and its legs, unless an update with a later `updated_at` is already stored.
+    fn upsert(&mut self, mut order: Order) {
+        if let Some(existing) = self.orders.get(&order.id)
+            && is_stale(existing, &order)
+        {
+            return;
+        }
+
+        for leg in order.legs.iter().flatten() {
+            self.parents.insert(leg.id.clone(), order.id.clone());
+        }
+        if let Some(legs) = order.legs.take() {
+            for leg in &legs {
+                self.upsert(leg.clone());
+            }
+            order.legs = Some(legs);
+        }
+
+        // Keep the copy of this leg inside its parent current
+        if let Some(parent_id) = self.parents.get(&order.id)
+            && let Some(parent) = self.orders.get_mut(parent_id)
+            && let Some(leg) = parent
+                .legs
+                .iter_mut()
+                .flatten()
+                .find(|leg| leg.id == order.id)
+        {
+            *leg = order.clone();
+        }
+
+        if let Some(client_order_id) = &order.client_order_id {
+            self.client_order_ids
+                .insert(client_order_id.clone(), order.id.clone());
+        }
+        self.orders.insert(order.id.clone(), order);
+    }
+}
+
+fn is_stale(existing: &Order, incoming: &Order) -> bool {
+    let time = |order: &Order| order.updated_at.as_deref().and_then(parse_rfc3339_nanos);
+    matches!((time(existing), time(incoming)), (Some(a), Some(b)) if b < a)
+}
+
+fn number(value: &Option<String>) -> Option<f64> {
+    value.as_deref()?.parse().ok()
+}
+
+/// Current state of the account's orders, seeded from a REST snapshot and kept up to date with trade updates.
+///
+/// Cloning is cheap and every clone shares the same state, so one thread can [`listen`](OrderTracker::listen)
+/// while others query.
+#[derive(Debug, Clone, Default)]
+pub struct OrderTracker {
+    state: Arc<RwLock<TrackerState>>,
+}
+
+impl OrderTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Seed from the open orders, with bracket legs nested. At most 500 orders are loaded, the most
+    /// the API returns in one request.
+    ///
+    /// Updates between the snapshot and a later [`listen`](OrderTracker::listen) are missed, use
+    /// [`seed_subscribed`](OrderTracker::seed_subscribed) to keep orders current from the start.
+    pub fn seed(account_type: AccountType) -> Result<Self, ureq::Error> {
+        let orders = GetOrdersQuery::new(account_type)
+            .status("open")
+            .nested(true)
+            .limit(500)
+            .send()?;
+        Ok(Self::from_orders(orders))
+    }
+
+    /// Seed from the open orders once `subscription` is connected, so updates that arrive while the
+    /// snapshot loads wait in the subscription. Apply them with [`listen_on`](OrderTracker::listen_on).
+    ///
+    /// ```no_run
+    /// use alpaca_api_client::{stream::TradeUpdateStream, trading::{AccountType, order::OrderTracker}};
+    ///
+    /// let subscription = TradeUpdateStream::new(AccountType::Paper).connect().unwrap();
+    /// let tracker = OrderTracker::seed_subscribed(AccountType::Paper, &subscription).unwrap();
+    /// let listener = tracker.clone();
+    /// std::thread::spawn(move || listener.listen_on(subscription));
+    /// ```
+    pub fn seed_subscribed(
+        account_type: AccountType,
+        _subscription: &TradeUpdateSubscription,
+    ) -> Result<Self, ureq::Error> {
+        Self::seed(account_type)
+    }
+
+    pub fn from_orders(orders: AllOrders) -> Self {
+        let tracker = Self::new();
+        {
+            let mut state = tracker.write();
+            for order in orders {
+                state.upsert(order);
+            }
+        }
+        tracker
+    }
+
+    /// Apply a trade update. Updates older than the stored order state are recorded in the history only.
+    pub fn apply(&self, update: &TradeUpdate) {
+        let mut state = self.write();
+        let order = &update.order;
+
+        state
+            .history
+            .entry(order.id.clone())
+            .or_default()
+            .push(OrderEvent {
+                event: update.event.clone(),
+                status: order.status.clone(),
+                timestamp: update.timestamp.clone(),
+                price: number(&update.price),
+                qty: number(&update.qty),
+            });
+
+        if matches!(update.event.as_str(), "fill" | "partial_fill")
+            && let (Some(price), Some(qty)) = (number(&update.price), number(&update.qty))
+        {
+            state.fills.push(Fill {
+                order_id: order.id.clone(),
+                client_order_id: order.client_order_id.clone(),
+                symbol: order.symbol.clone(),
+                side: order.side.clone(),
+                price,
+                qty,
+                position_qty: number(&update.position_qty),
+                timestamp: update.timestamp.clone(),
+                partial: update.event == "partial_fill",
+            });
+        }
+
+        state.upsert(order.clone());
+    }
+
+    /// Subscribe to trade updates and apply them until the connection closes. Blocks the calling thread.
+    pub fn listen(&self, account_type: AccountType) -> Result<(), StreamError> {
+        self.listen_on(TradeUpdateStream::new(account_type).connect()?)
+    }
+
+    /// Apply the updates of an existing subscription until the connection closes. Blocks the calling thread.
+    pub fn listen_on(&self, subscription: TradeUpdateSubscription) -> Result<(), StreamError> {
+        subscription.start(|update| self.apply(&update))
+    }
+
+    pub fn get(&self, order_id: &str) -> Option<Order> {
+        self.read().orders.get(order_id).cloned()
+    }
+
+    pub fn get_by_client_order_id(&self, client_order_id: &str) -> Option<Order> {
+        let state = self.read();
+        let order_id = state.client_order_ids.get(client_order_id)?;
+        state.orders.get(order_id).cloned()
+    }
+
+    /// All orders that can still fill, including open legs of filled parents.
+    pub fn open_orders(&self) -> Vec<Order> {
+        self.read()
+            .orders
+            .values()
+            .filter(|order| !is_terminal_status(&order.status))
+            .cloned()
+            .collect()
+    }
+
+    /// Every fill seen, in the order received.
+    pub fn fills(&self) -> Vec<Fill> {
+        self.read().fills.clone()
+    }
+
+    pub fn fills_for(&self, order_id: &str) -> Vec<Fill> {
+        self.read()
+            .fills
+            .iter()
+            .filter(|fill| fill.order_id == order_id)
+            .cloned()
+            .collect()
+    }
+
+    /// Trade updates applied to an order, oldest first.
+    pub fn history(&self, order_id: &str) -> Vec<OrderEvent> {
+        self.read()
+            .history
+            .get(order_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Legs of a bracket, OCO, OTO or multi-leg order.
+    pub fn legs(&self, parent_id: &str) -> Vec<Order> {
+        let state = self.read();
+        state
+            .orders
+            .get(parent_id)
+            .and_then(|parent| parent.legs.as_ref())
+            .map(|legs| {
+                legs.iter()
+                    .filter_map(|leg| state.orders.get(&leg.id).cloned())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Parent of a leg.
+    pub fn parent(&self, leg_id: &str) -> Option<Order> {
+        let state = self.read();
+        let parent_id = state.parents.get(leg_id)?;
+        state.orders.get(parent_id).cloned()
+    }
+
+    /// Ids of every version of an order, from the original through each replacement, following
+    /// `replaces` and `replaced_by` from any id in the chain.
+    pub fn replacement_chain(&self, order_id: &str) -> Vec<String> {
+        let state = self.read();
+        let mut first = order_id.to_string();
+        while let Some(previous) = state
+            .orders
+            .get(&first)
+            .and_then(|order| order.replaces.clone())
+            .filter(|previous| *previous != order_id)
+        {
+            first = previous;
+        }
+
+        let mut chain = vec![first];
+        while let Some(next) = state
+            .orders
+            .get(chain.last().unwrap())
+            .and_then(|order| order.replaced_by.clone())
+            .filter(|next| !chain.contains(next))
+        {
+            chain.push(next);
+        }
+        chain
+    }
+
+    /// The latest known version of an order that may have been replaced.
+    pub fn current(&self, order_id: &str) -> Option<Order> {
+        let chain = self.replacement_chain(order_id);
+        let state = self.read();
+        chain
+            .iter()
+            .rev()
+            .find_map(|id| state.orders.get(id).cloned())
+    }
+
+    /// Number of orders tracked, including legs and replaced orders.
+    pub fn len(&self) -> usize {
+        self.read().orders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.read().orders.is_empty()
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, TrackerState> {
+        self.state.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, TrackerState> {
+        self.state.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(id: &str, status: &str, updated_at: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": id, "client_order_id": format!("c-{}", id), "updated_at": updated_at,
+            "symbol": "AAPL", "qty": "10", "filled_qty": "0", "order_type": "limit", "type": "limit",
+            "side": "buy", "status": status, "extended_hours": false
+        })
+    }
+
+    fn update(
+        event: &str,
+        order: serde_json::Value,
+        price: Option<&str>,
+        qty: Option<&str>,
+    ) -> TradeUpdate {
+        serde_json::from_value(serde_json::json!({
+            "event": event, "order": order, "timestamp": "2025-03-20T14:00:00Z",
+            "price": price, "qty": qty, "position_qty": qty
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_tracker_applies_updates() {
+        let mut parent = order("p", "new", "2025-03-20T14:00:00Z");
+        parent["order_class"] = "bracket".into();
+        parent["legs"] = serde_json::json!([
+            order("tp", "held", "2025-03-20T14:00:00Z"),
+            order("sl", "held", "2025-03-20T14:00:00Z")
+        ]);
+        let tracker =
+            OrderTracker::from_orders(vec![serde_json::from_value(parent.clone()).unwrap()]);
+        assert_eq!(tracker.open_orders().len(), 3);
+        assert_eq!(tracker.parent("tp").unwrap().id, "p");
+
+        parent["status"] = "partially_filled".into();
+        parent["updated_at"] = "2025-03-20T14:01:00Z".into();
+        tracker.apply(&update(
+            "partial_fill",
+            parent.clone(),
+            Some("150.5"),
+            Some("4"),
+        ));
+        parent["status"] = "filled".into();
+        parent["updated_at"] = "2025-03-20T14:02:00Z".into();
+        tracker.apply(&update("fill", parent.clone(), Some("150.25"), Some("6")));
+
+        // A late update does not roll the order back
+        parent["status"] = "new".into();
+        parent["updated_at"] = "2025-03-20T13:59:00Z".into();
+        tracker.apply(&update("new", parent, None, None));
+
+        let mut take_profit = order("tp", "new", "2025-03-20T14:02:00Z");
+        take_profit["side"] = "sell".into();
+        tracker.apply(&update("new", take_profit, None, None));
+
+        assert_eq!(tracker.get("p").unwrap().status, "filled");
+        assert_eq!(tracker.history("p").len(), 3);
+        let fills = tracker.fills_for("p");
+        assert_eq!(fills.len(), 2);
+        assert!(fills[0].partial && !fills[1].partial);
+        assert_eq!(fills[1].price, 150.25);
+
+        assert_eq!(tracker.legs("p")[0].status, "new");
+        assert_eq!(tracker.get("p").unwrap().legs.unwrap()[0].status, "new");
+        assert_eq!(tracker.open_orders().len(), 2);
+        assert_eq!(tracker.get_by_client_order_id("c-sl").unwrap().id, "sl");
+    }
+
+    #[test]
+    fn test_tracker_follows_replacements() {
+        let tracker = OrderTracker::new();
+        let mut first = order("a", "replaced", "2025-03-20T14:01:00Z");
+        first["replaced_by"] = "b".into();
+        let mut second = order("b", "replaced", "2025-03-20T14:02:00Z");
+        second["replaces"] = "a".into();
+        second["replaced_by"] = "c".into();
+        let mut third = order("c", "new", "2025-03-20T14:03:00Z");
+        third["replaces"] = "b".into();
+
+        tracker.apply(&update("replaced", first, None, None));
+        tracker.apply(&update("replaced", second, None, None));
+        tracker.apply(&update("new", third, None, None));
+
+        assert_eq!(tracker.replacement_chain("b"), vec!["a", "b", "c"]);
+        assert_eq!(tracker.current("a").unwrap().id, "c");
+        assert_eq!(tracker.open_orders().len(), 1);
+    }
+}
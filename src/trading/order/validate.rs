@@ -0,0 +1,396 @@
+use super::CreateOrderQuery;
+use std::fmt;
+
+/// A single reason an order would be rejected.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderValidationError {
+    /// Neither `qty` nor `notional` is set.
+    MissingQuantity,
+    /// Both `qty` and `notional` are set.
+    QtyAndNotional,
+    /// A numeric field could not be parsed, or is not positive.
+    InvalidNumber {
+        field: &'static str,
+        value: String,
+    },
+    /// `limit` and `stop_limit` orders need a `limit_price`.
+    MissingLimitPrice,
+    /// `stop` and `stop_limit` orders need a `stop_price`.
+    MissingStopPrice,
+    /// `trailing_stop` orders need exactly one of `trail_price` or `trail_percent`.
+    InvalidTrail,
+    /// `bracket` and `oco` orders need a `take_profit`.
+    MissingTakeProfit,
+    /// `bracket` and `oco` orders need a `stop_loss`, `oto` orders need one of the two.
+    MissingStopLoss,
+    /// `oco` orders must be `limit` orders.
+    OcoNotLimit,
+    /// `mleg` orders need between 2 and 4 legs.
+    InvalidLegCount(usize),
+    /// Equity prices of $1.00 or more allow 2 decimals, below $1.00 allow 4.
+    SubPennyPrice {
+        field: &'static str,
+        price: String,
+    },
+    /// Crypto orders only accept `gtc` and `ioc`.
+    CryptoTimeInForce(String),
+    /// Crypto assets can not be sold short.
+    CryptoShort(String),
+    /// The symbol is not in the asset cache.
+    UnknownAsset(String),
+    NotTradable(String),
+    /// Fractional `qty` or `notional` on an asset that is not `fractionable`.
+    NotFractionable(String),
+    /// Sell order larger than the held position on an asset that is not `shortable`.
+    NotShortable(String),
+    /// Sell order larger than the held position on an asset that is not `easy_to_borrow`.
+    HardToBorrow(String),
+}
+
+impl fmt::Display for OrderValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingQuantity => write!(f, "one of qty or notional is required"),
+            Self::QtyAndNotional => write!(f, "qty and notional can not both be set"),
+            Self::InvalidNumber { field, value } => write!(f, "invalid {}: {}", field, value),
+            Self::MissingLimitPrice => write!(f, "limit_price is required for this order type"),
+            Self::MissingStopPrice => write!(f, "stop_price is required for this order type"),
+            Self::InvalidTrail => {
+                write!(f, "exactly one of trail_price or trail_percent is required")
+            }
+            Self::MissingTakeProfit => write!(f, "take_profit is required for this order class"),
+            Self::MissingStopLoss => write!(f, "stop_loss is required for this order class"),
+            Self::OcoNotLimit => write!(f, "oco orders must be limit orders"),
+            Self::InvalidLegCount(count) => {
+                write!(f, "multi-leg orders need 2 to 4 legs, got {}", count)
+            }
+            Self::SubPennyPrice { field, price } => {
+                write!(f, "sub-penny increment in {}: {}", field, price)
+            }
+            Self::CryptoTimeInForce(tif) => {
+                write!(f, "time_in_force {} is not supported for crypto", tif)
+            }
+            Self::CryptoShort(symbol) => write!(f, "{} can not be sold short", symbol),
+            Self::UnknownAsset(symbol) => write!(f, "unknown asset: {}", symbol),
+            Self::NotTradable(symbol) => write!(f, "{} is not tradable", symbol),
+            Self::NotFractionable(symbol) => write!(f, "{} is not fractionable", symbol),
+            Self::NotShortable(symbol) => write!(f, "{} is not shortable", symbol),
+            Self::HardToBorrow(symbol) => write!(f, "{} is not easy to borrow", symbol),
+        }
+    }
+}
+
+/// All the problems found with an order. Returned by [`CreateOrderQuery::validate`],
+/// and wrapped in `ureq::Error::Other` when validation runs as part of `send`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationErrors(pub Vec<OrderValidationError>);
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let errors: Vec<String> = self.0.iter().map(|e| e.to_string()).collect();
+        write!(f, "order validation failed: {}", errors.join("; "))
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+impl CreateOrderQuery<'_> {
+    /// Check the order shape, and the asset rules when an [`AssetCache`](crate::trading::assets::AssetCache) is attached.
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = Vec::new();
+
+        let qty = number(&mut errors, "qty", self.qty);
+        let notional = number(&mut errors, "notional", self.notional);
+        match (self.qty, self.notional) {
+            (None, None) => errors.push(OrderValidationError::MissingQuantity),
+            (Some(_), Some(_)) => errors.push(OrderValidationError::QtyAndNotional),
+            _ => {}
+        }
+
+        let order_type = self.r#type.as_str();
+        if matches!(order_type, "limit" | "stop_limit") && self.limit_price.is_none() {
+            errors.push(OrderValidationError::MissingLimitPrice);
+        }
+        if matches!(order_type, "stop" | "stop_limit") && self.stop_price.is_none() {
+            errors.push(OrderValidationError::MissingStopPrice);
+        }
+        if order_type == "trailing_stop"
+            && self.trail_price.is_some() == self.trail_percent.is_some()
+        {
+            errors.push(OrderValidationError::InvalidTrail);
+        }
+        number(&mut errors, "trail_price", self.trail_price);
+        number(&mut errors, "trail_percent", self.trail_percent);
+
+        match self.order_class.as_deref() {
+            Some("bracket") => {
+                if self.take_profit.is_none() {
+                    errors.push(OrderValidationError::MissingTakeProfit);
+                }
+                if self.stop_loss.is_none() {
+                    errors.push(OrderValidationError::MissingStopLoss);
+                }
+            }
+            Some("oco") => {
+                if order_type != "limit" {
+                    errors.push(OrderValidationError::OcoNotLimit);
+                }
+                if self.take_profit.is_none() {
+                    errors.push(OrderValidationError::MissingTakeProfit);
+                }
+                if self.stop_loss.is_none() {
+                    errors.push(OrderValidationError::MissingStopLoss);
+                }
+            }
+            Some("oto") if self.take_profit.is_none() && self.stop_loss.is_none() => {
+                errors.push(OrderValidationError::MissingStopLoss);
+            }
+            _ => {}
+        }
+
+        // Legs are options contracts, and the net limit price is negative for a credit
+        if self.order_class.as_deref() == Some("mleg") {
+            let legs = self.legs.as_deref().unwrap_or_default();
+            if !(2..=4).contains(&legs.len()) {
+                errors.push(OrderValidationError::InvalidLegCount(legs.len()));
+            }
+            for leg in legs {
+                number(&mut errors, "ratio_qty", Some(leg.ratio_qty));
+            }
+            if let Some(limit_price) = self.limit_price
+                && !limit_price.parse::<f64>().is_ok_and(f64::is_finite)
+            {
+                errors.push(OrderValidationError::InvalidNumber {
+                    field: "limit_price",
+                    value: limit_price.to_string(),
+                });
+            }
+            return finish(errors);
+        }
+
+        let asset = self.assets.and_then(|cache| cache.get(self.symbol));
+        let is_crypto = match asset {
+            Some(asset) => asset.class == "crypto",
+            None => self.symbol.contains('/'),
+        };
+
+        let prices = [
+            ("limit_price", self.limit_price),
+            ("stop_price", self.stop_price),
+            (
+                "take_profit.limit_price",
+                self.take_profit.as_ref().map(|tp| tp.limit_price),
+            ),
+            (
+                "stop_loss.stop_price",
+                self.stop_loss.as_ref().map(|sl| sl.stop_price),
+            ),
+            (
+                "stop_loss.limit_price",
+                self.stop_loss.as_ref().map(|sl| sl.limit_price),
+            ),
+        ];
+        for (field, price) in prices {
+            let value = number(&mut errors, field, price);
+            if let (Some(value), Some(raw)) = (value, price)
+                && !is_crypto
+                && decimals(raw) > if value >= 1.0 { 2 } else { 4 }
+            {
+                errors.push(OrderValidationError::SubPennyPrice {
+                    field,
+                    price: raw.to_string(),
+                });
+            }
+        }
+
+        let is_sell = self.side == "sell";
+        if is_crypto && !matches!(self.time_in_force.as_str(), "gtc" | "ioc") {
+            errors.push(OrderValidationError::CryptoTimeInForce(
+                self.time_in_force.clone(),
+            ));
+        }
+
+        if let Some(cache) = self.assets {
+            let symbol = self.symbol.to_string();
+            match asset {
+                None => errors.push(OrderValidationError::UnknownAsset(symbol)),
+                Some(asset) => {
+                    if !asset.tradable {
+                        errors.push(OrderValidationError::NotTradable(symbol.clone()));
+                    }
+                    let fractional = notional.is_some() || qty.is_some_and(|q| q.fract() != 0.0);
+                    if fractional && !asset.fractionable {
+                        errors.push(OrderValidationError::NotFractionable(symbol.clone()));
+                    }
+
+                    // Only known to be a short when positions were provided
+                    let held = cache.held_qty(self.symbol);
+                    let is_short = match (held, qty) {
+                        (Some(held), Some(qty)) => is_sell && qty > held.max(0.0),
+                        (Some(held), None) => is_sell && held <= 0.0,
+                        _ => false,
+                    };
+                    if is_short {
+                        if is_crypto {
+                            errors.push(OrderValidationError::CryptoShort(symbol));
+                        } else if !asset.shortable {
+                            errors.push(OrderValidationError::NotShortable(symbol));
+                        } else if !asset.easy_to_borrow {
+                            errors.push(OrderValidationError::HardToBorrow(symbol));
+                        }
+                    }
+                }
+            }
+        }
+
+        finish(errors)
+    }
+}
+
+fn finish(errors: Vec<OrderValidationError>) -> Result<(), ValidationErrors> {
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ValidationErrors(errors))
+    }
+}
+
+/// Parse an optional positive number, recording an error if it is malformed.
+fn number(
+    errors: &mut Vec<OrderValidationError>,
+    field: &'static str,
+    value: Option<&str>,
+) -> Option<f64> {
+    let raw = value?;
+    match raw.parse::<f64>() {
+        Ok(parsed) if parsed > 0.0 && parsed.is_finite() => Some(parsed),
+        _ => {
+            errors.push(OrderValidationError::InvalidNumber {
+                field,
+                value: raw.to_string(),
+            });
+            None
+        }
+    }
+}
+
+fn decimals(raw: &str) -> usize {
+    raw.split_once('.')
+        .map(|(_, fraction)| fraction.trim_end_matches('0').len())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::{
+        assets::{Asset, AssetCache},
+        order::{OrderClass, OrderSide, OrderType, TakeProfit, TimeInForce},
+    };
+
+    fn asset(symbol: &str, class: &str, fractionable: bool, shortable: bool) -> Asset {
+        Asset {
+            id: symbol.to_string(),
+            class: class.to_string(),
+            exchange: "NASDAQ".to_string(),
+            symbol: symbol.to_string(),
+            name: symbol.to_string(),
+            status: "active".to_string(),
+            tradable: true,
+            marginable: true,
+            shortable,
+            easy_to_borrow: shortable,
+            fractionable,
+            margin_requirement_long: None,
+            margin_requirement_short: None,
+            attributes: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_order_shape() {
+        let errors =
+            CreateOrderQuery::new("AAPL", OrderSide::Buy, OrderType::Limit, TimeInForce::Day)
+                .validate()
+                .unwrap_err();
+        assert_eq!(
+            errors.0,
+            vec![
+                OrderValidationError::MissingQuantity,
+                OrderValidationError::MissingLimitPrice
+            ]
+        );
+
+        let errors =
+            CreateOrderQuery::new("AAPL", OrderSide::Buy, OrderType::Market, TimeInForce::Day)
+                .qty("1")
+                .order_class(OrderClass::Bracket)
+                .take_profit(TakeProfit::new("210.125"))
+                .validate()
+                .unwrap_err();
+        assert!(errors.0.contains(&OrderValidationError::MissingStopLoss));
+        assert!(errors.0.contains(&OrderValidationError::SubPennyPrice {
+            field: "take_profit.limit_price",
+            price: "210.125".to_string()
+        }));
+
+        assert!(
+            CreateOrderQuery::new("PENNY", OrderSide::Buy, OrderType::Limit, TimeInForce::Day)
+                .qty("100")
+                .limit_price("0.1234")
+                .validate()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_validate_against_assets() {
+        let cache = AssetCache::from_assets(vec![
+            asset("BRK.A", "us_equity", false, false),
+            asset("BTC/USD", "crypto", true, false),
+        ])
+        .with_positions(&vec![]);
+
+        let errors = CreateOrderQuery::new(
+            "BRK.A",
+            OrderSide::Sell,
+            OrderType::Market,
+            TimeInForce::Day,
+        )
+        .qty("0.5")
+        .asset_cache(&cache)
+        .validate()
+        .unwrap_err();
+        assert_eq!(
+            errors.0,
+            vec![
+                OrderValidationError::NotFractionable("BRK.A".to_string()),
+                OrderValidationError::NotShortable("BRK.A".to_string())
+            ]
+        );
+
+        let errors = CreateOrderQuery::new(
+            "BTCUSD",
+            OrderSide::Buy,
+            OrderType::Market,
+            TimeInForce::Day,
+        )
+        .notional("100")
+        .asset_cache(&cache)
+        .validate()
+        .unwrap_err();
+        assert_eq!(
+            errors.0,
+            vec![OrderValidationError::CryptoTimeInForce("day".to_string())]
+        );
+
+        let errors =
+            CreateOrderQuery::new("NOPE", OrderSide::Buy, OrderType::Market, TimeInForce::Day)
+                .qty("1")
+                .asset_cache(&cache)
+                .validate_on_send(true)
+                .send(crate::trading::AccountType::Paper)
+                .unwrap_err();
+        assert!(errors.to_string().contains("unknown asset: NOPE"));
+    }
+}
@@ -0,0 +1,214 @@
+use super::{
+    CreateOrderQuery, GetOrdersQuery, Order, OrderBuilder, ReplaceOrderQuery, delete_by_id,
+    is_terminal_status,
+};
+use crate::{
+    is_dry_run,
+    stream::{TradeUpdateStream, TradeUpdateSubscription},
+    trading::AccountType,
+};
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+/// How often the order is fetched over REST while waiting, and the only source of updates
+/// when the trade updates stream can not be used.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug)]
+pub enum WaitError {
+    /// Submitting, canceling, replacing or fetching the order failed.
+    Request(ureq::Error),
+    /// The order did not reach the expected state in time. Holds the last known state.
+    Timeout(Box<Order>),
+}
+
+impl fmt::Display for WaitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaitError::Request(e) => write!(f, "order request failed: {}", e),
+            WaitError::Timeout(order) => write!(
+                f,
+                "timed out waiting for order {}, last status {}",
+                order.id, order.status
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WaitError {}
+
+impl From<ureq::Error> for WaitError {
+    fn from(e: ureq::Error) -> Self {
+        WaitError::Request(e)
+    }
+}
+
+/// Subscribe to trade updates before acting on an order, so no update is missed.
+/// Without a subscription, waiting falls back to polling.
+fn subscribe(account_type: AccountType) -> Option<TradeUpdateSubscription> {
+    TradeUpdateStream::new(account_type).connect().ok()
+}
+
+/// Wait until `done` holds for the order, from trade updates with periodic REST polls.
+/// In dry-run mode nothing was sent, so the order is returned as is.
+fn wait_until<F>(
+    mut subscription: Option<TradeUpdateSubscription>,
+    account_type: AccountType,
+    mut order: Order,
+    timeout: Duration,
+    done: F,
+) -> Result<Order, WaitError>
+where
+    F: Fn(&Order) -> bool,
+{
+    if is_dry_run() {
+        return Ok(order);
+    }
+    let deadline = Instant::now() + timeout;
+    let mut next_poll = Instant::now() + POLL_INTERVAL;
+
+    while !done(&order) {
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(WaitError::Timeout(Box::new(order)));
+        }
+        let until = next_poll.min(deadline);
+
+        match subscription.as_mut() {
+            Some(stream) => match stream.next(Some(until - now)) {
+                Ok(Some(update)) if update.order.id == order.id => {
+                    order = update.order;
+                    continue;
+                }
+                Ok(_) => {}
+                Err(_) => subscription = None,
+            },
+            None => std::thread::sleep(until - now),
+        }
+
+        if Instant::now() >= next_poll {
+            order = GetOrdersQuery::new(account_type).get_by_id(&order.id, true)?;
+            next_poll = Instant::now() + POLL_INTERVAL;
+        }
+    }
+    Ok(order)
+}
+
+fn is_terminal(order: &Order) -> bool {
+    is_terminal_status(&order.status)
+}
+
+/// Working at the exchange, or final. `accepted` and `pending_*` orders have not reached the exchange yet.
+fn is_live_or_terminal(order: &Order) -> bool {
+    matches!(order.status.as_str(), "new" | "partially_filled") || is_terminal(order)
+}
+
+impl CreateOrderQuery<'_> {
+    /// Send the order and block until it is filled or otherwise final (`canceled`, `expired`, `rejected`).
+    /// The final order is returned whatever its status, check `status` for `filled`.
+    pub fn send_and_wait_fill(
+        self,
+        account_type: AccountType,
+        timeout: Duration,
+    ) -> Result<Order, WaitError> {
+        let subscription = subscribe(account_type);
+        let order = self.send(account_type)?;
+        wait_until(subscription, account_type, order, timeout, is_terminal)
+    }
+}
+
+impl<K> OrderBuilder<'_, K> {
+    /// See [`CreateOrderQuery::send_and_wait_fill`].
+    pub fn send_and_wait_fill(
+        self,
+        account_type: AccountType,
+        timeout: Duration,
+    ) -> Result<Order, WaitError> {
+        self.into_query().send_and_wait_fill(account_type, timeout)
+    }
+}
+
+impl ReplaceOrderQuery<'_> {
+    /// Replace the order and block until the replacement is `new`, `partially_filled` or final.
+    /// Returns the new order.
+    pub fn replace_and_confirm(
+        self,
+        account_type: AccountType,
+        timeout: Duration,
+    ) -> Result<Order, WaitError> {
+        let subscription = subscribe(account_type);
+        let order = self.send(account_type)?;
+        wait_until(
+            subscription,
+            account_type,
+            order,
+            timeout,
+            is_live_or_terminal,
+        )
+    }
+}
+
+/// Cancel an order and block until it is final. The order may have filled before the cancel
+/// took effect, so check `status` for `canceled`.
+pub fn cancel_and_confirm(
+    order_id: &str,
+    account_type: AccountType,
+    timeout: Duration,
+) -> Result<Order, WaitError> {
+    let subscription = subscribe(account_type);
+    let order = GetOrdersQuery::new(account_type).get_by_id(order_id, true)?;
+    if is_terminal(&order) {
+        return Ok(order);
+    }
+    delete_by_id(order_id, account_type)?;
+    wait_until(subscription, account_type, order, timeout, is_terminal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::order::{OrderSide, OrderType, TimeInForce};
+
+    #[test]
+    fn test_wait_until_times_out_with_last_state() {
+        let order: Order = serde_json::from_value(serde_json::json!({
+            "id": "o", "symbol": "AAPL", "order_type": "limit", "type": "limit",
+            "side": "buy", "status": "new", "extended_hours": false
+        }))
+        .unwrap();
+
+        // A replacement is confirmed once it reaches the exchange
+        let mut replaced = order.clone();
+        assert!(is_live_or_terminal(&replaced));
+        for status in ["accepted", "pending_replace", "pending_new"] {
+            replaced.status = status.to_string();
+            assert!(!is_live_or_terminal(&replaced));
+        }
+
+        // Already final, returns without polling
+        let mut filled = order.clone();
+        filled.status = "filled".to_string();
+        let done = wait_until(
+            None,
+            AccountType::Paper,
+            filled,
+            Duration::ZERO,
+            is_terminal,
+        );
+        assert_eq!(done.unwrap().status, "filled");
+
+        let err = wait_until(None, AccountType::Paper, order, Duration::ZERO, is_terminal);
+        assert!(matches!(err, Err(WaitError::Timeout(order)) if order.status == "new"));
+    }
+
+    #[test]
+    fn test_send_and_wait_fill() {
+        let order =
+            CreateOrderQuery::new("AAPL", OrderSide::Buy, OrderType::Market, TimeInForce::Day)
+                .qty("1")
+                .send_and_wait_fill(AccountType::Paper, Duration::from_secs(10));
+        dbg!(&order);
+    }
+}
@@ -0,0 +1,356 @@
+use super::{
+    AccountType,
+    positions::{AllPositions, PositionsQuery},
+};
+use crate::stream::{
+    MarketDataMessage, StreamError, StreamQuote, StreamTrade, TradeUpdate, TradeUpdateStream,
+    TradeUpdateSubscription,
+};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+/// Quantities below this are treated as flat, to absorb float error from fractional fills.
+const QTY_EPSILON: f64 = 1e-9;
+
+/// A position as tracked by the [`PositionBook`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BookPosition {
+    /// As the streams expect it, e.g. `BTC/USD` for crypto.
+    pub symbol: String,
+    /// Signed, negative for short positions.
+    pub qty: f64,
+    pub avg_entry_price: f64,
+    /// 100 for options, 1 otherwise.
+    pub multiplier: f64,
+    /// Latest trade price or quote midpoint.
+    pub mark: Option<f64>,
+    pub realized_pl: f64,
+}
+
+impl BookPosition {
+    fn new(symbol: String, multiplier: f64) -> Self {
+        Self {
+            symbol,
+            qty: 0.0,
+            avg_entry_price: 0.0,
+            multiplier,
+            mark: None,
+            realized_pl: 0.0,
+        }
+    }
+
+    pub fn is_flat(&self) -> bool {
+        self.qty.abs() < QTY_EPSILON
+    }
+
+    pub fn cost_basis(&self) -> f64 {
+        self.qty * self.avg_entry_price * self.multiplier
+    }
+
+    pub fn market_value(&self) -> Option<f64> {
+        Some(self.qty * self.mark? * self.multiplier)
+    }
+
+    /// `None` until the position has a mark.
+    pub fn unrealized_pl(&self) -> Option<f64> {
+        Some((self.mark? - self.avg_entry_price) * self.qty * self.multiplier)
+    }
+
+    /// Apply a signed fill, realizing P&L on the part that reduces the position.
+    fn fill(&mut self, signed_qty: f64, price: f64) {
+        let reducing = self.qty * signed_qty < 0.0;
+        if reducing {
+            let closed = signed_qty.abs().min(self.qty.abs());
+            self.realized_pl +=
+                (price - self.avg_entry_price) * closed * self.qty.signum() * self.multiplier;
+        }
+
+        let new_qty = self.qty + signed_qty;
+        if new_qty.abs() < QTY_EPSILON {
+            self.qty = 0.0;
+            self.avg_entry_price = 0.0;
+        } else if !reducing {
+            self.avg_entry_price = (self.avg_entry_price * self.qty + price * signed_qty) / new_qty;
+            self.qty = new_qty;
+        } else if new_qty.signum() != self.qty.signum() {
+            // Flipped from long to short or back, the remainder opens at the fill price
+            self.avg_entry_price = price;
+            self.qty = new_qty;
+        } else {
+            self.qty = new_qty;
+        }
+    }
+}
+
+/// Totals across the book.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BookTotals {
+    pub realized_pl: f64,
+    /// Positions without a mark are left out.
+    pub unrealized_pl: f64,
+    pub market_value: f64,
+}
+
+/// The book's key, crypto symbols without the slash.
+fn normalize(symbol: &str) -> String {
+    symbol.replace('/', "")
+}
+
+/// Crypto pairs from the positions API, which omits the slash, as the streams expect them.
+fn stream_symbol(symbol: &str, asset_class: &str) -> String {
+    if asset_class == "crypto" && !symbol.contains('/') {
+        for quote in ["USDT", "USDC", "USD", "BTC"] {
+            if let Some(base) = symbol.strip_suffix(quote)
+                && !base.is_empty()
+            {
+                return format!("{}/{}", base, quote);
+            }
+        }
+    }
+    symbol.to_string()
+}
+
+fn multiplier(asset_class: Option<&str>) -> f64 {
+    match asset_class {
+        Some("us_option") => 100.0,
+        _ => 1.0,
+    }
+}
+
+/// Live positions and P&L, seeded from open positions, updated from fills and marked to market from stream data.
+///
+/// Cloning is cheap and every clone shares the same book, so trade updates and market data can be applied
+/// from different threads.
+#[derive(Debug, Clone, Default)]
+pub struct PositionBook {
+    positions: Arc<RwLock<BTreeMap<String, BookPosition>>>,
+}
+
+impl PositionBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Seed from the open positions. Fills between the snapshot and a later [`listen`](PositionBook::listen)
+    /// are missed, use [`seed_subscribed`](PositionBook::seed_subscribed) to keep the book current from the start.
+    pub fn seed(account_type: AccountType) -> Result<Self, ureq::Error> {
+        let positions = PositionsQuery::new(account_type).get_all_open_positions()?;
+        Ok(Self::from_positions(&positions))
+    }
+
+    /// Seed from the open positions once `subscription` is connected, so fills that arrive while the
+    /// snapshot loads wait in the subscription. Apply them with [`listen_on`](PositionBook::listen_on);
+    /// a fill already in the snapshot is reconciled through its `position_qty`.
+    pub fn seed_subscribed(
+        account_type: AccountType,
+        _subscription: &TradeUpdateSubscription,
+    ) -> Result<Self, ureq::Error> {
+        Self::seed(account_type)
+    }
+
+    pub fn from_positions(positions: &AllPositions) -> Self {
+        let book = Self::new();
+        {
+            let mut book = book.write();
+            for position in positions {
+                let mut qty: f64 = position.qty.parse().unwrap_or(0.0);
+                if position.side == "short" && qty > 0.0 {
+                    qty = -qty;
+                }
+                let mut entry = BookPosition::new(
+                    stream_symbol(&position.symbol, &position.asset_class),
+                    multiplier(Some(&position.asset_class)),
+                );
+                entry.qty = qty;
+                entry.avg_entry_price = position.avg_entry_price.parse().unwrap_or(0.0);
+                entry.mark = position.current_price.parse().ok();
+                book.insert(normalize(&position.symbol), entry);
+            }
+        }
+        book
+    }
+
+    /// Apply a `fill` or `partial_fill` trade update, other events are ignored.
+    /// The quantity is reconciled with the update's `position_qty` when present.
+    pub fn apply_fill(&self, update: &TradeUpdate) {
+        if !matches!(update.event.as_str(), "fill" | "partial_fill") {
+            return;
+        }
+        let number = |value: &Option<String>| -> Option<f64> { value.as_deref()?.parse().ok() };
+        let (Some(price), Some(qty)) = (number(&update.price), number(&update.qty)) else {
+            return;
+        };
+        let signed_qty = match update.order.side.as_str() {
+            "sell" => -qty,
+            _ => qty,
+        };
+
+        let symbol = &update.order.symbol;
+        let mut book = self.write();
+        let position = book.entry(normalize(symbol)).or_insert_with(|| {
+            BookPosition::new(
+                symbol.clone(),
+                multiplier(update.order.asset_class.as_deref()),
+            )
+        });
+        position.fill(signed_qty, price);
+
+        if let Some(position_qty) = number(&update.position_qty)
+            && (position_qty - position.qty).abs() > QTY_EPSILON
+        {
+            position.qty = position_qty;
+            if position.is_flat() {
+                position.avg_entry_price = 0.0;
+            }
+        }
+    }
+
+    /// Mark a symbol at a price, for positions in the book only.
+    pub fn mark(&self, symbol: &str, price: f64) {
+        if let Some(position) = self.write().get_mut(&normalize(symbol)) {
+            position.mark = Some(price);
+        }
+    }
+
+    pub fn on_trade(&self, trade: &StreamTrade) {
+        self.mark(&trade.symbol, trade.p);
+    }
+
+    /// Marks at the quote midpoint when both sides are present.
+    pub fn on_quote(&self, quote: &StreamQuote) {
+        if quote.bp > 0.0 && quote.ap > 0.0 {
+            self.mark(&quote.symbol, (quote.bp + quote.ap) / 2.0);
+        }
+    }
+
+    /// Mark from a `StockStream` or `CryptoStream` trade, quote or bar close.
+    pub fn on_market_data(&self, message: &MarketDataMessage) {
+        match message {
+            MarketDataMessage::Trade(trade) => self.on_trade(trade),
+            MarketDataMessage::Quote(quote) => self.on_quote(quote),
+            MarketDataMessage::Bar(bar) | MarketDataMessage::UpdatedBar(bar) => {
+                self.mark(&bar.symbol, bar.c as f64)
+            }
+            MarketDataMessage::DailyBar(_) | MarketDataMessage::Status(_) => {}
+        }
+    }
+
+    /// Subscribe to trade updates and apply fills until the connection closes. Blocks the calling thread.
+    pub fn listen(&self, account_type: AccountType) -> Result<(), StreamError> {
+        self.listen_on(TradeUpdateStream::new(account_type).connect()?)
+    }
+
+    /// Apply the fills of an existing subscription until the connection closes. Blocks the calling thread.
+    pub fn listen_on(&self, subscription: TradeUpdateSubscription) -> Result<(), StreamError> {
+        subscription.start(|update| self.apply_fill(&update))
+    }
+
+    pub fn position(&self, symbol: &str) -> Option<BookPosition> {
+        self.read().get(&normalize(symbol)).cloned()
+    }
+
+    /// Every symbol traded or held, including flat positions with realized P&L, sorted by symbol.
+    pub fn positions(&self) -> Vec<BookPosition> {
+        self.read().values().cloned().collect()
+    }
+
+    pub fn open_positions(&self) -> Vec<BookPosition> {
+        self.read()
+            .values()
+            .filter(|p| !p.is_flat())
+            .cloned()
+            .collect()
+    }
+
+    /// Symbols with open positions, for subscribing a market data stream.
+    pub fn symbols(&self) -> Vec<String> {
+        self.open_positions()
+            .into_iter()
+            .map(|p| p.symbol)
+            .collect()
+    }
+
+    pub fn totals(&self) -> BookTotals {
+        let mut totals = BookTotals::default();
+        for position in self.read().values() {
+            totals.realized_pl += position.realized_pl;
+            totals.unrealized_pl += position.unrealized_pl().unwrap_or(0.0);
+            totals.market_value += position.market_value().unwrap_or(0.0);
+        }
+        totals
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, BTreeMap<String, BookPosition>> {
+        self.positions.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, BTreeMap<String, BookPosition>> {
+        self.positions.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(symbol: &str, side: &str, price: &str, qty: &str, position_qty: &str) -> TradeUpdate {
+        serde_json::from_value(serde_json::json!({
+            "event": "fill", "timestamp": "2025-03-20T14:00:00Z",
+            "price": price, "qty": qty, "position_qty": position_qty,
+            "order": {
+                "id": "o", "symbol": symbol, "asset_class": "us_equity", "order_type": "market",
+                "type": "market", "side": side, "status": "filled", "extended_hours": false
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_position_book_fills_and_marks() {
+        let positions: AllPositions = serde_json::from_value(serde_json::json!([{
+            "asset_id": "id", "symbol": "AAPL", "exchange": "NASDAQ", "asset_class": "us_equity",
+            "avg_entry_price": "100", "qty": "10", "qty_available": "10", "side": "long",
+            "market_value": "1050", "cost_basis": "1000", "unrealized_pl": "50", "unrealized_plpc": "0.05",
+            "unrealized_intraday_pl": "0", "unrealized_intraday_plpc": "0",
+            "current_price": "105", "lastday_price": "104", "change_today": "0"
+        }]))
+        .unwrap();
+        let book = PositionBook::from_positions(&positions);
+        assert_eq!(book.position("AAPL").unwrap().unrealized_pl(), Some(50.0));
+
+        book.apply_fill(&fill("AAPL", "buy", "110", "10", "20"));
+        let aapl = book.position("AAPL").unwrap();
+        assert_eq!((aapl.qty, aapl.avg_entry_price), (20.0, 105.0));
+
+        // Sell through zero: realize 25 shares worth, open 5 short at the fill price
+        book.apply_fill(&fill("AAPL", "sell", "120", "25", "-5"));
+        let aapl = book.position("AAPL").unwrap();
+        assert_eq!((aapl.qty, aapl.avg_entry_price), (-5.0, 120.0));
+        assert_eq!(aapl.realized_pl, 300.0);
+
+        book.on_trade(
+            &serde_json::from_str(
+                r#"{"T":"t","S":"AAPL","p":118,"s":1,"t":"2025-03-20T14:01:00Z"}"#,
+            )
+            .unwrap(),
+        );
+        assert_eq!(book.position("AAPL").unwrap().unrealized_pl(), Some(10.0));
+
+        // Crypto symbols match with or without the slash
+        book.apply_fill(&fill("BTC/USD", "buy", "60000", "0.5", "0.5"));
+        book.on_quote(&serde_json::from_str(r#"{"T":"q","S":"BTC/USD","bp":60990,"bs":1,"ap":61010,"as":1,"t":"2025-03-20T14:01:00Z"}"#).unwrap());
+        assert_eq!(
+            book.position("BTCUSD").unwrap().unrealized_pl(),
+            Some(500.0)
+        );
+
+        let totals = book.totals();
+        assert_eq!(totals.realized_pl, 300.0);
+        assert_eq!(totals.unrealized_pl, 510.0);
+        assert_eq!(book.symbols(), vec!["AAPL", "BTC/USD"]);
+        assert_eq!(stream_symbol("ETHUSDT", "crypto"), "ETH/USDT");
+        assert_eq!(stream_symbol("BTCUSD", "crypto"), "BTC/USD");
+    }
+}
@@ -1,5 +1,5 @@
 use super::{order::Order, AccountType};
-use crate::request;
+use crate::{dry_run, json_request, market_data::options::OptionSymbol, request};
 use serde::Deserialize;
 
 pub type AllPositions = Vec<Position>;
@@ -33,6 +33,34 @@ pub struct Position {
     pub change_today: String,
 }
 
+impl Position {
+    /// The parsed OCC symbol for options positions, `None` for other asset classes.
+    pub fn option_symbol(&self) -> Option<OptionSymbol> {
+        match self.asset_class.as_str() {
+            "us_option" => self.symbol.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// The market order that would close `qty` of the position, for dry-run mode.
+    fn dry_run_close(&self, qty: f64) -> Order {
+        let side = match self.side.as_str() {
+            "short" => "buy",
+            _ => "sell",
+        };
+        let mut order = Order::dry_run(&self.symbol, side, "market");
+        order.asset_id = Some(self.asset_id.clone());
+        order.asset_class = Some(self.asset_class.clone());
+        order.qty = Some(qty.to_string());
+        order.time_in_force = Some("day".to_string());
+        order
+    }
+
+    fn abs_qty(&self) -> f64 {
+        self.qty.parse::<f64>().unwrap_or(0.0).abs()
+    }
+}
+
 pub struct PositionsQuery<'a> {
     url: &'a str,
 }
@@ -76,6 +104,17 @@ impl<'a> PositionsQuery<'a> {
     ) -> Result<AllClosedPositions, ureq::Error> {
         let query = format!("?cancel_orders={}", cancel_orders);
         let route = format!("{}{}", self.url, query);
+        if dry_run::intercept("DELETE", &route) {
+            let positions = self.get_all_open_positions()?;
+            return Ok(positions
+                .iter()
+                .map(|position| ClosedPosition {
+                    symbol: position.symbol.clone(),
+                    status: 200,
+                    body: position.dry_run_close(position.abs_qty()),
+                })
+                .collect());
+        }
         let response = request("DELETE", &route).call()?;
 
         let status = response.status().as_u16();
@@ -101,11 +140,42 @@ impl<'a> PositionsQuery<'a> {
             query.push_str(&format!("&percentage={}", percentage));
         }
         let route = format!("{}?{}", url, query);
+        if dry_run::intercept("DELETE", &route) {
+            let position = self.get_position_by_symbol(id_or_symbol)?;
+            let close_qty = match (qty, percentage) {
+                (Some(qty), _) => qty as f64,
+                (None, Some(percentage)) => position.abs_qty() * percentage as f64 / 100.0,
+                (None, None) => position.abs_qty(),
+            };
+            return Ok(position.dry_run_close(close_qty));
+        }
         let response = request("DELETE", &route).call()?;
         let position = response.into_body().read_json()?;
 
         Ok(position)
     }
+
+    /// Exercise a held option contract. The position is settled and reflected in activities as `OPEXC`.
+    pub fn exercise(&self, symbol_or_contract_id: &'a str) -> Result<(), ureq::Error> {
+        let route = format!("{}/{}/exercise", self.url, symbol_or_contract_id);
+        if dry_run::intercept("POST", &route) {
+            return Ok(());
+        }
+        json_request("POST", &route).send_empty()?;
+
+        Ok(())
+    }
+
+    /// Instruct that a held option contract is not exercised at expiry, even when in the money.
+    pub fn do_not_exercise(&self, symbol_or_contract_id: &'a str) -> Result<(), ureq::Error> {
+        let route = format!("{}/{}/do-not-exercise", self.url, symbol_or_contract_id);
+        if dry_run::intercept("POST", &route) {
+            return Ok(());
+        }
+        json_request("POST", &route).send_empty()?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
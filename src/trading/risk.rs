@@ -0,0 +1,682 @@
+use super::{
+    AccountType,
+    account::{Account, get_account},
+    order::{CreateOrderQuery, DeleteOrderResult, OrderLeg, delete_all_orders},
+    position_book::PositionBook,
+    positions::{AllClosedPositions, PositionsQuery},
+};
+use crate::{market_data::options::OptionSymbol, stream::TradeUpdateSubscription};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Limits enforced by a [`RiskManager`]. Every limit is off until set.
+#[derive(Debug, Clone, Default)]
+pub struct RiskLimits {
+    max_order_notional: Option<f64>,
+    max_position_value: Option<f64>,
+    position_limits: HashMap<String, f64>,
+    max_gross_exposure: Option<f64>,
+    max_orders_per_minute: Option<usize>,
+    allowed_symbols: Option<HashSet<String>>,
+    max_daily_loss: Option<f64>,
+}
+
+impl RiskLimits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Largest dollar value of a single order.
+    pub fn max_order_notional(mut self, max_order_notional: f64) -> Self {
+        self.max_order_notional = Some(max_order_notional);
+        self
+    }
+
+    /// Largest absolute dollar value of the position in any one symbol after the order.
+    pub fn max_position_value(mut self, max_position_value: f64) -> Self {
+        self.max_position_value = Some(max_position_value);
+        self
+    }
+
+    /// Override `max_position_value` for one symbol.
+    pub fn position_limit(mut self, symbol: &str, max_position_value: f64) -> Self {
+        self.position_limits
+            .insert(normalize(symbol), max_position_value);
+        self
+    }
+
+    /// Largest sum of absolute position values after the order, as a multiple of `Account.equity`.
+    pub fn max_gross_exposure(mut self, multiple_of_equity: f64) -> Self {
+        self.max_gross_exposure = Some(multiple_of_equity);
+        self
+    }
+
+    pub fn max_orders_per_minute(mut self, max_orders_per_minute: usize) -> Self {
+        self.max_orders_per_minute = Some(max_orders_per_minute);
+        self
+    }
+
+    /// Only these symbols can be traded. For multi-leg orders every leg must be allowed.
+    pub fn allowed_symbols(mut self, symbols: &[&str]) -> Self {
+        self.allowed_symbols = Some(symbols.iter().map(|s| normalize(s)).collect());
+        self
+    }
+
+    /// Largest drop of `equity` below `last_equity` (the previous close) before new risk is refused, in dollars.
+    pub fn max_daily_loss(mut self, max_daily_loss: f64) -> Self {
+        self.max_daily_loss = Some(max_daily_loss);
+        self
+    }
+
+    fn position_limit_for(&self, symbol: &str) -> Option<f64> {
+        self.position_limits
+            .get(symbol)
+            .copied()
+            .or(self.max_position_value)
+    }
+}
+
+/// Why an order was refused by the [`RiskManager`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum RiskViolation {
+    /// The kill switch is engaged, nothing is sent until it is reset.
+    KillSwitch,
+    SymbolNotAllowed(String),
+    /// No limit, stop or reference price to value the order with.
+    MissingPrice(String),
+    OrderNotional {
+        notional: f64,
+        limit: f64,
+    },
+    PositionValue {
+        symbol: String,
+        value: f64,
+        limit: f64,
+    },
+    GrossExposure {
+        exposure: f64,
+        limit: f64,
+    },
+    RateLimit {
+        limit: usize,
+    },
+    DailyLoss {
+        loss: f64,
+        limit: f64,
+    },
+}
+
+impl fmt::Display for RiskViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RiskViolation::KillSwitch => write!(f, "kill switch is engaged"),
+            RiskViolation::SymbolNotAllowed(symbol) => write!(f, "{} is not allowed", symbol),
+            RiskViolation::MissingPrice(symbol) => {
+                write!(f, "no price to value the order for {} with", symbol)
+            }
+            RiskViolation::OrderNotional { notional, limit } => write!(
+                f,
+                "order notional {:.2} exceeds limit {:.2}",
+                notional, limit
+            ),
+            RiskViolation::PositionValue {
+                symbol,
+                value,
+                limit,
+            } => write!(
+                f,
+                "{} position value {:.2} would exceed limit {:.2}",
+                symbol, value, limit
+            ),
+            RiskViolation::GrossExposure { exposure, limit } => write!(
+                f,
+                "gross exposure {:.2} would exceed limit {:.2}",
+                exposure, limit
+            ),
+            RiskViolation::RateLimit { limit } => {
+                write!(f, "more than {} orders in the last minute", limit)
+            }
+            RiskViolation::DailyLoss { loss, limit } => {
+                write!(f, "daily loss {:.2} exceeds limit {:.2}", loss, limit)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RiskViolation {}
+
+/// What the kill switch canceled and closed.
+#[derive(Debug)]
+pub struct KillSwitchResult {
+    pub canceled_orders: Vec<DeleteOrderResult>,
+    pub closed_positions: AllClosedPositions,
+}
+
+#[derive(Debug, Default)]
+struct RiskState {
+    equity: f64,
+    last_equity: f64,
+    prices: HashMap<String, f64>,
+    sent: VecDeque<Instant>,
+    killed: bool,
+}
+
+/// An order's effect on one symbol's position.
+struct Exposure {
+    symbol: String,
+    held: f64,
+    new_qty: f64,
+    price: Option<f64>,
+    multiplier: f64,
+}
+
+impl Exposure {
+    fn reduces(&self) -> bool {
+        self.new_qty.abs() <= self.held.abs() && self.held * self.new_qty >= 0.0
+    }
+
+    fn value(&self) -> Option<f64> {
+        Some((self.new_qty * self.price? * self.multiplier).abs())
+    }
+}
+
+fn record(state: &mut RiskState) -> Instant {
+    while state
+        .sent
+        .front()
+        .is_some_and(|sent| sent.elapsed() >= RATE_WINDOW)
+    {
+        state.sent.pop_front();
+    }
+    let now = Instant::now();
+    state.sent.push_back(now);
+    now
+}
+
+fn normalize(symbol: &str) -> String {
+    symbol.replace('/', "")
+}
+
+fn multiplier(symbol: &str) -> f64 {
+    match symbol.parse::<OptionSymbol>() {
+        Ok(_) => 100.0,
+        Err(_) => 1.0,
+    }
+}
+
+fn number(value: Option<&str>) -> Option<f64> {
+    value?.parse().ok()
+}
+
+/// Pre-trade checks for every order sent with [`CreateOrderQuery::risk_manager`].
+///
+/// Positions and marks come from a [`PositionBook`], equity from the account. Orders that only reduce an
+/// existing position skip the notional, position, exposure and daily loss checks, so a strategy can always get flat.
+///
+/// Cloning is cheap and every clone shares the same state, including the kill switch.
+#[derive(Debug, Clone)]
+pub struct RiskManager {
+    limits: Arc<RiskLimits>,
+    book: PositionBook,
+    state: Arc<Mutex<RiskState>>,
+}
+
+impl RiskManager {
+    /// A manager with an empty position book and zero equity. Use `load` to start from the account.
+    pub fn new(limits: RiskLimits) -> Self {
+        Self {
+            limits: Arc::new(limits),
+            book: PositionBook::new(),
+            state: Arc::default(),
+        }
+    }
+
+    /// Fetch the account and open positions. Keep the book current with `book().listen`, or use
+    /// [`load_subscribed`](RiskManager::load_subscribed) to miss no fill in between.
+    pub fn load(limits: RiskLimits, account_type: AccountType) -> Result<Self, ureq::Error> {
+        let manager = Self::new(limits).position_book(PositionBook::seed(account_type)?);
+        manager.refresh_account(account_type)?;
+        Ok(manager)
+    }
+
+    /// Fetch the account and open positions once `subscription` is connected, see
+    /// [`PositionBook::seed_subscribed`]. Apply its fills with `book().listen_on`.
+    pub fn load_subscribed(
+        limits: RiskLimits,
+        account_type: AccountType,
+        subscription: &TradeUpdateSubscription,
+    ) -> Result<Self, ureq::Error> {
+        let book = PositionBook::seed_subscribed(account_type, subscription)?;
+        let manager = Self::new(limits).position_book(book);
+        manager.refresh_account(account_type)?;
+        Ok(manager)
+    }
+
+    /// Share a position book that is kept current elsewhere, e.g. with `PositionBook::listen`.
+    pub fn position_book(mut self, book: PositionBook) -> Self {
+        self.book = book;
+        self
+    }
+
+    pub fn book(&self) -> &PositionBook {
+        &self.book
+    }
+
+    /// Fetch `equity` and `last_equity`. Call periodically, the daily loss and exposure limits use the last values.
+    pub fn refresh_account(&self, account_type: AccountType) -> Result<(), ureq::Error> {
+        self.update_account(&get_account(account_type)?);
+        Ok(())
+    }
+
+    pub fn update_account(&self, account: &Account) {
+        let mut state = self.state();
+        state.equity = account.equity.parse().unwrap_or(0.0);
+        state.last_equity = account.last_equity.parse().unwrap_or(0.0);
+    }
+
+    /// Price used to value market orders in symbols the position book has no mark for.
+    pub fn set_price(&self, symbol: &str, price: f64) {
+        self.state().prices.insert(normalize(symbol), price);
+    }
+
+    /// `last_equity - equity`, positive when down on the day.
+    pub fn daily_loss(&self) -> f64 {
+        let state = self.state();
+        state.last_equity - state.equity
+    }
+
+    /// Check an order against the limits without counting it towards the rate limit.
+    pub fn check(&self, query: &CreateOrderQuery) -> Result<(), RiskViolation> {
+        self.check_with(query, &self.state())
+    }
+
+    /// Check an order and, when it passes, count it towards the rate limit, for orders sent some other
+    /// way than [`CreateOrderQuery::risk_manager`]. The order counts even if it then fails to send.
+    pub fn approve(&self, query: &CreateOrderQuery) -> Result<(), RiskViolation> {
+        self.reserve(query).map(|_| ())
+    }
+
+    /// Check an order and count it towards the rate limit under the same lock, so concurrent senders
+    /// can't all pass the limit. Returns the slot to [`release`](RiskManager::release) if the send fails.
+    pub(crate) fn reserve(&self, query: &CreateOrderQuery) -> Result<Instant, RiskViolation> {
+        let mut state = self.state();
+        self.check_with(query, &state)?;
+        Ok(record(&mut state))
+    }
+
+    /// Give back a slot taken by `reserve` for an order that was not sent.
+    pub(crate) fn release(&self, reserved: Instant) {
+        let mut state = self.state();
+        if let Some(index) = state.sent.iter().rposition(|sent| *sent == reserved) {
+            state.sent.remove(index);
+        }
+    }
+
+    /// Refuse every order from now on.
+    pub fn engage_kill_switch(&self) {
+        self.state().killed = true;
+    }
+
+    pub fn reset_kill_switch(&self) {
+        self.state().killed = false;
+    }
+
+    pub fn is_killed(&self) -> bool {
+        self.state().killed
+    }
+
+    /// Engage the kill switch, cancel all open orders and close all positions.
+    pub fn kill(&self, account_type: AccountType) -> Result<KillSwitchResult, ureq::Error> {
+        self.engage_kill_switch();
+        let canceled_orders = delete_all_orders(account_type)?;
+        let closed_positions = PositionsQuery::new(account_type).close_all_positions(true)?;
+        Ok(KillSwitchResult {
+            canceled_orders,
+            closed_positions,
+        })
+    }
+
+    fn check_with(&self, query: &CreateOrderQuery, state: &RiskState) -> Result<(), RiskViolation> {
+        let limits = &self.limits;
+        if state.killed {
+            return Err(RiskViolation::KillSwitch);
+        }
+
+        let symbols: Vec<&str> = match &query.legs {
+            Some(legs) if query.symbol.is_empty() => legs.iter().map(|leg| leg.symbol).collect(),
+            _ => vec![query.symbol],
+        };
+        if let Some(allowed) = &limits.allowed_symbols
+            && let Some(symbol) = symbols.iter().find(|s| !allowed.contains(&normalize(s)))
+        {
+            return Err(RiskViolation::SymbolNotAllowed(symbol.to_string()));
+        }
+
+        if let Some(limit) = limits.max_orders_per_minute {
+            let recent = state
+                .sent
+                .iter()
+                .filter(|sent| sent.elapsed() < RATE_WINDOW)
+                .count();
+            if recent >= limit {
+                return Err(RiskViolation::RateLimit { limit });
+            }
+        }
+
+        let (exposures, notional) = match &query.legs {
+            Some(legs) if query.symbol.is_empty() => {
+                self.multi_leg_exposures(query, legs, state)?
+            }
+            _ => self.exposure(query, state)?,
+        };
+        if exposures.iter().all(Exposure::reduces) {
+            return Ok(());
+        }
+
+        if let Some(limit) = limits.max_order_notional
+            && notional > limit
+        {
+            return Err(RiskViolation::OrderNotional { notional, limit });
+        }
+
+        if let Some(limit) = limits.max_daily_loss {
+            let loss = state.last_equity - state.equity;
+            if loss > limit {
+                return Err(RiskViolation::DailyLoss { loss, limit });
+            }
+        }
+
+        let mut values = 0.0;
+        for exposure in &exposures {
+            let needs_value = limits.max_gross_exposure.is_some()
+                || limits.position_limit_for(&exposure.symbol).is_some();
+            let value = match exposure.value() {
+                Some(value) => value,
+                None if !needs_value => continue,
+                None => return Err(RiskViolation::MissingPrice(exposure.symbol.clone())),
+            };
+            values += value;
+            if let Some(limit) = limits.position_limit_for(&exposure.symbol)
+                && value > limit
+            {
+                return Err(RiskViolation::PositionValue {
+                    symbol: exposure.symbol.clone(),
+                    value,
+                    limit,
+                });
+            }
+        }
+
+        if let Some(multiple) = limits.max_gross_exposure {
+            let others: f64 = self
+                .book
+                .open_positions()
+                .iter()
+                .filter(|p| !exposures.iter().any(|e| e.symbol == normalize(&p.symbol)))
+                .filter_map(|p| p.market_value())
+                .map(f64::abs)
+                .sum();
+            let exposure = others + values;
+            let limit = multiple * state.equity;
+            if exposure > limit {
+                return Err(RiskViolation::GrossExposure { exposure, limit });
+            }
+        }
+        Ok(())
+    }
+
+    /// A single-leg order's effect on its symbol, and its notional.
+    fn exposure(
+        &self,
+        query: &CreateOrderQuery,
+        state: &RiskState,
+    ) -> Result<(Vec<Exposure>, f64), RiskViolation> {
+        let limits = &self.limits;
+        let symbol = normalize(query.symbol);
+        let needs_value = limits.max_order_notional.is_some()
+            || limits.max_gross_exposure.is_some()
+            || limits.position_limit_for(&symbol).is_some();
+        let position = self.book.position(&symbol);
+        let sign = if query.side == "sell" { -1.0 } else { 1.0 };
+
+        let price = number(query.limit_price)
+            .or(number(query.stop_price))
+            .or(position.as_ref().and_then(|p| p.mark))
+            .or(state.prices.get(&symbol).copied());
+        let multiplier = position
+            .as_ref()
+            .map_or_else(|| multiplier(&symbol), |p| p.multiplier);
+
+        let (order_qty, notional) = match (number(query.qty), number(query.notional), price) {
+            (Some(qty), _, Some(price)) => (qty, qty * price * multiplier),
+            (None, Some(notional), Some(price)) => (notional / (price * multiplier), notional),
+            (Some(qty), _, None) if !needs_value => (qty, 0.0),
+            // Unknown size, treated as adding risk
+            _ if !needs_value => (f64::INFINITY, 0.0),
+            _ => return Err(RiskViolation::MissingPrice(query.symbol.to_string())),
+        };
+
+        let held = position.as_ref().map_or(0.0, |p| p.qty);
+        let exposure = Exposure {
+            symbol,
+            held,
+            new_qty: held + sign * order_qty,
+            price,
+            multiplier,
+        };
+        Ok((vec![exposure], notional))
+    }
+
+    /// Each leg's effect on its symbol, trading `ratio_qty` contracts per strategy unit, and the order's
+    /// notional at its net limit price, which is quoted per unit like a single contract.
+    fn multi_leg_exposures(
+        &self,
+        query: &CreateOrderQuery,
+        legs: &[OrderLeg],
+        state: &RiskState,
+    ) -> Result<(Vec<Exposure>, f64), RiskViolation> {
+        // Unknown size, treated as adding risk
+        let units = number(query.qty).unwrap_or(f64::INFINITY);
+        let mut exposures = Vec::with_capacity(legs.len());
+        let mut contract_multiplier: f64 = 1.0;
+        for leg in legs {
+            let symbol = normalize(leg.symbol);
+            let position = self.book.position(&symbol);
+            let multiplier = position
+                .as_ref()
+                .map_or_else(|| multiplier(&symbol), |p| p.multiplier);
+            let ratio = number(Some(leg.ratio_qty)).unwrap_or(1.0);
+            let sign = if leg.side == "sell" { -1.0 } else { 1.0 };
+            let held = position.as_ref().map_or(0.0, |p| p.qty);
+            let price = position
+                .as_ref()
+                .and_then(|p| p.mark)
+                .or(state.prices.get(&symbol).copied());
+            exposures.push(Exposure {
+                symbol,
+                held,
+                new_qty: held + sign * units * ratio,
+                price,
+                multiplier,
+            });
+            contract_multiplier = contract_multiplier.max(multiplier);
+        }
+
+        if self.limits.max_order_notional.is_none() {
+            return Ok((exposures, 0.0));
+        }
+        match number(query.limit_price) {
+            Some(price) => Ok((exposures, units * price.abs() * contract_multiplier)),
+            None => Err(RiskViolation::MissingPrice(
+                legs.iter()
+                    .map(|leg| leg.symbol)
+                    .collect::<Vec<_>>()
+                    .join(","),
+            )),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, RiskState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::{
+        order::{OrderLeg, OrderSide, OrderType, TimeInForce},
+        positions::AllPositions,
+    };
+
+    fn order<'a>(symbol: &'a str, side: OrderSide, qty: &'a str) -> CreateOrderQuery<'a> {
+        CreateOrderQuery::new(symbol, side, OrderType::Market, TimeInForce::Day).qty(qty)
+    }
+
+    #[test]
+    fn test_risk_manager_limits() {
+        let positions: AllPositions = serde_json::from_value(serde_json::json!([{
+            "asset_id": "id", "symbol": "AAPL", "exchange": "NASDAQ", "asset_class": "us_equity",
+            "avg_entry_price": "100", "qty": "50", "qty_available": "50", "side": "long",
+            "market_value": "5000", "cost_basis": "5000", "unrealized_pl": "0", "unrealized_plpc": "0",
+            "unrealized_intraday_pl": "0", "unrealized_intraday_plpc": "0",
+            "current_price": "100", "lastday_price": "100", "change_today": "0"
+        }]))
+        .unwrap();
+        let limits = RiskLimits::new()
+            .max_order_notional(3_000.0)
+            .max_position_value(6_000.0)
+            .max_gross_exposure(0.7)
+            .max_orders_per_minute(3)
+            .allowed_symbols(&["AAPL", "MSFT", "BTC/USD"])
+            .max_daily_loss(500.0);
+        let risk = RiskManager::new(limits).position_book(PositionBook::from_positions(&positions));
+        {
+            let mut state = risk.state();
+            state.equity = 10_000.0;
+            state.last_equity = 10_000.0;
+        }
+
+        assert_eq!(
+            risk.check(&order("TSLA", OrderSide::Buy, "1")),
+            Err(RiskViolation::SymbolNotAllowed("TSLA".to_string()))
+        );
+        assert_eq!(
+            risk.check(&order("MSFT", OrderSide::Buy, "1")),
+            Err(RiskViolation::MissingPrice("MSFT".to_string()))
+        );
+        risk.set_price("MSFT", 400.0);
+        assert!(risk.check(&order("MSFT", OrderSide::Buy, "5")).is_ok());
+        assert!(matches!(
+            risk.check(&order("MSFT", OrderSide::Buy, "10")),
+            Err(RiskViolation::OrderNotional { notional, .. }) if notional == 4_000.0
+        ));
+        assert!(matches!(
+            risk.check(&order("AAPL", OrderSide::Buy, "20")),
+            Err(RiskViolation::PositionValue { value, .. }) if value == 7_000.0
+        ));
+        // 5000 in AAPL plus 2400 in MSFT is over 0.7x equity
+        assert!(matches!(
+            risk.check(&order("MSFT", OrderSide::Buy, "6")),
+            Err(RiskViolation::GrossExposure { exposure, limit }) if exposure == 7_400.0 && limit == 7_000.0
+        ));
+        risk.set_price("BTCUSD", 60_000.0);
+        let btc = CreateOrderQuery::new(
+            "BTC/USD",
+            OrderSide::Buy,
+            OrderType::Market,
+            TimeInForce::GoodTilCanceled,
+        )
+        .notional("2000");
+        assert!(risk.check(&btc).is_ok());
+
+        // Down more than the daily loss limit: new risk is refused, reducing AAPL is not
+        risk.state().equity = 9_000.0;
+        assert!(matches!(
+            risk.check(&order("MSFT", OrderSide::Buy, "1")),
+            Err(RiskViolation::DailyLoss { loss, .. }) if loss == 1_000.0
+        ));
+        assert!(risk.check(&order("AAPL", OrderSide::Sell, "50")).is_ok());
+        assert!(risk.check(&order("AAPL", OrderSide::Sell, "51")).is_err());
+
+        for _ in 0..2 {
+            risk.approve(&order("AAPL", OrderSide::Sell, "1")).unwrap();
+        }
+        // A slot given back for a failed send is free again
+        let reserved = risk.reserve(&order("AAPL", OrderSide::Sell, "1")).unwrap();
+        assert!(risk.reserve(&order("AAPL", OrderSide::Sell, "1")).is_err());
+        risk.release(reserved);
+        risk.reserve(&order("AAPL", OrderSide::Sell, "1")).unwrap();
+        assert_eq!(
+            risk.check(&order("AAPL", OrderSide::Sell, "1")),
+            Err(RiskViolation::RateLimit { limit: 3 })
+        );
+
+        risk.clone().engage_kill_switch();
+        assert_eq!(
+            risk.check(&order("AAPL", OrderSide::Sell, "1")),
+            Err(RiskViolation::KillSwitch)
+        );
+    }
+
+    #[test]
+    fn test_risk_manager_multi_leg() {
+        let limits = RiskLimits::new()
+            .max_order_notional(1_000.0)
+            .position_limit("MSFT250620C00400000", 2_000.0)
+            .max_daily_loss(500.0);
+        let risk = RiskManager::new(limits);
+        {
+            let mut state = risk.state();
+            state.equity = 10_000.0;
+            state.last_equity = 10_000.0;
+        }
+        let spread = |qty| {
+            CreateOrderQuery::multi_leg(
+                vec![
+                    OrderLeg::new("MSFT250620C00400000", OrderSide::Buy, "2"),
+                    OrderLeg::new("MSFT250620C00410000", OrderSide::Sell, "1"),
+                ],
+                OrderType::Limit,
+                TimeInForce::Day,
+            )
+            .qty(qty)
+        };
+
+        assert_eq!(
+            risk.check(&spread("1")),
+            Err(RiskViolation::MissingPrice(
+                "MSFT250620C00400000,MSFT250620C00410000".to_string()
+            ))
+        );
+        assert!(matches!(
+            risk.check(&spread("10").limit_price("1.50")),
+            Err(RiskViolation::OrderNotional { notional, .. }) if notional == 1_500.0
+        ));
+        assert_eq!(
+            risk.check(&spread("5").limit_price("1.50")),
+            Err(RiskViolation::MissingPrice(
+                "MSFT250620C00400000".to_string()
+            ))
+        );
+        // 5 units of 2 contracts at 3.00
+        risk.set_price("MSFT250620C00400000", 3.0);
+        assert!(matches!(
+            risk.check(&spread("5").limit_price("1.50")),
+            Err(RiskViolation::PositionValue { value, .. }) if value == 3_000.0
+        ));
+        assert!(risk.check(&spread("3").limit_price("1.50")).is_ok());
+
+        risk.state().equity = 9_000.0;
+        assert!(matches!(
+            risk.check(&spread("3").limit_price("1.50")),
+            Err(RiskViolation::DailyLoss { loss, .. }) if loss == 1_000.0
+        ));
+    }
+}
@@ -0,0 +1,275 @@
+use super::{AccountType, assets::Asset};
+use crate::{json_request, request};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Debug)]
+pub struct Watchlist {
+    pub id: String,
+    pub account_id: String,
+    pub name: String,
+    pub created_at: String,
+    pub updated_at: String,
+    /// Not included when listing all watchlists.
+    #[serde(default)]
+    pub assets: Vec<Asset>,
+}
+
+impl Watchlist {
+    pub fn symbols(&self) -> Vec<&str> {
+        self.assets.iter().map(|a| a.symbol.as_str()).collect()
+    }
+}
+
+pub type AllWatchlists = Vec<Watchlist>;
+
+fn watchlists_url(account_type: AccountType) -> &'static str {
+    match account_type {
+        AccountType::Live => "https://api.alpaca.markets/v2/watchlists",
+        AccountType::Paper => "https://paper-api.alpaca.markets/v2/watchlists",
+    }
+}
+
+/// A watchlist is addressed either by its id or by its unique name.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WatchlistRef<'a> {
+    Id(&'a str),
+    Name(&'a str),
+}
+
+impl WatchlistRef<'_> {
+    /// Route to the watchlist, with an optional path suffix such as a symbol.
+    fn route(&self, url: &str, suffix: Option<&str>) -> (String, Option<(&'static str, String)>) {
+        let suffix = suffix.map(|s| format!("/{}", s)).unwrap_or_default();
+        match self {
+            WatchlistRef::Id(id) => (format!("{}/{}{}", url, id, suffix), None),
+            WatchlistRef::Name(name) => (
+                format!("{}:by_name{}", url, suffix),
+                Some(("name", name.to_string())),
+            ),
+        }
+    }
+}
+
+pub struct WatchlistsQuery<'a> {
+    url: &'a str,
+}
+
+impl<'a> WatchlistsQuery<'a> {
+    pub fn new(account_type: AccountType) -> Self {
+        Self {
+            url: watchlists_url(account_type),
+        }
+    }
+
+    /// All watchlists of the account, without their assets.
+    pub fn get_all(&self) -> Result<AllWatchlists, ureq::Error> {
+        let response = request("GET", self.url).call()?;
+        let watchlists = response.into_body().read_json()?;
+
+        Ok(watchlists)
+    }
+
+    pub fn get_by_id(&self, id: &'a str) -> Result<Watchlist, ureq::Error> {
+        self.get(WatchlistRef::Id(id))
+    }
+
+    pub fn get_by_name(&self, name: &'a str) -> Result<Watchlist, ureq::Error> {
+        self.get(WatchlistRef::Name(name))
+    }
+
+    pub fn get(&self, watchlist: WatchlistRef<'a>) -> Result<Watchlist, ureq::Error> {
+        let (route, query) = watchlist.route(self.url, None);
+        let mut req = request("GET", &route);
+        if let Some((key, value)) = query {
+            req = req.query(key, value);
+        }
+        let response = req.call()?;
+        let watchlist = response.into_body().read_json()?;
+
+        Ok(watchlist)
+    }
+
+    /// Append a symbol to the watchlist, returning the updated watchlist.
+    pub fn add_asset(
+        &self,
+        watchlist: WatchlistRef<'a>,
+        symbol: &'a str,
+    ) -> Result<Watchlist, ureq::Error> {
+        let (route, query) = watchlist.route(self.url, None);
+        let mut req = json_request("POST", &route);
+        if let Some((key, value)) = query {
+            req = req.query(key, value);
+        }
+        let response = req
+            .header("Content-Type", "application/json")
+            .send_json(&AddAsset { symbol })?;
+        let watchlist = response.into_body().read_json()?;
+
+        Ok(watchlist)
+    }
+
+    /// Remove a symbol from the watchlist, returning the updated watchlist.
+    pub fn remove_asset(
+        &self,
+        watchlist: WatchlistRef<'a>,
+        symbol: &'a str,
+    ) -> Result<Watchlist, ureq::Error> {
+        let (route, query) = watchlist.route(self.url, Some(symbol));
+        let mut req = request("DELETE", &route);
+        if let Some((key, value)) = query {
+            req = req.query(key, value);
+        }
+        let response = req.call()?;
+        let watchlist = response.into_body().read_json()?;
+
+        Ok(watchlist)
+    }
+
+    pub fn delete(&self, watchlist: WatchlistRef<'a>) -> Result<(), ureq::Error> {
+        let (route, query) = watchlist.route(self.url, None);
+        let mut req = request("DELETE", &route);
+        if let Some((key, value)) = query {
+            req = req.query(key, value);
+        }
+        req.call()?;
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct AddAsset<'a> {
+    symbol: &'a str,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CreateWatchlistQuery<'a> {
+    pub name: &'a str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub symbols: Vec<&'a str>,
+}
+
+impl<'a> CreateWatchlistQuery<'a> {
+    /// Watchlist names must be unique within the account.
+    pub fn new(name: &'a str) -> Self {
+        Self {
+            name,
+            symbols: Vec::new(),
+        }
+    }
+
+    pub fn symbols(mut self, symbols: Vec<&'a str>) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    pub fn send(&self, account_type: AccountType) -> Result<Watchlist, ureq::Error> {
+        let response = json_request("POST", watchlists_url(account_type))
+            .header("Content-Type", "application/json")
+            .send_json(self)?;
+        let watchlist = response.into_body().read_json()?;
+
+        Ok(watchlist)
+    }
+}
+
+/// Rename a watchlist and/or replace its symbols.
+#[derive(Serialize, Debug)]
+pub struct UpdateWatchlistQuery<'a> {
+    #[serde(skip_serializing)]
+    pub watchlist: WatchlistRef<'a>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<&'a str>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbols: Option<Vec<&'a str>>,
+}
+
+impl<'a> UpdateWatchlistQuery<'a> {
+    pub fn new(watchlist: WatchlistRef<'a>) -> Self {
+        Self {
+            watchlist,
+            name: None,
+            symbols: None,
+        }
+    }
+
+    pub fn name(mut self, name: &'a str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Replaces the full list of symbols, in this order.
+    pub fn symbols(mut self, symbols: Vec<&'a str>) -> Self {
+        self.symbols = Some(symbols);
+        self
+    }
+
+    pub fn send(&self, account_type: AccountType) -> Result<Watchlist, ureq::Error> {
+        let (route, query) = self.watchlist.route(watchlists_url(account_type), None);
+        let mut req = json_request("PUT", &route);
+        if let Some((key, value)) = query {
+            req = req.query(key, value);
+        }
+        let response = req
+            .header("Content-Type", "application/json")
+            .send_json(self)?;
+        let watchlist = response.into_body().read_json()?;
+
+        Ok(watchlist)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watchlist_routes() {
+        let url = watchlists_url(AccountType::Paper);
+        assert_eq!(
+            WatchlistRef::Id("abc").route(url, Some("AAPL")),
+            (
+                "https://paper-api.alpaca.markets/v2/watchlists/abc/AAPL".to_string(),
+                None
+            )
+        );
+        assert_eq!(
+            WatchlistRef::Name("Tech Leaders").route(url, None),
+            (
+                "https://paper-api.alpaca.markets/v2/watchlists:by_name".to_string(),
+                Some(("name", "Tech Leaders".to_string()))
+            )
+        );
+
+        let update =
+            UpdateWatchlistQuery::new(WatchlistRef::Id("abc")).symbols(vec!["AAPL", "MSFT"]);
+        assert_eq!(
+            serde_json::to_string(&update).unwrap(),
+            r#"{"symbols":["AAPL","MSFT"]}"#
+        );
+    }
+
+    #[test]
+    fn test_watchlist_lifecycle() {
+        let created = CreateWatchlistQuery::new("alpaca_api_client_test")
+            .symbols(vec!["AAPL"])
+            .send(AccountType::Paper)
+            .unwrap();
+        let watchlists = WatchlistsQuery::new(AccountType::Paper);
+
+        let added = watchlists
+            .add_asset(WatchlistRef::Id(&created.id), "MSFT")
+            .unwrap();
+        assert_eq!(added.symbols(), vec!["AAPL", "MSFT"]);
+
+        let removed = watchlists
+            .remove_asset(WatchlistRef::Name("alpaca_api_client_test"), "AAPL")
+            .unwrap();
+        dbg!(&removed);
+        assert_eq!(removed.symbols(), vec!["MSFT"]);
+
+        watchlists.delete(WatchlistRef::Id(&created.id)).unwrap();
+    }
+}